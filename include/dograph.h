@@ -0,0 +1,40 @@
+/* C API of the dograph crate.  Build with
+ * `cargo rustc --release --features ffi --crate-type cdylib` and link against
+ * the resulting `libdograph` shared library. */
+#ifndef DOGRAPH_H
+#define DOGRAPH_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct DirectedAcyclicGraph DirectedAcyclicGraph;
+
+/* NULL if vertex_count is too big. */
+DirectedAcyclicGraph *dograph_new(size_t vertex_count);
+void dograph_free(DirectedAcyclicGraph *dag);
+
+size_t dograph_get_vertex_count(const DirectedAcyclicGraph *dag);
+
+/* 1 if the edge exists, 0 if it doesn't, -1 if (u, v) is not a valid edge. */
+int32_t dograph_get_edge(const DirectedAcyclicGraph *dag, size_t u, size_t v);
+/* 0 on success, -1 if (u, v) is not a valid edge. */
+int32_t dograph_set_edge(DirectedAcyclicGraph *dag, size_t u, size_t v, int32_t exists);
+
+void dograph_for_each_edge(const DirectedAcyclicGraph *dag,
+                           void (*callback)(size_t u, size_t v, void *user_data),
+                           void *user_data);
+
+/* Number of vertices written or -1 if output_len is too small or output is
+ * NULL.  A DAG without vertices never touches output, so NULL, 0 is fine. */
+ptrdiff_t dograph_topological_sort(const DirectedAcyclicGraph *dag, size_t *output,
+                                   size_t output_len);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -1,6 +1,14 @@
+use std::collections::{HashMap, VecDeque};
+
 use fixedbitset::FixedBitSet;
+#[cfg(feature = "num-bigint")]
+use num_bigint::BigUint;
+#[cfg(feature = "num-bigint")]
+use num_traits::{One, Zero};
 
-use crate::DirectedAcyclicGraph;
+use crate::incremental::IncrementalTopologicalOrder;
+use crate::traversal::{get_incoming_edges_count, get_vertices_without_incoming_edges};
+use crate::{CycleError, DirectedAcyclicGraph, DirectedGraph};
 
 /// Computes a mapping: vertex -> set of vertices that are descendants of vertex.
 pub fn get_descendants(dag: &DirectedAcyclicGraph) -> Vec<FixedBitSet> {
@@ -38,6 +46,42 @@ pub fn transitive_reduction(dag: &DirectedAcyclicGraph) -> DirectedAcyclicGraph
     result
 }
 
+/// Returns the [minimum equivalent
+/// graph](https://en.wikipedia.org/wiki/Transitive_reduction)
+/// of a DAG, i.e. a subgraph with the fewest edges that preserves reachability
+/// between all the pairs of vertices, along with the number of edges of `dag`
+/// that are missing from it because they're implied by other paths.
+///
+/// For DAGs (unlike for graphs with cycles) the minimum equivalent graph is
+/// unique and coincides with the [`transitive_reduction`].  The count of the
+/// removed edges is a measure of redundancy in e.g. dependency declarations.
+pub fn get_minimum_equivalent_graph(dag: &DirectedAcyclicGraph) -> (DirectedAcyclicGraph, usize) {
+    let reduction = transitive_reduction(dag);
+    let removed_edge_count = dag.iter_edges().count() - reduction.iter_edges().count();
+    (reduction, removed_edge_count)
+}
+
+/// Returns the edges `(u, v)` implied by some other path from `u` to `v`, i.e.
+/// the edges of `dag` missing from its [`transitive_reduction`], sorted.
+/// Useful for reporting redundant dependency declarations.
+pub fn get_redundant_edges(dag: &DirectedAcyclicGraph) -> Vec<(usize, usize)> {
+    let descendants = get_descendants(dag);
+    let mut reachable_through_children = FixedBitSet::with_capacity(dag.get_vertex_count());
+    let mut result: Vec<(usize, usize)> = Vec::new();
+    for u in 0..dag.get_vertex_count() {
+        reachable_through_children.clear();
+        for v in dag.iter_children(u) {
+            reachable_through_children.union_with(&descendants[v]);
+        }
+        result.extend(
+            dag.iter_children(u)
+                .filter(|&v| reachable_through_children[v])
+                .map(|v| (u, v)),
+        );
+    }
+    result
+}
+
 /// Returns a new DAG that is a [transitive
 /// closure](https://en.wikipedia.org/wiki/Transitive_closure) of a DAG.
 pub fn transitive_closure(dag: &DirectedAcyclicGraph) -> DirectedAcyclicGraph {
@@ -46,8 +90,8 @@ pub fn transitive_closure(dag: &DirectedAcyclicGraph) -> DirectedAcyclicGraph {
     // http://www.compsci.hunter.cuny.edu/~sweiss/course_materials/csci335/lecture_notes/chapter08.pdf
 
     let descendants = get_descendants(dag);
-    for u in 0..dag.get_vertex_count() {
-        for v in descendants[u].ones() {
+    for (u, u_descendants) in descendants.iter().enumerate() {
+        for v in u_descendants.ones() {
             result.set_edge(u, v, true);
         }
     }
@@ -55,6 +99,912 @@ pub fn transitive_closure(dag: &DirectedAcyclicGraph) -> DirectedAcyclicGraph {
     result
 }
 
+/// Same as [`transitive_closure`] but uses the [Method of Four
+/// Russians](https://en.wikipedia.org/wiki/Method_of_Four_Russians), which is
+/// faster for dense DAGs.
+///
+/// Vertices are split into blocks of 8 consecutive ones, processed from the
+/// last block to the first.  Once the closure rows of a block are final, the
+/// unions of all 256 subsets of them are tabulated, so that every vertex
+/// before the block takes in its descendants from the whole block with a
+/// single table lookup and one bit set union instead of up to 8.
+pub fn transitive_closure_four_russians(dag: &DirectedAcyclicGraph) -> DirectedAcyclicGraph {
+    const BLOCK_SIZE: usize = 8;
+    let vertex_count = dag.get_vertex_count();
+    let mut rows: Vec<FixedBitSet> = (0..vertex_count)
+        .map(|u| {
+            let mut row = FixedBitSet::with_capacity(vertex_count);
+            dag.union_children_into(u, &mut row);
+            row
+        })
+        .collect();
+    let mut table: Vec<FixedBitSet> =
+        vec![FixedBitSet::with_capacity(vertex_count); 1 << BLOCK_SIZE];
+    for start in (0..vertex_count).step_by(BLOCK_SIZE).rev() {
+        let end = (start + BLOCK_SIZE).min(vertex_count);
+        // Blocks after this one have already been accounted for, so only the
+        // paths within the block remain.
+        for u in (start..end).rev() {
+            for v in (u + 1)..end {
+                if rows[u][v] {
+                    let (before, after) = rows.split_at_mut(v);
+                    before[u].union_with(&after[0]);
+                }
+            }
+        }
+        for mask in 1..(1 << (end - start)) {
+            let lowest = (mask as u32).trailing_zeros() as usize;
+            let (previous, current) = table.split_at_mut(mask);
+            current[0].clone_from(&previous[mask & (mask - 1)]);
+            current[0].union_with(&rows[start + lowest]);
+        }
+        for row in &mut rows[..start] {
+            let mask = (start..end)
+                .filter(|v| row[*v])
+                .fold(0, |mask, v| mask | (1 << (v - start)));
+            if mask != 0 {
+                row.union_with(&table[mask]);
+            }
+        }
+    }
+    DirectedAcyclicGraph::from_edges_iter(
+        vertex_count,
+        rows.iter()
+            .enumerate()
+            .flat_map(|(u, row)| row.ones().map(move |v| (u, v))),
+    )
+}
+
+/// Same as [`transitive_closure`] but computes the closure rows of
+/// independent vertices concurrently with [rayon](https://docs.rs/rayon).
+///
+/// Vertices are grouped into layers by the length of the longest path
+/// starting at them.  All the children of a vertex lie in lower layers, so the
+/// rows of a single layer can be computed in parallel once the lower layers
+/// are done.
+#[cfg(feature = "rayon")]
+pub fn transitive_closure_parallel(dag: &DirectedAcyclicGraph) -> DirectedAcyclicGraph {
+    use rayon::prelude::*;
+
+    let vertex_count = dag.get_vertex_count();
+    let mut layers: Vec<Vec<usize>> = Vec::new();
+    for (u, height) in crate::paths::get_out_eccentricities(dag)
+        .into_iter()
+        .enumerate()
+    {
+        if layers.len() <= height {
+            layers.resize(height + 1, Vec::new());
+        }
+        layers[height].push(u);
+    }
+
+    let mut descendants: Vec<FixedBitSet> = vec![FixedBitSet::default(); vertex_count];
+    for layer in &layers {
+        let rows: Vec<FixedBitSet> = layer
+            .par_iter()
+            .map(|u| {
+                let mut row = FixedBitSet::with_capacity(vertex_count);
+                dag.union_children_into(*u, &mut row);
+                for v in dag.iter_children(*u) {
+                    row.union_with(&descendants[v]);
+                }
+                row
+            })
+            .collect();
+        for (u, row) in layer.iter().zip(rows) {
+            descendants[*u] = row;
+        }
+    }
+
+    DirectedAcyclicGraph::from_edges_iter(
+        vertex_count,
+        descendants
+            .iter()
+            .enumerate()
+            .flat_map(|(u, row)| row.ones().map(move |v| (u, v))),
+    )
+}
+
+/// Counts ordered pairs of vertices `(u, v)` such that there's a path from `u`
+/// to `v`, i.e. the number of edges in the [`transitive_closure`], without
+/// materializing the closure as a DAG.
+pub fn count_reachable_pairs(dag: &DirectedAcyclicGraph) -> usize {
+    get_descendants(dag)
+        .iter()
+        .map(|descendants| descendants.count_ones(..))
+        .sum()
+}
+
+/// See [`iter_reachable_pairs`].
+pub struct ReachablePairsIterator<'a> {
+    dag: &'a DirectedAcyclicGraph,
+    u: usize,
+    row: FixedBitSet,
+    // The ones of `row` not yet returned, in decreasing order.
+    descendants: Vec<usize>,
+    to_visit: Vec<usize>,
+}
+
+impl<'a> ReachablePairsIterator<'a> {
+    // Computes the closure row of `self.u` with a DFS.
+    fn fill_row(&mut self) {
+        self.row.clear();
+        self.to_visit.extend(self.dag.iter_children(self.u));
+        while let Some(v) = self.to_visit.pop() {
+            if self.row.put(v) {
+                continue;
+            }
+            self.to_visit
+                .extend(self.dag.iter_children(v).filter(|&w| !self.row[w]));
+        }
+        self.descendants.extend(self.row.ones());
+        self.descendants.reverse();
+    }
+}
+
+impl<'a> Iterator for ReachablePairsIterator<'a> {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let vertex_count = self.dag.get_vertex_count();
+        while self.u < vertex_count {
+            if let Some(v) = self.descendants.pop() {
+                return Some((self.u, v));
+            }
+            self.u += 1;
+            if self.u < vertex_count {
+                self.fill_row();
+            }
+        }
+        None
+    }
+}
+
+/// Iterates over the ordered pairs of vertices `(u, v)` such that there's a
+/// path from `u` to `v`, i.e. the edges of the [`transitive_closure`], sorted.
+/// Only one closure row is kept in memory at a time, computed when needed, at
+/// the cost of a search per vertex.
+pub fn iter_reachable_pairs(dag: &DirectedAcyclicGraph) -> ReachablePairsIterator<'_> {
+    let mut iterator = ReachablePairsIterator {
+        dag,
+        u: 0,
+        row: FixedBitSet::with_capacity(dag.get_vertex_count()),
+        descendants: Vec::new(),
+        to_visit: Vec::new(),
+    };
+    if dag.get_vertex_count() > 0 {
+        iterator.fill_row();
+    }
+    iterator
+}
+
+// paths[s][t] is the natural logarithm of the number of paths from s to t, or
+// -inf if there are none.  Only the ratios of the counts matter but the counts
+// themselves overflow even f64 on DAGs with about a thousand layers.
+fn get_log_path_counts_matrix(dag: &DirectedAcyclicGraph) -> Vec<Vec<f64>> {
+    let vertex_count = dag.get_vertex_count();
+    let mut paths: Vec<Vec<f64>> = vec![Vec::new(); vertex_count];
+    for s in (0..vertex_count).rev() {
+        let children: Vec<usize> = dag.iter_children(s).collect();
+        let mut s_paths = vec![f64::NEG_INFINITY; vertex_count];
+        s_paths[s] = 0.0;
+        for (t, s_t_paths) in s_paths.iter_mut().enumerate().skip(s + 1) {
+            let max = children
+                .iter()
+                .map(|c| paths[*c][t])
+                .fold(f64::NEG_INFINITY, f64::max);
+            if max == f64::NEG_INFINITY {
+                continue;
+            }
+            let sum: f64 = children.iter().map(|c| (paths[*c][t] - max).exp()).sum();
+            *s_t_paths = max + sum.ln();
+        }
+        paths[s] = s_paths;
+    }
+    paths
+}
+
+// A mapping: vertex v -> the sum over all vertices t reachable from v (t != v)
+// of the fraction of s -> t paths that pass through v, where `paths` is the
+// matrix from `get_log_path_counts_matrix`.  Computed backwards from the
+// children of v like the dependencies in Brandes' algorithm.  `children` is a
+// mapping: vertex -> its children.
+fn get_dependencies_on_source(children: &[Vec<usize>], paths: &[Vec<f64>], s: usize) -> Vec<f64> {
+    let mut dependencies: Vec<f64> = vec![0.0; children.len()];
+    for v in (s..children.len()).rev() {
+        if paths[s][v] == f64::NEG_INFINITY {
+            continue;
+        }
+        dependencies[v] = children[v]
+            .iter()
+            .map(|&c| (paths[s][v] - paths[s][c]).exp() * (1.0 + dependencies[c]))
+            .sum();
+    }
+    dependencies
+}
+
+/// Computes the betweenness centrality of every vertex of a DAG.
+///
+/// The score of a vertex `v` is the sum over all pairs `(s, t)` (`s != v !=
+/// t`) connected by at least one path of the fraction of `s` -> `t` paths that
+/// pass through `v`.  Unlike the classic definition, *all* paths are counted,
+/// not just the shortest ones, as that's usually what matters in dependency
+/// graphs.  Path counts are obtained with a dynamic programming pass over the
+/// vertices in reverse topological order, which replaces the per-source
+/// searches of Brandes' algorithm, and are kept as logarithms so that the
+/// scores stay finite however many paths there are.
+///
+/// Takes `O(|V||E|)` time and `O(|V|^2)` memory.
+pub fn get_betweenness_centrality(dag: &DirectedAcyclicGraph) -> Vec<f64> {
+    let vertex_count = dag.get_vertex_count();
+    let paths = get_log_path_counts_matrix(dag);
+    let children: Vec<Vec<usize>> = (0..vertex_count)
+        .map(|u| dag.iter_children(u).collect())
+        .collect();
+
+    let mut result: Vec<f64> = vec![0.0; vertex_count];
+    for s in 0..vertex_count {
+        let dependencies = get_dependencies_on_source(&children, &paths, s);
+        for v in (s + 1)..vertex_count {
+            result[v] += dependencies[v];
+        }
+    }
+    result
+}
+
+/// Same as [`get_betweenness_centrality`] but for edges: the score of an edge
+/// `(u, v)` is the sum over all pairs `(s, t)` connected by at least one path
+/// of the fraction of `s` -> `t` paths that pass through `(u, v)`.  Edges with
+/// high scores are "choke points" whose removal disconnects the most.
+///
+/// Takes `O(|V||E|)` time and `O(|V|^2)` memory.
+pub fn get_edge_betweenness_centrality(dag: &DirectedAcyclicGraph) -> HashMap<(usize, usize), f64> {
+    let paths = get_log_path_counts_matrix(dag);
+    let children: Vec<Vec<usize>> = (0..dag.get_vertex_count())
+        .map(|u| dag.iter_children(u).collect())
+        .collect();
+    let edges: Vec<(usize, usize)> = dag.iter_edges().collect();
+    let mut scores: Vec<f64> = vec![0.0; edges.len()];
+    for s in 0..dag.get_vertex_count() {
+        let dependencies = get_dependencies_on_source(&children, &paths, s);
+        for ((u, v), score) in edges.iter().zip(scores.iter_mut()) {
+            if paths[s][*u] != f64::NEG_INFINITY {
+                *score += (paths[s][*u] - paths[s][*v]).exp() * (1.0 + dependencies[*v]);
+            }
+        }
+    }
+    edges.into_iter().zip(scores).collect()
+}
+
+// `add` returns `None` on overflow.
+fn get_source_path_counts_with<T: Clone, F: Fn(&T, &T) -> Option<T>>(
+    dag: &DirectedAcyclicGraph,
+    zero: T,
+    one: T,
+    add: F,
+) -> Option<Vec<T>> {
+    let mut counts: Vec<T> = vec![zero; dag.get_vertex_count()];
+    for u in get_vertices_without_incoming_edges(dag) {
+        counts[u] = one.clone();
+    }
+    for u in 0..dag.get_vertex_count() {
+        for v in dag.iter_children(u) {
+            counts[v] = add(&counts[v], &counts[u])?;
+        }
+    }
+    Some(counts)
+}
+
+// `add` returns `None` on overflow.
+fn get_sink_path_counts_with<T: Clone, F: Fn(&T, &T) -> Option<T>>(
+    dag: &DirectedAcyclicGraph,
+    zero: T,
+    one: T,
+    add: F,
+) -> Option<Vec<T>> {
+    let mut counts: Vec<T> = vec![zero.clone(); dag.get_vertex_count()];
+    for u in (0..dag.get_vertex_count()).rev() {
+        let mut u_count = zero.clone();
+        let mut has_children = false;
+        for v in dag.iter_children(u) {
+            u_count = add(&u_count, &counts[v])?;
+            has_children = true;
+        }
+        counts[u] = if has_children { u_count } else { one.clone() };
+    }
+    Some(counts)
+}
+
+/// Computes a mapping: vertex -> number of paths that start at any vertex
+/// without incoming edges and end at vertex.  Such a vertex itself accounts for
+/// one (empty) path.
+///
+/// The counts grow exponentially with the number of layers, e.g. a complete
+/// DAG with 70 vertices has 2^68 paths already.  Returns `None` if a count
+/// doesn't fit in `u64`.  See `get_source_path_counts_big` for an arbitrary
+/// precision variant.
+pub fn get_source_path_counts(dag: &DirectedAcyclicGraph) -> Option<Vec<u64>> {
+    get_source_path_counts_with(dag, 0, 1, |a, b| a.checked_add(*b))
+}
+
+/// Computes a mapping: vertex -> number of paths that start at vertex and end
+/// at any vertex without outgoing edges.  Such a vertex itself accounts for one
+/// (empty) path.
+///
+/// Returns `None` if a count doesn't fit in `u64`, see
+/// [`get_source_path_counts`].  See `get_sink_path_counts_big` for an
+/// arbitrary precision variant.
+pub fn get_sink_path_counts(dag: &DirectedAcyclicGraph) -> Option<Vec<u64>> {
+    get_sink_path_counts_with(dag, 0, 1, |a, b| a.checked_add(*b))
+}
+
+/// Computes a mapping: vertex -> number of maximal (source to sink) paths that
+/// pass through vertex.  It is the product of [`get_source_path_counts`] and
+/// [`get_sink_path_counts`] and is a measure of how critical a vertex is to the
+/// DAG as a whole.
+///
+/// Returns `None` if a score doesn't fit in `u64`.  See
+/// `get_flow_through_scores_big` for an arbitrary precision variant.
+pub fn get_flow_through_scores(dag: &DirectedAcyclicGraph) -> Option<Vec<u64>> {
+    get_source_path_counts(dag)?
+        .into_iter()
+        .zip(get_sink_path_counts(dag)?)
+        .map(|(from_sources, to_sinks)| from_sources.checked_mul(to_sinks))
+        .collect()
+}
+
+/// Computes a mapping: edge -> number of maximal (source to sink) paths that
+/// pass through edge.  It is the edge counterpart of
+/// [`get_flow_through_scores`]: the number of paths from the sources to `u`
+/// times the number of paths from `v` to the sinks.
+///
+/// Returns `None` if a score doesn't fit in `u64`.  See
+/// `get_edge_flow_through_scores_big` for an arbitrary precision variant.
+pub fn get_edge_flow_through_scores(
+    dag: &DirectedAcyclicGraph,
+) -> Option<HashMap<(usize, usize), u64>> {
+    let from_sources = get_source_path_counts(dag)?;
+    let to_sinks = get_sink_path_counts(dag)?;
+    dag.iter_edges()
+        .map(|(u, v)| Some(((u, v), from_sources[u].checked_mul(to_sinks[v])?)))
+        .collect()
+}
+
+/// Same as [`get_source_path_counts`] but never overflows.
+#[cfg(feature = "num-bigint")]
+pub fn get_source_path_counts_big(dag: &DirectedAcyclicGraph) -> Vec<BigUint> {
+    get_source_path_counts_with(dag, BigUint::zero(), BigUint::one(), |a, b| Some(a + b)).unwrap()
+}
+
+/// Same as [`get_sink_path_counts`] but never overflows.
+#[cfg(feature = "num-bigint")]
+pub fn get_sink_path_counts_big(dag: &DirectedAcyclicGraph) -> Vec<BigUint> {
+    get_sink_path_counts_with(dag, BigUint::zero(), BigUint::one(), |a, b| Some(a + b)).unwrap()
+}
+
+/// Same as [`get_flow_through_scores`] but never overflows.
+#[cfg(feature = "num-bigint")]
+pub fn get_flow_through_scores_big(dag: &DirectedAcyclicGraph) -> Vec<BigUint> {
+    get_source_path_counts_big(dag)
+        .into_iter()
+        .zip(get_sink_path_counts_big(dag))
+        .map(|(from_sources, to_sinks)| from_sources * to_sinks)
+        .collect()
+}
+
+/// Same as [`get_edge_flow_through_scores`] but never overflows.
+#[cfg(feature = "num-bigint")]
+pub fn get_edge_flow_through_scores_big(
+    dag: &DirectedAcyclicGraph,
+) -> HashMap<(usize, usize), BigUint> {
+    let from_sources = get_source_path_counts_big(dag);
+    let to_sinks = get_sink_path_counts_big(dag);
+    dag.iter_edges()
+        .map(|(u, v)| ((u, v), &from_sources[u] * &to_sinks[v]))
+        .collect()
+}
+
+/// Returns the vertices of a DAG ordered by decreasing
+/// [`get_flow_through_scores`].  Ties are broken by the smaller vertex first.
+/// Returns `None` if a score doesn't fit in `u64`.
+pub fn get_vertices_ranked_by_flow_through(dag: &DirectedAcyclicGraph) -> Option<Vec<usize>> {
+    let scores = get_flow_through_scores(dag)?;
+    let mut result: Vec<usize> = (0..dag.get_vertex_count()).collect();
+    result.sort_by(|u, v| scores[*v].cmp(&scores[*u]).then(u.cmp(v)));
+    Some(result)
+}
+
+/// Returns the sub-DAG induced by `vertices` along with a mapping: sub-DAG
+/// vertex -> `dag` vertex.  Vertices keep their relative order.
+pub fn get_induced_subgraph(
+    dag: &DirectedAcyclicGraph,
+    vertices: &FixedBitSet,
+) -> (DirectedAcyclicGraph, Vec<usize>) {
+    let mapping: Vec<usize> = vertices.ones().collect();
+    let mut result = DirectedAcyclicGraph::empty(mapping.len());
+    for (new_u, u) in mapping.iter().enumerate() {
+        for (new_v, v) in mapping.iter().enumerate().skip(new_u + 1) {
+            if dag.get_edge(*u, *v) {
+                result.set_edge(new_u, new_v, true);
+            }
+        }
+    }
+    (result, mapping)
+}
+
+fn get_children(dag: &DirectedAcyclicGraph) -> Vec<FixedBitSet> {
+    (0..dag.get_vertex_count())
+        .map(|u| {
+            let mut children = FixedBitSet::with_capacity(dag.get_vertex_count());
+            children.extend(dag.iter_children(u));
+            children
+        })
+        .collect()
+}
+
+// Union-find with path halving.
+fn find(representatives: &mut [usize], mut u: usize) -> usize {
+    while representatives[u] != u {
+        representatives[u] = representatives[representatives[u]];
+        u = representatives[u];
+    }
+    u
+}
+
+// The number of weakly connected components.
+fn count_weakly_connected_components(dag: &DirectedAcyclicGraph) -> usize {
+    let mut representatives: Vec<usize> = (0..dag.get_vertex_count()).collect();
+    let mut component_count = dag.get_vertex_count();
+    for (u, v) in dag.iter_edges() {
+        let (u, v) = (find(&mut representatives, u), find(&mut representatives, v));
+        if u != v {
+            representatives[u] = v;
+            component_count -= 1;
+        }
+    }
+    component_count
+}
+
+/// Checks whether every two vertices are connected by a path if the edges'
+/// directions are ignored.  DAGs with no vertices are considered connected.
+pub fn is_weakly_connected(dag: &DirectedAcyclicGraph) -> bool {
+    count_weakly_connected_components(dag) <= 1
+}
+
+/// Checks whether a DAG is equal to its [`transitive_closure`] without
+/// computing it, i.e. whether every child of a child of `u` is also a child of
+/// `u`.
+pub fn is_transitively_closed(dag: &DirectedAcyclicGraph) -> bool {
+    let children = get_children(dag);
+    (0..dag.get_vertex_count()).all(|u| {
+        children[u]
+            .ones()
+            .all(|v| children[v].is_subset(&children[u]))
+    })
+}
+
+/// Checks whether a DAG is equal to its [`transitive_reduction`] without
+/// computing it, i.e. whether no child of `u` is also reachable from `u`
+/// through some other child.
+///
+/// Searches from the grandchildren of every vertex `u`, skipping the direct
+/// edges, and stops at the first child of `u` reached.  Only vertices up to
+/// the last child of `u` can lead to one, so the search doesn't go past it.
+pub fn is_transitively_reduced(dag: &DirectedAcyclicGraph) -> bool {
+    let vertex_count = dag.get_vertex_count();
+    let children = get_children(dag);
+    let mut visited = FixedBitSet::with_capacity(vertex_count);
+    let mut to_visit: Vec<usize> = Vec::new();
+    (0..vertex_count).all(|u| {
+        let last_child = match children[u].ones().last() {
+            Some(last_child) => last_child,
+            None => return true,
+        };
+        visited.clear();
+        to_visit.clear();
+        to_visit.extend(children[u].ones().flat_map(|v| children[v].ones()));
+        while let Some(w) = to_visit.pop() {
+            if w > last_child || visited.put(w) {
+                continue;
+            }
+            if children[u][w] {
+                return false;
+            }
+            to_visit.extend(children[w].ones());
+        }
+        true
+    })
+}
+
+/// Computes the set of vertices reachable from any of `sources`, including
+/// `sources` themselves, in a single sweep over the vertices in topological
+/// order that ORs in the children of every reached vertex a word at a time.
+/// Much faster than a separate traversal from every source for big sets of
+/// sources.
+pub fn get_reachable_from_set(dag: &DirectedAcyclicGraph, sources: &FixedBitSet) -> FixedBitSet {
+    let mut reached = FixedBitSet::with_capacity(dag.get_vertex_count());
+    reached.extend(sources.ones());
+    let first = match sources.ones().next() {
+        Some(first) => first,
+        None => return reached,
+    };
+    for u in first..dag.get_vertex_count() {
+        if reached[u] {
+            dag.union_children_into(u, &mut reached);
+        }
+    }
+    reached
+}
+
+// Maximum matching in a bipartite graph with both sides being `0..n` using the
+// Hopcroft–Karp algorithm.  `adjacency[u]` is the set of right vertices
+// adjacent to the left vertex `u`.  Returns a mapping: left vertex -> matched
+// right vertex.
+fn get_maximum_matching(adjacency: &[FixedBitSet]) -> Vec<Option<usize>> {
+    const UNREACHED: usize = usize::MAX;
+    let vertex_count = adjacency.len();
+    let mut left_match: Vec<Option<usize>> = vec![None; vertex_count];
+    let mut right_match: Vec<Option<usize>> = vec![None; vertex_count];
+    let mut layers: Vec<usize> = vec![UNREACHED; vertex_count];
+    loop {
+        // Layer the left vertices by their alternating distance from a free
+        // left vertex.
+        let mut queue: VecDeque<usize> = VecDeque::new();
+        for u in 0..vertex_count {
+            if left_match[u].is_none() {
+                layers[u] = 0;
+                queue.push_back(u);
+            } else {
+                layers[u] = UNREACHED;
+            }
+        }
+        let mut found_augmenting_path = false;
+        while let Some(u) = queue.pop_front() {
+            for v in adjacency[u].ones() {
+                match right_match[v] {
+                    None => found_augmenting_path = true,
+                    Some(w) if layers[w] == UNREACHED => {
+                        layers[w] = layers[u] + 1;
+                        queue.push_back(w);
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+        if !found_augmenting_path {
+            return left_match;
+        }
+
+        // Find vertex-disjoint shortest augmenting paths with an iterative
+        // depth-first search along the layers.
+        // A mapping: left vertex -> the first right vertex not tried yet.
+        let mut next_candidate: Vec<usize> = vec![0; vertex_count];
+        for root in 0..vertex_count {
+            if left_match[root].is_some() {
+                continue;
+            }
+            let mut stack: Vec<usize> = vec![root];
+            while let Some(&u) = stack.last() {
+                let v = match (next_candidate[u]..vertex_count).find(|v| adjacency[u].contains(*v))
+                {
+                    Some(v) => v,
+                    None => {
+                        layers[u] = UNREACHED;
+                        stack.pop();
+                        continue;
+                    }
+                };
+                next_candidate[u] = v + 1;
+                match right_match[v] {
+                    None => {
+                        for x in &stack {
+                            let y = next_candidate[*x] - 1;
+                            left_match[*x] = Some(y);
+                            right_match[y] = Some(*x);
+                        }
+                        break;
+                    }
+                    Some(w) if layers[w] == layers[u] + 1 => stack.push(w),
+                    Some(_) => {}
+                }
+            }
+        }
+    }
+}
+
+/// Splits the vertices into the smallest possible number of chains, i.e.
+/// sequences of vertices where each one is reachable from the previous one.
+/// By [Dilworth's theorem](https://en.wikipedia.org/wiki/Dilworth%27s_theorem)
+/// their number is the width of the DAG (the size of its largest antichain).
+///
+/// Uses Fulkerson's reduction to a maximum bipartite matching over the
+/// transitive closure, which is kept as one bit per vertex pair.  Chains are
+/// sorted by their first vertex and vertices within chains are in increasing
+/// order.
+pub fn get_minimum_chain_decomposition(dag: &DirectedAcyclicGraph) -> Vec<Vec<usize>> {
+    let successors = get_maximum_matching(&get_descendants(dag));
+    let mut has_predecessor = FixedBitSet::with_capacity(dag.get_vertex_count());
+    has_predecessor.extend(successors.iter().flatten().copied());
+    let mut result: Vec<Vec<usize>> = Vec::new();
+    for first in 0..dag.get_vertex_count() {
+        if has_predecessor[first] {
+            continue;
+        }
+        let mut chain: Vec<usize> = vec![first];
+        while let Some(next) = successors[*chain.last().unwrap()] {
+            chain.push(next);
+        }
+        result.push(chain);
+    }
+    result
+}
+
+/// Splits `vertex` into two vertices connected by an edge: `vertex` keeps the
+/// incoming edges and the new vertex `vertex + 1` gets the outgoing ones.
+/// Vertices after `vertex` are shifted by one, i.e. `u > vertex` becomes `u +
+/// 1`, so that the result is still a valid DAG.  A standard way of turning
+/// vertex capacities or costs into edge ones for flow-style algorithms.
+pub fn split_vertex(dag: &DirectedAcyclicGraph, vertex: usize) -> DirectedAcyclicGraph {
+    assert!(vertex < dag.get_vertex_count());
+    let map_source = |u: usize| if u < vertex { u } else { u + 1 };
+    let map_target = |v: usize| if v <= vertex { v } else { v + 1 };
+    DirectedAcyclicGraph::from_edges_iter(
+        dag.get_vertex_count() + 1,
+        dag.iter_edges()
+            .map(|(u, v)| (map_source(u), map_target(v)))
+            .chain(std::iter::once((vertex, vertex + 1))),
+    )
+}
+
+/// Unifies two DAGs given a partial correspondence between their vertices:
+/// `mapping` pairs `(u, w)` say that vertex `u` of `dag` and vertex `w` of
+/// `other` are the same vertex.  Unmapped vertices of `other` become new
+/// vertices and the edges of both DAGs are unioned.
+///
+/// Since the result generally doesn't follow the vertex order of either input,
+/// its vertices are renumbered topologically.  Returns the merged DAG along with
+/// two mappings: `dag` vertex -> merged vertex and `other` vertex -> merged
+/// vertex.  If the union has a cycle, returns an edge of `other` (in `other`'s
+/// numbering) that closes one instead.
+///
+/// Every vertex of `other` can be mapped at most once.  Panics otherwise.
+pub fn merge(
+    dag: &DirectedAcyclicGraph,
+    other: &DirectedAcyclicGraph,
+    mapping: &[(usize, usize)],
+) -> Result<(DirectedAcyclicGraph, Vec<usize>, Vec<usize>), CycleError> {
+    let mut union = IncrementalTopologicalOrder::new(dag.get_vertex_count());
+    let mut other_to_union: Vec<Option<usize>> = vec![None; other.get_vertex_count()];
+    for &(u, w) in mapping {
+        assert!(u < dag.get_vertex_count());
+        assert!(other_to_union[w].is_none(), "vertex {} mapped twice", w);
+        other_to_union[w] = Some(u);
+    }
+    let other_to_union: Vec<usize> = other_to_union
+        .into_iter()
+        .map(|u| u.unwrap_or_else(|| union.add_vertex()))
+        .collect();
+
+    // Edges of `dag` alone are acyclic, so only edges of `other` can fail.
+    for (u, v) in dag.iter_edges() {
+        union.try_add_edge(u, v).unwrap();
+    }
+    for (u, v) in other.iter_edges() {
+        union
+            .try_add_edge(other_to_union[u], other_to_union[v])
+            .map_err(|_| CycleError { edge: (u, v) })?;
+    }
+
+    let (merged, union_to_merged) = union.to_dag();
+    let dag_to_merged = union_to_merged[..dag.get_vertex_count()].to_vec();
+    let other_to_merged = other_to_union
+        .into_iter()
+        .map(|u| union_to_merged[u])
+        .collect();
+    Ok((merged, dag_to_merged, other_to_merged))
+}
+
+/// Collapses every block of a partition of the vertices into a single vertex,
+/// e.g. build targets into the crates they belong to.  `partition` is a
+/// mapping: vertex -> block id, where the ids are arbitrary, and there's an
+/// edge between two different blocks iff there's an edge between any of their
+/// vertices.
+///
+/// The blocks are renumbered topologically, preferring blocks with smaller ids
+/// first, so that the numbering is kept if it's topological already.  Returns
+/// the quotient DAG along with the mapping: vertex -> quotient vertex, or an
+/// edge between two blocks (as block ids) that closes a cycle among them.
+///
+/// Requires a block id for every vertex.  Panics otherwise.
+pub fn quotient(
+    dag: &DirectedAcyclicGraph,
+    partition: &[usize],
+) -> Result<(DirectedAcyclicGraph, Vec<usize>), CycleError> {
+    assert_eq!(partition.len(), dag.get_vertex_count());
+    // Compact the ids into `0..block_count`, keeping their order.
+    let mut block_ids = partition.to_vec();
+    block_ids.sort_unstable();
+    block_ids.dedup();
+    let blocks: Vec<usize> = partition
+        .iter()
+        .map(|id| block_ids.binary_search(id).unwrap())
+        .collect();
+    let block_graph = DirectedGraph::from_edges_iter(
+        block_ids.len(),
+        dag.iter_edges()
+            .map(|(u, v)| (blocks[u], blocks[v]))
+            .filter(|(a, b)| a != b),
+    );
+    let (quotient_dag, block_to_quotient) =
+        block_graph
+            .to_dag()
+            .map_err(|CycleError { edge: (a, b) }| CycleError {
+                edge: (block_ids[a], block_ids[b]),
+            })?;
+    let mapping = blocks.into_iter().map(|b| block_to_quotient[b]).collect();
+    Ok((quotient_dag, mapping))
+}
+
+/// Contracts every maximal chain `v0 -> v1 -> ... -> vk`, where each `(vi,
+/// vi+1)` is the only edge out of `vi` and the only edge into `vi+1`, into a
+/// single vertex.  Returns the condensed DAG along with the expansion map:
+/// condensed vertex -> the vertices of its chain, in order.  Vertices not on
+/// any such chain stay on their own.
+///
+/// Reachability between vertices of different chains is preserved.  The chains
+/// are numbered in the order of their first vertices.
+pub fn get_chain_condensation(
+    dag: &DirectedAcyclicGraph,
+) -> (DirectedAcyclicGraph, Vec<Vec<usize>>) {
+    let vertex_count = dag.get_vertex_count();
+    let in_degrees = get_incoming_edges_count(dag);
+    let out_degrees: Vec<usize> = (0..vertex_count)
+        .map(|u| dag.iter_children(u).count())
+        .collect();
+    // The next vertex on the chain, if the chain continues past `u`.
+    let next = |u: usize| {
+        if out_degrees[u] != 1 {
+            return None;
+        }
+        let v = dag.iter_children(u).next().unwrap();
+        if in_degrees[v] == 1 {
+            Some(v)
+        } else {
+            None
+        }
+    };
+    let mut chain_of: Vec<usize> = vec![usize::MAX; vertex_count];
+    let mut chains: Vec<Vec<usize>> = Vec::new();
+    for head in 0..vertex_count {
+        if chain_of[head] != usize::MAX {
+            continue;
+        }
+        let mut chain: Vec<usize> = vec![head];
+        chain_of[head] = chains.len();
+        let mut u = head;
+        while let Some(v) = next(u) {
+            chain_of[v] = chains.len();
+            chain.push(v);
+            u = v;
+        }
+        chains.push(chain);
+    }
+    // Only the last vertex of a chain can have edges leaving it and they
+    // always point at the first vertex of another chain, so the heads' order
+    // is topological.
+    let condensed = DirectedAcyclicGraph::from_edges_iter(
+        chains.len(),
+        chains.iter().enumerate().flat_map(|(c, chain)| {
+            let tail = *chain.last().unwrap();
+            let chain_of = &chain_of;
+            dag.iter_children(tail)
+                .filter(move |v| chain_of[*v] != c)
+                .map(move |v| (c, chain_of[v]))
+        }),
+    );
+    (condensed, chains)
+}
+
+/// Summary of the vertex degrees of a DAG.  See [`get_degree_statistics`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct DegreeStatistics {
+    /// A mapping: in-degree -> number of vertices having it.
+    pub in_degree_histogram: Vec<usize>,
+    /// A mapping: out-degree -> number of vertices having it.
+    pub out_degree_histogram: Vec<usize>,
+    pub max_in_degree: usize,
+    pub max_out_degree: usize,
+    /// The mean in-degree, which is always equal to the mean out-degree
+    /// (`|E| / |V|`).  Zero for DAGs with no vertices.
+    pub mean_degree: f64,
+}
+
+/// Computes degree histograms, maxima and the mean degree.
+pub fn get_degree_statistics(dag: &DirectedAcyclicGraph) -> DegreeStatistics {
+    let in_degrees = get_incoming_edges_count(dag);
+    let out_degrees: Vec<usize> = (0..dag.get_vertex_count())
+        .map(|u| dag.iter_children(u).count())
+        .collect();
+    let edge_count: usize = out_degrees.iter().sum();
+    let get_histogram = |degrees: &[usize]| {
+        let mut histogram: Vec<usize> = Vec::new();
+        for degree in degrees {
+            if histogram.len() <= *degree {
+                histogram.resize(*degree + 1, 0);
+            }
+            histogram[*degree] += 1;
+        }
+        histogram
+    };
+    DegreeStatistics {
+        in_degree_histogram: get_histogram(&in_degrees),
+        out_degree_histogram: get_histogram(&out_degrees),
+        max_in_degree: in_degrees.iter().copied().max().unwrap_or(0),
+        max_out_degree: out_degrees.iter().copied().max().unwrap_or(0),
+        mean_degree: if dag.get_vertex_count() == 0 {
+            0.0
+        } else {
+            edge_count as f64 / dag.get_vertex_count() as f64
+        },
+    }
+}
+
+/// Summary statistics of a DAG.  See [`get_summary`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Summary {
+    pub vertex_count: usize,
+    pub edge_count: usize,
+    /// The number of vertices without incoming edges.
+    pub source_count: usize,
+    /// The number of vertices without outgoing edges.
+    pub sink_count: usize,
+    /// The length (number of edges) of the longest path, as in
+    /// [`crate::paths::get_diameter`].
+    pub height: usize,
+    /// The size of the biggest layer when vertices are grouped by the length
+    /// of the longest path ending at them.  Every layer is an antichain, so
+    /// this is a lower bound on the width (see
+    /// [`get_minimum_chain_decomposition`]).
+    pub width_estimate: usize,
+    /// `|E|` divided by the maximum possible number of edges, `|V| * (|V| -
+    /// 1) / 2`.  Zero for DAGs with fewer than 2 vertices.
+    pub density: f64,
+    /// The number of weakly connected components.
+    pub component_count: usize,
+}
+
+/// Computes [`Summary`] statistics.
+pub fn get_summary(dag: &DirectedAcyclicGraph) -> Summary {
+    let vertex_count = dag.get_vertex_count();
+    let mut has_parents = FixedBitSet::with_capacity(vertex_count);
+    let mut has_children = FixedBitSet::with_capacity(vertex_count);
+    let mut depths: Vec<usize> = vec![0; vertex_count];
+    let mut edge_count = 0;
+    // Edges come in the row-major order, so all the edges into `u` have been
+    // seen by the time any edge out of it is.
+    for (u, v) in dag.iter_edges() {
+        edge_count += 1;
+        has_children.insert(u);
+        has_parents.insert(v);
+        depths[v] = std::cmp::max(depths[v], depths[u] + 1);
+    }
+    let height = depths.iter().copied().max().unwrap_or(0);
+    let mut layer_sizes: Vec<usize> = vec![0; height + 1];
+    for depth in depths {
+        layer_sizes[depth] += 1;
+    }
+    Summary {
+        vertex_count,
+        edge_count,
+        source_count: vertex_count - has_parents.count_ones(..),
+        sink_count: vertex_count - has_children.count_ones(..),
+        height,
+        width_estimate: layer_sizes.into_iter().max().unwrap_or(0),
+        density: dag.density(),
+        component_count: count_weakly_connected_components(dag),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -133,4 +1083,489 @@ mod tests {
     ) {
         quickcheck::QuickCheck::new().quickcheck(prop_transitive_closure_and_transitive_reduction_intersection_equals_transitive_reduction_modulo_order as fn(DirectedAcyclicGraph) -> bool);
     }
+
+    fn prop_closure_and_reduction_predicates(dag: DirectedAcyclicGraph) -> bool {
+        let closure = transitive_closure(&dag);
+        let reduction = transitive_reduction(&dag);
+        let edges: Vec<(usize, usize)> = dag.iter_edges().collect();
+        is_transitively_closed(&closure)
+            && is_transitively_reduced(&reduction)
+            && is_transitively_closed(&dag) == (closure.iter_edges().collect::<Vec<_>>() == edges)
+            && is_transitively_reduced(&dag)
+                == (reduction.iter_edges().collect::<Vec<_>>() == edges)
+    }
+
+    #[test]
+    fn closure_and_reduction_predicates() {
+        quickcheck::QuickCheck::new()
+            .quickcheck(prop_closure_and_reduction_predicates as fn(DirectedAcyclicGraph) -> bool);
+    }
+
+    fn prop_reachable_pairs_count_equals_closure_edges_count(dag: DirectedAcyclicGraph) -> bool {
+        count_reachable_pairs(&dag) == transitive_closure(&dag).iter_edges().count()
+    }
+
+    #[test]
+    fn reachable_pairs_count_equals_closure_edges_count() {
+        let dag =
+            DirectedAcyclicGraph::from_edges_iter(4, vec![(0, 1), (1, 2), (2, 3)].into_iter());
+        assert_eq!(count_reachable_pairs(&dag), 6);
+        quickcheck::QuickCheck::new().quickcheck(
+            prop_reachable_pairs_count_equals_closure_edges_count
+                as fn(DirectedAcyclicGraph) -> bool,
+        );
+    }
+
+    #[test]
+    fn minimum_equivalent_graph_drops_implied_edges() {
+        let dag = DirectedAcyclicGraph::from_edges_iter(
+            4,
+            vec![(0, 1), (1, 2), (2, 3), (0, 2), (0, 3), (1, 3)].into_iter(),
+        );
+        let (minimum, removed_edge_count) = get_minimum_equivalent_graph(&dag);
+        assert_eq!(
+            minimum.iter_edges().collect::<Vec<_>>(),
+            vec![(0, 1), (1, 2), (2, 3)]
+        );
+        assert_eq!(removed_edge_count, 3);
+        assert_eq!(get_minimum_equivalent_graph(&minimum).1, 0);
+    }
+
+    fn prop_betweenness_matches_path_counting(dag: DirectedAcyclicGraph) -> bool {
+        let vertex_count = dag.get_vertex_count();
+        // paths[s][t] is the number of paths from s to t.
+        let mut paths: Vec<Vec<f64>> = vec![vec![0.0; vertex_count]; vertex_count];
+        for s in (0..vertex_count).rev() {
+            let mut s_paths = vec![0.0; vertex_count];
+            s_paths[s] = 1.0;
+            for c in dag.iter_children(s) {
+                for (s_t_paths, c_t_paths) in s_paths.iter_mut().zip(&paths[c]) {
+                    *s_t_paths += c_t_paths;
+                }
+            }
+            paths[s] = s_paths;
+        }
+        let fraction = |s: usize, u: usize, v: usize, t: usize| {
+            if paths[s][u] == 0.0 || paths[v][t] == 0.0 {
+                0.0
+            } else {
+                paths[s][u] * paths[v][t] / paths[s][t]
+            }
+        };
+        let close = |a: f64, b: f64| (a - b).abs() <= 1e-9 * f64::max(1.0, b.abs());
+        let scores = get_betweenness_centrality(&dag);
+        let edge_scores = get_edge_betweenness_centrality(&dag);
+        (0..vertex_count).all(|v| {
+            let expected = (0..v)
+                .flat_map(|s| ((v + 1)..vertex_count).map(move |t| (s, t)))
+                .map(|(s, t)| fraction(s, v, v, t))
+                .sum();
+            close(scores[v], expected)
+        }) && dag.iter_edges().all(|(u, v)| {
+            let expected = (0..=u)
+                .flat_map(|s| (v..vertex_count).map(move |t| (s, t)))
+                .map(|(s, t)| fraction(s, u, v, t))
+                .sum();
+            close(edge_scores[&(u, v)], expected)
+        })
+    }
+
+    #[test]
+    fn betweenness_matches_path_counting() {
+        quickcheck::QuickCheck::new()
+            .rng(quickcheck::Gen::new(20))
+            .quickcheck(prop_betweenness_matches_path_counting as fn(DirectedAcyclicGraph) -> bool);
+    }
+
+    #[test]
+    fn betweenness_centrality_of_diamond() {
+        let dag = DirectedAcyclicGraph::from_edges_iter(
+            5,
+            vec![(0, 1), (0, 2), (1, 3), (2, 3), (3, 4)].into_iter(),
+        );
+        assert_eq!(
+            get_betweenness_centrality(&dag),
+            vec![0.0, 1.0, 1.0, 3.0, 0.0]
+        );
+
+        let edge_scores = get_edge_betweenness_centrality(&dag);
+        assert_eq!(edge_scores.len(), 5);
+        assert_eq!(edge_scores[&(0, 1)], 2.0);
+        assert_eq!(edge_scores[&(1, 3)], 3.0);
+        assert_eq!(edge_scores[&(3, 4)], 4.0);
+
+        let edge_scores = get_edge_flow_through_scores(&dag).unwrap();
+        assert_eq!(edge_scores[&(0, 1)], 1);
+        assert_eq!(edge_scores[&(3, 4)], 2);
+    }
+
+    #[test]
+    fn centrality_stays_finite_when_path_counts_overflow_f64() {
+        // About 1.93^|V| paths between the ends, way past f64::MAX.
+        let vertex_count = 1100;
+        let dag = DirectedAcyclicGraph::from_edges_iter(
+            vertex_count,
+            (0..vertex_count)
+                .flat_map(|u| ((u + 1)..std::cmp::min(u + 5, vertex_count)).map(move |v| (u, v))),
+        );
+        let scores = get_betweenness_centrality(&dag);
+        assert!(scores.iter().all(|score| score.is_finite()));
+        // Every pair around the middle vertex is connected only through it or
+        // its neighbours, so its score is a sizeable fraction of all pairs.
+        assert!(scores[vertex_count / 2] > 1000.0);
+        let edge_scores = get_edge_betweenness_centrality(&dag);
+        assert!(edge_scores.values().all(|score| score.is_finite()));
+    }
+
+    #[test]
+    fn flow_through_ranking_of_diamond() {
+        let dag = DirectedAcyclicGraph::from_edges_iter(
+            6,
+            vec![(0, 1), (0, 2), (1, 3), (2, 3), (3, 4)].into_iter(),
+        );
+        assert_eq!(get_source_path_counts(&dag), Some(vec![1, 1, 1, 2, 2, 1]));
+        assert_eq!(get_sink_path_counts(&dag), Some(vec![2, 1, 1, 1, 1, 1]));
+        assert_eq!(get_flow_through_scores(&dag), Some(vec![2, 1, 1, 2, 2, 1]));
+        assert_eq!(
+            get_vertices_ranked_by_flow_through(&dag),
+            Some(vec![0, 3, 4, 1, 2, 5])
+        );
+    }
+
+    #[test]
+    fn path_counts_overflow_is_detected() {
+        // Every subset of the inner vertices forms a distinct 0 -> 69 path.
+        let dag = DirectedAcyclicGraph::from_edges_iter(
+            70,
+            (0..70).flat_map(|u| ((u + 1)..70).map(move |v| (u, v))),
+        );
+        assert_eq!(get_source_path_counts(&dag), None);
+        assert_eq!(get_sink_path_counts(&dag), None);
+        assert_eq!(get_flow_through_scores(&dag), None);
+        assert_eq!(get_edge_flow_through_scores(&dag), None);
+        assert_eq!(get_vertices_ranked_by_flow_through(&dag), None);
+        // 2^31 paths still fit.
+        let dag = DirectedAcyclicGraph::from_edges_iter(
+            33,
+            (0..33).flat_map(|u| ((u + 1)..33).map(move |v| (u, v))),
+        );
+        assert_eq!(get_source_path_counts(&dag).unwrap()[32], 1 << 31);
+        assert_eq!(get_flow_through_scores(&dag).unwrap()[16], 1 << 30);
+    }
+
+    #[cfg(feature = "num-bigint")]
+    #[test]
+    fn big_path_counts_do_not_overflow() {
+        let dag = DirectedAcyclicGraph::from_edges_iter(
+            70,
+            (0..70).flat_map(|u| ((u + 1)..70).map(move |v| (u, v))),
+        );
+        assert_eq!(
+            get_source_path_counts_big(&dag)[69],
+            BigUint::from(2u8).pow(68)
+        );
+        assert_eq!(
+            get_sink_path_counts_big(&dag)[0],
+            BigUint::from(2u8).pow(68)
+        );
+    }
+
+    #[test]
+    fn degree_statistics_of_fork() {
+        let dag = crate::dag!(4; 0 => 1, 0 => 2, 0 => 3, 1 => 3);
+        assert_eq!(
+            get_degree_statistics(&dag),
+            DegreeStatistics {
+                in_degree_histogram: vec![1, 2, 1],
+                out_degree_histogram: vec![2, 1, 0, 1],
+                max_in_degree: 2,
+                max_out_degree: 3,
+                mean_degree: 1.0,
+            }
+        );
+    }
+
+    fn prop_degree_histograms_cover_all_vertices(dag: DirectedAcyclicGraph) -> bool {
+        let statistics = get_degree_statistics(&dag);
+        let edge_count = dag.iter_edges().count();
+        [
+            &statistics.in_degree_histogram,
+            &statistics.out_degree_histogram,
+        ]
+        .iter()
+        .all(|histogram| {
+            histogram.iter().sum::<usize>() == dag.get_vertex_count()
+                && histogram
+                    .iter()
+                    .enumerate()
+                    .map(|(degree, count)| degree * count)
+                    .sum::<usize>()
+                    == edge_count
+        }) && (dag.get_vertex_count() == 0
+            || (statistics.in_degree_histogram.len() == statistics.max_in_degree + 1
+                && statistics.out_degree_histogram.len() == statistics.max_out_degree + 1))
+    }
+
+    #[test]
+    fn degree_histograms_cover_all_vertices() {
+        quickcheck::QuickCheck::new().quickcheck(
+            prop_degree_histograms_cover_all_vertices as fn(DirectedAcyclicGraph) -> bool,
+        );
+    }
+
+    fn prop_reachable_from_set_is_union_of_descendants(dag: DirectedAcyclicGraph) -> bool {
+        let descendants = get_descendants(&dag);
+        let mut sources = FixedBitSet::with_capacity(dag.get_vertex_count());
+        sources.extend((0..dag.get_vertex_count()).filter(|u| u % 7 == 3));
+        let mut expected = sources.clone();
+        for u in sources.ones() {
+            expected.union_with(&descendants[u]);
+        }
+        get_reachable_from_set(&dag, &sources) == expected
+    }
+
+    #[test]
+    fn reachable_from_set_is_union_of_descendants() {
+        quickcheck::QuickCheck::new().quickcheck(
+            prop_reachable_from_set_is_union_of_descendants as fn(DirectedAcyclicGraph) -> bool,
+        );
+        let dag = crate::dag!(5; 0 => 1, 2 => 3, 3 => 4);
+        let sources: FixedBitSet = [2].into_iter().collect();
+        assert_eq!(
+            get_reachable_from_set(&dag, &sources)
+                .ones()
+                .collect::<Vec<_>>(),
+            vec![2, 3, 4]
+        );
+    }
+
+    fn prop_four_russians_closure_agrees(dag: DirectedAcyclicGraph) -> bool {
+        transitive_closure_four_russians(&dag) == transitive_closure(&dag)
+    }
+
+    #[test]
+    fn four_russians_closure_agrees() {
+        quickcheck::QuickCheck::new()
+            .quickcheck(prop_four_russians_closure_agrees as fn(DirectedAcyclicGraph) -> bool);
+        let sparse = crate::dag!(20; 0 => 9, 9 => 17, 17 => 19, 3 => 4);
+        assert_eq!(
+            transitive_closure_four_russians(&sparse),
+            transitive_closure(&sparse)
+        );
+    }
+
+    #[cfg(feature = "rayon")]
+    fn prop_parallel_closure_agrees(dag: DirectedAcyclicGraph) -> bool {
+        transitive_closure_parallel(&dag) == transitive_closure(&dag)
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel_closure_agrees() {
+        quickcheck::QuickCheck::new()
+            .quickcheck(prop_parallel_closure_agrees as fn(DirectedAcyclicGraph) -> bool);
+    }
+
+    fn prop_chain_decomposition_is_valid(dag: DirectedAcyclicGraph) -> bool {
+        let descendants = get_descendants(&dag);
+        let chains = get_minimum_chain_decomposition(&dag);
+        let mut vertices: Vec<usize> = chains.iter().flatten().copied().collect();
+        vertices.sort_unstable();
+        vertices == (0..dag.get_vertex_count()).collect::<Vec<usize>>()
+            && chains.iter().all(|chain| {
+                chain
+                    .windows(2)
+                    .all(|pair| descendants[pair[0]].contains(pair[1]))
+            })
+    }
+
+    #[test]
+    fn chain_decomposition_is_valid() {
+        quickcheck::QuickCheck::new()
+            .quickcheck(prop_chain_decomposition_is_valid as fn(DirectedAcyclicGraph) -> bool);
+    }
+
+    fn prop_chain_count_is_width(dag: DirectedAcyclicGraph) -> bool {
+        let width = crate::poset::Poset::new(&dag)
+            .iter_maximal_antichains()
+            .map(|antichain| antichain.len())
+            .max()
+            .unwrap_or(0);
+        get_minimum_chain_decomposition(&dag).len() == width
+    }
+
+    #[test]
+    fn chain_decomposition_is_minimum() {
+        quickcheck::QuickCheck::new()
+            .rng(quickcheck::Gen::new(20))
+            .quickcheck(prop_chain_count_is_width as fn(DirectedAcyclicGraph) -> bool);
+
+        // Width 3: {2, 3, 4} is an antichain.
+        let dag = crate::dag!(6; 0 => 2, 0 => 3, 1 => 4, 2 => 5, 3 => 5, 4 => 5);
+        assert_eq!(
+            get_minimum_chain_decomposition(&dag),
+            vec![vec![0, 2, 5], vec![1, 4], vec![3]]
+        );
+        assert_eq!(
+            get_minimum_chain_decomposition(&crate::dag!(3)),
+            vec![vec![0], vec![1], vec![2]]
+        );
+    }
+
+    #[test]
+    fn split_vertex_moves_edges() {
+        //  0 -> 1 -> 2, 0 -> 2
+        let dag = crate::dag!(3; 0 => 1, 1 => 2, 0 => 2);
+        assert_eq!(
+            split_vertex(&dag, 1),
+            crate::dag!(4; 0 => 1, 1 => 2, 2 => 3, 0 => 3)
+        );
+        assert_eq!(
+            split_vertex(&dag, 2),
+            crate::dag!(4; 0 => 1, 1 => 2, 0 => 2, 2 => 3)
+        );
+    }
+
+    #[test]
+    fn merge_via_mapping() {
+        // dag: 0 -> 1, other: 0 -> 1 -> 2 where other's 0 is dag's 1.
+        let dag = crate::dag!(2; 0 => 1);
+        let other = crate::dag!(3; 0 => 1, 1 => 2);
+        let (merged, dag_to_merged, other_to_merged) = merge(&dag, &other, &[(1, 0)]).unwrap();
+        assert_eq!(merged, crate::dag!(4; 0 => 1, 1 => 2, 2 => 3));
+        assert_eq!(dag_to_merged, vec![0, 1]);
+        assert_eq!(other_to_merged, vec![1, 2, 3]);
+
+        // Other's 0 -> 1 is dag's 1 -> 0.
+        assert_eq!(
+            merge(&dag, &crate::dag!(2; 0 => 1), &[(1, 0), (0, 1)]),
+            Err(CycleError { edge: (0, 1) })
+        );
+    }
+
+    fn prop_reachable_pairs_are_closure_edges(dag: DirectedAcyclicGraph) -> bool {
+        iter_reachable_pairs(&dag).eq(transitive_closure(&dag).iter_edges())
+    }
+
+    #[test]
+    fn reachable_pairs_are_closure_edges() {
+        quickcheck::QuickCheck::new()
+            .quickcheck(prop_reachable_pairs_are_closure_edges as fn(DirectedAcyclicGraph) -> bool);
+    }
+
+    fn prop_redundant_edges_are_missing_from_reduction(dag: DirectedAcyclicGraph) -> bool {
+        let reduction = transitive_reduction(&dag);
+        get_redundant_edges(&dag)
+            .into_iter()
+            .eq(dag.iter_edges().filter(|&(u, v)| !reduction.get_edge(u, v)))
+    }
+
+    #[test]
+    fn redundant_edges_are_missing_from_reduction() {
+        quickcheck::QuickCheck::new().quickcheck(
+            prop_redundant_edges_are_missing_from_reduction as fn(DirectedAcyclicGraph) -> bool,
+        );
+    }
+
+    #[test]
+    fn summary_of_fork() {
+        //  0 -> 1 -> 2, 0 -> 3, 4
+        let dag = crate::dag!(5; 0 => 1, 1 => 2, 0 => 3);
+        assert_eq!(
+            get_summary(&dag),
+            Summary {
+                vertex_count: 5,
+                edge_count: 3,
+                source_count: 2,
+                sink_count: 3,
+                height: 2,
+                width_estimate: 2,
+                density: 0.3,
+                component_count: 2,
+            }
+        );
+        let empty = get_summary(&DirectedAcyclicGraph::empty(0));
+        assert_eq!((empty.width_estimate, empty.density), (0, 0.0));
+    }
+
+    fn prop_summary_agrees_with_separate_calls(dag: DirectedAcyclicGraph) -> bool {
+        let summary = get_summary(&dag);
+        summary.height == crate::paths::get_diameter(&dag)
+            && summary.source_count
+                == crate::traversal::get_vertices_without_incoming_edges(&dag).len()
+            && (summary.component_count <= 1) == is_weakly_connected(&dag)
+            && summary.width_estimate <= get_minimum_chain_decomposition(&dag).len()
+    }
+
+    #[test]
+    fn summary_agrees_with_separate_calls() {
+        quickcheck::QuickCheck::new().quickcheck(
+            prop_summary_agrees_with_separate_calls as fn(DirectedAcyclicGraph) -> bool,
+        );
+    }
+
+    #[test]
+    fn chain_condensation_of_diamond_with_tails() {
+        //  0 -> 1 -> 2 -> 3 -> 5 -> 6
+        //       1 ------> 4 -> 5
+        let dag = crate::dag!(7; 0 => 1, 1 => 2, 2 => 3, 3 => 5, 1 => 4, 4 => 5, 5 => 6);
+        let (condensed, chains) = get_chain_condensation(&dag);
+        assert_eq!(chains, vec![vec![0, 1], vec![2, 3], vec![4], vec![5, 6]]);
+        assert_eq!(condensed, crate::dag!(4; 0 => 1, 0 => 2, 1 => 3, 2 => 3));
+    }
+
+    fn prop_chain_condensation_preserves_reachability(dag: DirectedAcyclicGraph) -> bool {
+        let (condensed, chains) = get_chain_condensation(&dag);
+        let mut chain_of: Vec<usize> = vec![usize::MAX; dag.get_vertex_count()];
+        for (c, chain) in chains.iter().enumerate() {
+            for u in chain {
+                chain_of[*u] = c;
+            }
+        }
+        let descendants = get_descendants(&dag);
+        let condensed_descendants = get_descendants(&condensed);
+        chain_of.iter().all(|c| *c != usize::MAX)
+            && chains
+                .iter()
+                .all(|chain| chain.windows(2).all(|w| dag.get_edge(w[0], w[1])))
+            && (0..dag.get_vertex_count()).all(|u| {
+                (0..dag.get_vertex_count())
+                    .filter(|v| chain_of[u] != chain_of[*v])
+                    .all(|v| {
+                        descendants[u].contains(v)
+                            == condensed_descendants[chain_of[u]].contains(chain_of[v])
+                    })
+            })
+    }
+
+    #[test]
+    fn chain_condensation_preserves_reachability() {
+        quickcheck::QuickCheck::new().quickcheck(
+            prop_chain_condensation_preserves_reachability as fn(DirectedAcyclicGraph) -> bool,
+        );
+    }
+
+    #[test]
+    fn quotient_by_partition() {
+        //  0 -> 1 -> 2 -> 3, 0 -> 3
+        let dag = crate::dag!(4; 0 => 1, 1 => 2, 2 => 3, 0 => 3);
+        // Blocks {3}, {0, 1} and {2} get renumbered topologically.
+        let (quotient_dag, mapping) = quotient(&dag, &[1, 1, 2, 0]).unwrap();
+        assert_eq!(quotient_dag, crate::dag!(3; 0 => 1, 1 => 2, 0 => 2));
+        assert_eq!(mapping, vec![0, 0, 1, 2]);
+        // Sparse ids don't cost anything and are kept in cycle errors.
+        assert_eq!(
+            quotient(&dag, &[usize::MAX, usize::MAX, 7, 0]),
+            Ok((quotient_dag, mapping))
+        );
+        // Blocks {0, 2} and {1, 3} depend on each other.
+        assert_eq!(
+            quotient(&dag, &[0, usize::MAX, 0, usize::MAX]),
+            Err(CycleError {
+                edge: (usize::MAX, 0)
+            })
+        );
+        // The trivial partition gives back the same DAG.
+        assert_eq!(quotient(&dag, &[0, 1, 2, 3]), Ok((dag, vec![0, 1, 2, 3])));
+    }
 }
@@ -0,0 +1,205 @@
+use std::fmt;
+
+use rkyv::rancor::{self, Source};
+use rkyv::util::AlignedVec;
+
+use crate::strictly_upper_triangular_logical_matrix::{
+    checked_strictly_upper_triangular_matrix_capacity, strictly_upper_triangular_matrix_capacity,
+    unchecked_get_index_from_row_column,
+};
+use crate::DirectedAcyclicGraph;
+
+/// A [`DirectedAcyclicGraph`] in a form that can be serialized with
+/// [rkyv](https://rkyv.org/).  The archived form, [`ArchivedDirectedAcyclicGraph`],
+/// can be queried in place, straight from the serialized bytes (e.g. a mapped
+/// file), without deserializing first.
+///
+/// The layout mirrors the in-memory one: the bits of the [row-major
+/// packed](crate::StrictlyUpperTriangularLogicalMatrix) matrix stored in
+/// 32-bit little-endian blocks, least significant bit first.
+#[derive(Clone, Debug, PartialEq, rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+#[rkyv(archived = ArchivedDirectedAcyclicGraph)]
+pub struct ArchivableDirectedAcyclicGraph {
+    vertex_count: u64,
+    blocks: Vec<u32>,
+}
+
+impl From<&DirectedAcyclicGraph> for ArchivableDirectedAcyclicGraph {
+    fn from(dag: &DirectedAcyclicGraph) -> Self {
+        let vertex_count = dag.get_vertex_count();
+        let mut blocks: Vec<u32> =
+            vec![0; strictly_upper_triangular_matrix_capacity(vertex_count).div_ceil(32)];
+        for (u, v) in dag.iter_edges() {
+            let index = unchecked_get_index_from_row_column(u, v, vertex_count);
+            blocks[index / 32] |= 1 << (index % 32);
+        }
+        Self {
+            vertex_count: vertex_count as u64,
+            blocks,
+        }
+    }
+}
+
+impl ArchivableDirectedAcyclicGraph {
+    pub fn to_dag(&self) -> DirectedAcyclicGraph {
+        let vertex_count = self.vertex_count as usize;
+        DirectedAcyclicGraph::from_edges_iter(
+            vertex_count,
+            iter_ones(vertex_count, |index| {
+                self.blocks.get(index).copied().unwrap_or(0)
+            }),
+        )
+    }
+}
+
+fn iter_ones<B: Fn(usize) -> u32>(
+    vertex_count: usize,
+    get_block: B,
+) -> impl Iterator<Item = (usize, usize)> {
+    (0..vertex_count)
+        .flat_map(move |u| ((u + 1)..vertex_count).map(move |v| (u, v)))
+        .filter(move |(u, v)| {
+            let index = unchecked_get_index_from_row_column(*u, *v, vertex_count);
+            (get_block(index / 32) >> (index % 32)) & 1 == 1
+        })
+}
+
+impl ArchivedDirectedAcyclicGraph {
+    #[inline]
+    pub fn get_vertex_count(&self) -> usize {
+        self.vertex_count.to_native() as usize
+    }
+
+    #[inline]
+    fn get_bit(&self, index: usize) -> bool {
+        // Archives with too few blocks for their vertex count are considered
+        // to have no edges past the last block rather than panicking.
+        self.blocks
+            .get(index / 32)
+            .is_some_and(|block| (block.to_native() >> (index % 32)) & 1 == 1)
+    }
+
+    /// Requires `u < v`.  Panics otherwise.
+    pub fn get_edge(&self, u: usize, v: usize) -> bool {
+        assert!(u < self.get_vertex_count());
+        assert!(v < self.get_vertex_count());
+        assert!(u < v);
+        self.get_bit(unchecked_get_index_from_row_column(
+            u,
+            v,
+            self.get_vertex_count(),
+        ))
+    }
+
+    /// Iterates over the edges in an order that favors CPU cache locality.
+    pub fn iter_edges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..self.get_vertex_count()).flat_map(move |u| self.iter_children(u).map(move |v| (u, v)))
+    }
+
+    /// Iterates over vertices `v` such that there's an edge `(u, v)` in the
+    /// DAG.
+    pub fn iter_children(&self, u: usize) -> impl Iterator<Item = usize> + '_ {
+        assert!(u < self.get_vertex_count());
+        let vertex_count = self.get_vertex_count();
+        // Row `u` occupies consecutive bits.
+        let start = unchecked_get_index_from_row_column(u, u + 1, vertex_count);
+        ((u + 1)..vertex_count)
+            .zip(start..)
+            .filter(move |(_, index)| self.get_bit(*index))
+            .map(|(v, _)| v)
+    }
+
+    /// Deserializes the whole graph.
+    pub fn to_dag(&self) -> DirectedAcyclicGraph {
+        DirectedAcyclicGraph::from_edges_iter(self.get_vertex_count(), self.iter_edges())
+    }
+}
+
+/// Serializes `dag` into bytes that can be later passed to [`access`].
+pub fn to_bytes(dag: &DirectedAcyclicGraph) -> Result<AlignedVec, rancor::Error> {
+    rkyv::to_bytes::<rancor::Error>(&ArchivableDirectedAcyclicGraph::from(dag))
+}
+
+// The archive is well-formed but its block count doesn't match its vertex
+// count.
+#[derive(Debug)]
+struct WrongBlockCount {
+    vertex_count: u64,
+    block_count: usize,
+}
+
+impl fmt::Display for WrongBlockCount {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} blocks don't match a DAG with {} vertices",
+            self.block_count, self.vertex_count
+        )
+    }
+}
+
+impl std::error::Error for WrongBlockCount {}
+
+/// Validates `bytes` and returns a view of the archived DAG without copying
+/// or deserializing it.  Apart from the layout, the number of blocks is
+/// checked against the vertex count.
+pub fn access(bytes: &[u8]) -> Result<&ArchivedDirectedAcyclicGraph, rancor::Error> {
+    let archived = rkyv::access::<ArchivedDirectedAcyclicGraph, rancor::Error>(bytes)?;
+    let vertex_count = archived.vertex_count.to_native();
+    let expected_block_count = usize::try_from(vertex_count)
+        .ok()
+        .and_then(checked_strictly_upper_triangular_matrix_capacity)
+        .map(|capacity| capacity.div_ceil(32));
+    if expected_block_count != Some(archived.blocks.len()) {
+        return Err(rancor::Error::new(WrongBlockCount {
+            vertex_count,
+            block_count: archived.blocks.len(),
+        }));
+    }
+    Ok(archived)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prop_archive_roundtrips(dag: DirectedAcyclicGraph) -> bool {
+        let bytes = to_bytes(&dag).unwrap();
+        let archived = access(&bytes).unwrap();
+        let deserialized =
+            rkyv::deserialize::<ArchivableDirectedAcyclicGraph, rancor::Error>(archived).unwrap();
+        archived.get_vertex_count() == dag.get_vertex_count()
+            && archived.iter_edges().eq(dag.iter_edges())
+            && archived.to_dag() == dag
+            && deserialized.to_dag() == dag
+    }
+
+    #[test]
+    fn archive_roundtrips() {
+        quickcheck::QuickCheck::new()
+            .quickcheck(prop_archive_roundtrips as fn(DirectedAcyclicGraph) -> bool);
+    }
+
+    #[test]
+    fn queries_archive_in_place() {
+        let dag = crate::dag!(4; 0 => 1, 0 => 3, 2 => 3);
+        let bytes = to_bytes(&dag).unwrap();
+        let archived = access(&bytes).unwrap();
+        assert!(archived.get_edge(0, 3));
+        assert!(!archived.get_edge(1, 2));
+        assert_eq!(archived.iter_children(0).collect::<Vec<_>>(), vec![1, 3]);
+        assert!(access(&[0xff; 3]).is_err());
+    }
+
+    #[test]
+    fn rejects_wrong_block_count() {
+        for (vertex_count, blocks) in [(u64::MAX, vec![]), (40, vec![0; 3]), (4, vec![0; 2])] {
+            let archivable = ArchivableDirectedAcyclicGraph {
+                vertex_count,
+                blocks,
+            };
+            let bytes = rkyv::to_bytes::<rancor::Error>(&archivable).unwrap();
+            assert!(access(&bytes).is_err());
+        }
+    }
+}
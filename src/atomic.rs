@@ -0,0 +1,263 @@
+//! Building DAGs from multiple threads at once: either sharing a single
+//! [`AtomicDirectedAcyclicGraph`] or merging thread-local partial DAGs with a
+//! [`ConcurrentDagBuilder`].
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use crate::strictly_upper_triangular_logical_matrix::{
+    strictly_upper_triangular_matrix_capacity, unchecked_get_index_from_row_column,
+};
+use crate::{DirectedAcyclicGraph, StrictlyUpperTriangularLogicalMatrix};
+
+/// A DAG whose edges can be added and removed from multiple threads at once
+/// through a shared reference, without locking: the adjacency matrix is
+/// stored in [`AtomicU64`] words updated with single atomic operations.
+///
+/// Meant for the construction phase only.  Once all the threads are done,
+/// turn it into a regular [`DirectedAcyclicGraph`] with [`Self::into_dag`],
+/// which takes it by value so no concurrent updates can be missed.
+///
+/// ```
+/// use dograph::atomic::AtomicDirectedAcyclicGraph;
+///
+/// let dag = AtomicDirectedAcyclicGraph::empty(100);
+/// std::thread::scope(|scope| {
+///     for t in 0..4 {
+///         let dag = &dag;
+///         scope.spawn(move || {
+///             for u in (t..99).step_by(4) {
+///                 dag.set_edge(u, u + 1, true);
+///             }
+///         });
+///     }
+/// });
+/// assert_eq!(dag.into_dag().iter_edges().count(), 99);
+/// ```
+#[derive(Debug)]
+pub struct AtomicDirectedAcyclicGraph {
+    vertex_count: usize,
+    words: Vec<AtomicU64>,
+}
+
+impl AtomicDirectedAcyclicGraph {
+    pub fn empty(vertex_count: usize) -> Self {
+        let capacity = strictly_upper_triangular_matrix_capacity(vertex_count);
+        Self {
+            vertex_count,
+            words: (0..capacity.div_ceil(64))
+                .map(|_| AtomicU64::new(0))
+                .collect(),
+        }
+    }
+
+    #[inline]
+    pub fn get_vertex_count(&self) -> usize {
+        self.vertex_count
+    }
+
+    #[inline]
+    fn get_word_and_mask(&self, u: usize, v: usize) -> (&AtomicU64, u64) {
+        assert!(u < self.vertex_count);
+        assert!(v < self.vertex_count);
+        assert!(u < v);
+        let index = unchecked_get_index_from_row_column(u, v, self.vertex_count);
+        (&self.words[index / 64], 1 << (index % 64))
+    }
+
+    /// Requires `u < v`.  Panics otherwise.
+    pub fn get_edge(&self, u: usize, v: usize) -> bool {
+        let (word, mask) = self.get_word_and_mask(u, v);
+        word.load(Ordering::Relaxed) & mask != 0
+    }
+
+    /// Requires `u < v`.  Panics otherwise.  Returns the previous value.
+    /// Safe to call from many threads at once, also for edges sharing a word.
+    pub fn set_edge(&self, u: usize, v: usize, exists: bool) -> bool {
+        let (word, mask) = self.get_word_and_mask(u, v);
+        let previous = if exists {
+            word.fetch_or(mask, Ordering::Relaxed)
+        } else {
+            word.fetch_and(!mask, Ordering::Relaxed)
+        };
+        previous & mask != 0
+    }
+
+    /// Converts into a regular DAG.  As `self` is taken by value, all the
+    /// threads updating it must have finished (e.g. been joined), which also
+    /// makes all their updates visible.
+    pub fn into_dag(self) -> DirectedAcyclicGraph {
+        let mut matrix = StrictlyUpperTriangularLogicalMatrix::zeroed(self.vertex_count);
+        let words = self.words.into_iter().flat_map(|word| {
+            let word = word.into_inner();
+            [word as u32, (word >> 32) as u32]
+        });
+        matrix.with_blocks_mut(|blocks| {
+            for (block, word) in blocks.iter_mut().zip(words) {
+                *block = word;
+            }
+        });
+        DirectedAcyclicGraph::from_adjacency_matrix(matrix)
+    }
+}
+
+impl From<&DirectedAcyclicGraph> for AtomicDirectedAcyclicGraph {
+    fn from(dag: &DirectedAcyclicGraph) -> Self {
+        let result = Self::empty(dag.get_vertex_count());
+        for (u, v) in dag.iter_edges() {
+            result.set_edge(u, v, true);
+        }
+        result
+    }
+}
+
+/// Builds a DAG from multiple threads, each accumulating edges into its own
+/// [`PartialDag`] without any synchronization.  Partial DAGs are merged into
+/// the result with word-wise ORs when dropped, so the only contention is one
+/// short lock per partial DAG.
+///
+/// Compared to [`AtomicDirectedAcyclicGraph`], every thread needs memory for a
+/// whole adjacency matrix but touching shared cache lines is avoided.
+///
+/// ```
+/// use dograph::atomic::ConcurrentDagBuilder;
+///
+/// let builder = ConcurrentDagBuilder::new(100);
+/// std::thread::scope(|scope| {
+///     for t in 0..4 {
+///         let builder = &builder;
+///         scope.spawn(move || {
+///             let mut partial = builder.partial();
+///             for u in (t..99).step_by(4) {
+///                 partial.set_edge(u, u + 1, true);
+///             }
+///         });
+///     }
+/// });
+/// assert_eq!(builder.build().iter_edges().count(), 99);
+/// ```
+#[derive(Debug)]
+pub struct ConcurrentDagBuilder {
+    vertex_count: usize,
+    merged: Mutex<StrictlyUpperTriangularLogicalMatrix>,
+}
+
+impl ConcurrentDagBuilder {
+    pub fn new(vertex_count: usize) -> Self {
+        Self {
+            vertex_count,
+            merged: Mutex::new(StrictlyUpperTriangularLogicalMatrix::zeroed(vertex_count)),
+        }
+    }
+
+    /// A new empty partial DAG that gets merged into the result when dropped.
+    pub fn partial(&self) -> PartialDag<'_> {
+        PartialDag {
+            builder: self,
+            dag: DirectedAcyclicGraph::empty(self.vertex_count),
+        }
+    }
+
+    /// The union of all the partial DAGs.  Taking `self` by value ensures all
+    /// of them have been dropped, i.e. merged.
+    pub fn build(self) -> DirectedAcyclicGraph {
+        DirectedAcyclicGraph::from_adjacency_matrix(self.merged.into_inner().unwrap())
+    }
+}
+
+/// A thread-local part of a DAG being built by a [`ConcurrentDagBuilder`].
+/// Derefs to a [`DirectedAcyclicGraph`] holding just the edges added through
+/// it.  Edges can only be changed through [`PartialDag::set_edge`], so that the
+/// size always matches the builder's.
+#[derive(Debug)]
+pub struct PartialDag<'a> {
+    builder: &'a ConcurrentDagBuilder,
+    dag: DirectedAcyclicGraph,
+}
+
+impl std::ops::Deref for PartialDag<'_> {
+    type Target = DirectedAcyclicGraph;
+
+    fn deref(&self) -> &Self::Target {
+        &self.dag
+    }
+}
+
+impl PartialDag<'_> {
+    /// Requires `u < v`.  Panics otherwise.
+    pub fn set_edge(&mut self, u: usize, v: usize, exists: bool) {
+        self.dag.set_edge(u, v, exists);
+    }
+}
+
+impl Drop for PartialDag<'_> {
+    fn drop(&mut self) {
+        let dag = std::mem::replace(&mut self.dag, DirectedAcyclicGraph::empty(0));
+        let matrix = dag.into_adjacency_matrix();
+        // Poisoning is harmless here: all that ever happens to the merged
+        // matrix is ORing more edges in.
+        let mut merged = match self.builder.merged.lock() {
+            Ok(merged) => merged,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        merged.union_with(&matrix);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prop_atomic_roundtrips(dag: DirectedAcyclicGraph) -> bool {
+        AtomicDirectedAcyclicGraph::from(&dag).into_dag() == dag
+    }
+
+    #[test]
+    fn atomic_roundtrips() {
+        quickcheck::QuickCheck::new()
+            .quickcheck(prop_atomic_roundtrips as fn(DirectedAcyclicGraph) -> bool);
+    }
+
+    #[test]
+    fn concurrent_updates_sharing_words() {
+        let dag = AtomicDirectedAcyclicGraph::empty(40);
+        std::thread::scope(|scope| {
+            for t in 0..8 {
+                let dag = &dag;
+                scope.spawn(move || {
+                    for u in 0..40 {
+                        for v in ((u + 1)..40).filter(|v| (u + v) % 8 == t) {
+                            assert!(!dag.set_edge(u, v, true));
+                        }
+                    }
+                });
+            }
+        });
+        assert!(dag.set_edge(0, 39, false));
+        assert!(!dag.get_edge(0, 39));
+        assert_eq!(dag.into_dag().iter_edges().count(), 40 * 39 / 2 - 1);
+    }
+
+    fn prop_partial_dags_merge_into_union(dag: DirectedAcyclicGraph) -> bool {
+        let builder = ConcurrentDagBuilder::new(dag.get_vertex_count());
+        let edges: Vec<(usize, usize)> = dag.iter_edges().collect();
+        std::thread::scope(|scope| {
+            for chunk in edges.chunks(7) {
+                let builder = &builder;
+                scope.spawn(move || {
+                    let mut partial = builder.partial();
+                    for &(u, v) in chunk {
+                        partial.set_edge(u, v, true);
+                    }
+                });
+            }
+        });
+        builder.build() == dag
+    }
+
+    #[test]
+    fn partial_dags_merge_into_union() {
+        quickcheck::QuickCheck::new()
+            .quickcheck(prop_partial_dags_merge_into_union as fn(DirectedAcyclicGraph) -> bool);
+    }
+}
@@ -0,0 +1,268 @@
+use fixedbitset::FixedBitSet;
+
+use crate::algorithm::get_descendants;
+use crate::DirectedAcyclicGraph;
+
+/// Lazily evaluates a value for every vertex of a DAG from the values of its
+/// parents, memoizing the results.
+///
+/// `compute(vertex, parent_values)` gets the values of the parents of `vertex`
+/// in increasing parent order.  Once some input changes (e.g. the external data
+/// a vertex reads from), call [`Dataflow::invalidate`] on the affected vertex
+/// and only it and its descendants are recomputed, and only when their values
+/// are asked for.
+pub struct Dataflow<'a, T, F> {
+    dag: &'a DirectedAcyclicGraph,
+    parents: Vec<Vec<usize>>,
+    compute: F,
+    values: Vec<Option<T>>,
+}
+
+impl<'a, T, F> Dataflow<'a, T, F>
+where
+    F: FnMut(usize, &[&T]) -> T,
+{
+    pub fn new(dag: &'a DirectedAcyclicGraph, compute: F) -> Self {
+        let mut parents: Vec<Vec<usize>> = vec![Vec::new(); dag.get_vertex_count()];
+        for (u, v) in dag.iter_edges() {
+            parents[v].push(u);
+        }
+        Self {
+            dag,
+            parents,
+            compute,
+            values: (0..dag.get_vertex_count()).map(|_| None).collect(),
+        }
+    }
+
+    /// Returns the value of `vertex`, (re)computing it and any of its stale
+    /// ancestors first if necessary.
+    pub fn get(&mut self, vertex: usize) -> &T {
+        let mut stale = FixedBitSet::with_capacity(self.dag.get_vertex_count());
+        let mut to_visit: Vec<usize> = vec![vertex];
+        while let Some(u) = to_visit.pop() {
+            if stale[u] || self.values[u].is_some() {
+                continue;
+            }
+            stale.insert(u);
+            to_visit.extend(self.parents[u].iter().copied());
+        }
+
+        // Parents are always smaller than their children, so increasing order
+        // is a topological one.
+        for u in stale.ones() {
+            let parent_values: Vec<&T> = self.parents[u]
+                .iter()
+                .map(|p| self.values[*p].as_ref().unwrap())
+                .collect();
+            let value = (self.compute)(u, &parent_values);
+            self.values[u] = Some(value);
+        }
+
+        self.values[vertex].as_ref().unwrap()
+    }
+
+    /// Returns the memoized value of `vertex` if it's up to date.
+    pub fn get_cached(&self, vertex: usize) -> Option<&T> {
+        self.values[vertex].as_ref()
+    }
+
+    /// Marks the value of `vertex` and of all its descendants as stale.
+    pub fn invalidate(&mut self, vertex: usize) {
+        let mut to_visit: Vec<usize> = vec![vertex];
+        while let Some(u) = to_visit.pop() {
+            // Descendants of a stale vertex are stale already.
+            if self.values[u].take().is_some() || u == vertex {
+                to_visit.extend(self.dag.iter_children(u));
+            }
+        }
+    }
+}
+
+/// Computes a value for every vertex of a DAG in dependency order: vertices
+/// without parents get `init_source(vertex)`, the other ones `step(vertex,
+/// parent_values)` where `parent_values` are the values already computed for
+/// the parents, in increasing parent order.  Returns a mapping: vertex ->
+/// value.
+///
+/// This is the eager counterpart of [`Dataflow`] and the general shape of
+/// most dynamic programming algorithms over DAGs, e.g. longest paths or path
+/// counts.
+pub fn fold_topological<T, S, F>(
+    dag: &DirectedAcyclicGraph,
+    mut init_source: S,
+    mut step: F,
+) -> Vec<T>
+where
+    S: FnMut(usize) -> T,
+    F: FnMut(usize, &[&T]) -> T,
+{
+    let mut parents: Vec<Vec<usize>> = vec![Vec::new(); dag.get_vertex_count()];
+    for (u, v) in dag.iter_edges() {
+        parents[v].push(u);
+    }
+    let mut values: Vec<T> = Vec::with_capacity(dag.get_vertex_count());
+    // Parents are always smaller, so they're computed first.
+    for (u, u_parents) in parents.iter().enumerate() {
+        let value = if u_parents.is_empty() {
+            init_source(u)
+        } else {
+            let parent_values: Vec<&T> = u_parents.iter().map(|p| &values[*p]).collect();
+            step(u, &parent_values)
+        };
+        values.push(value);
+    }
+    values
+}
+
+/// Computes a mapping: vertex -> `combine` of the `values` of all of its
+/// descendants (not including itself), starting from `identity`.  `combine`
+/// has to be associative and commutative, e.g. a sum.
+///
+/// The descendant sets are computed once with a bit-parallel pass over the
+/// vertices in reverse topological order and then each aggregate takes time
+/// proportional to the number of descendants.  If `combine` is also
+/// idempotent, [`get_descendant_aggregates_idempotent`] is faster.
+pub fn get_descendant_aggregates<T, F>(
+    dag: &DirectedAcyclicGraph,
+    values: &[T],
+    identity: T,
+    combine: F,
+) -> Vec<T>
+where
+    T: Clone,
+    F: Fn(&T, &T) -> T,
+{
+    assert_eq!(values.len(), dag.get_vertex_count());
+    get_descendants(dag)
+        .iter()
+        .map(|descendants| {
+            descendants.ones().fold(identity.clone(), |aggregate, v| {
+                combine(&aggregate, &values[v])
+            })
+        })
+        .collect()
+}
+
+/// Same as [`get_descendant_aggregates`] but for `combine` that's also
+/// idempotent (`combine(x, x) == x`), e.g. a maximum or a set union.  Then the
+/// aggregate of a vertex can be computed from the aggregates of its children
+/// even though their descendant sets overlap, in `O(|V| + |E|)` calls to
+/// `combine`.
+pub fn get_descendant_aggregates_idempotent<T, F>(
+    dag: &DirectedAcyclicGraph,
+    values: &[T],
+    identity: T,
+    combine: F,
+) -> Vec<T>
+where
+    T: Clone,
+    F: Fn(&T, &T) -> T,
+{
+    assert_eq!(values.len(), dag.get_vertex_count());
+    let mut result: Vec<T> = vec![identity; dag.get_vertex_count()];
+    // Children are always bigger, so they're computed first.
+    for u in (0..dag.get_vertex_count()).rev() {
+        let aggregate = dag
+            .iter_children(u)
+            .fold(result[u].clone(), |aggregate, v| {
+                combine(&combine(&aggregate, &values[v]), &result[v])
+            });
+        result[u] = aggregate;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::{Cell, RefCell};
+
+    #[test]
+    fn recomputes_only_invalidated_vertices() {
+        //  0   1
+        //   \ / \
+        //    2   3
+        let dag = crate::dag!(4; 0 => 2, 1 => 2, 1 => 3);
+        let inputs = RefCell::new(vec![1, 10, 0, 0]);
+        let evaluations = Cell::new(0);
+        let mut dataflow = Dataflow::new(&dag, |u, parent_values: &[&i32]| {
+            evaluations.set(evaluations.get() + 1);
+            inputs.borrow()[u] + parent_values.iter().copied().sum::<i32>()
+        });
+
+        assert_eq!(*dataflow.get(2), 11);
+        assert_eq!(evaluations.get(), 3);
+        assert_eq!(dataflow.get_cached(3), None);
+        assert_eq!(*dataflow.get(3), 10);
+        assert_eq!(evaluations.get(), 4);
+
+        inputs.borrow_mut()[0] = 5;
+        dataflow.invalidate(0);
+        assert_eq!(dataflow.get_cached(1), Some(&10));
+        assert_eq!(dataflow.get_cached(2), None);
+        assert_eq!(*dataflow.get(2), 15);
+        assert_eq!(evaluations.get(), 6);
+    }
+
+    fn prop_fold_topological_agrees_with_dataflow(dag: DirectedAcyclicGraph) -> bool {
+        // Number of paths from the sources.
+        let folded: Vec<u128> = fold_topological(
+            &dag,
+            |_| 1,
+            |_, parent_values| parent_values.iter().copied().sum(),
+        );
+        let mut dataflow = Dataflow::new(&dag, |_, parent_values: &[&u128]| {
+            if parent_values.is_empty() {
+                1
+            } else {
+                parent_values.iter().copied().sum()
+            }
+        });
+        (0..dag.get_vertex_count()).all(|u| *dataflow.get(u) == folded[u])
+    }
+
+    #[test]
+    fn fold_topological_agrees_with_dataflow() {
+        quickcheck::QuickCheck::new()
+            .rng(quickcheck::Gen::new(30))
+            .quickcheck(
+                prop_fold_topological_agrees_with_dataflow as fn(DirectedAcyclicGraph) -> bool,
+            );
+    }
+
+    #[test]
+    fn descendant_aggregates_of_diamond() {
+        //    0
+        //   / \
+        //  1   2
+        //   \ /
+        //    3
+        let dag = crate::dag!(4; 0 => 1, 0 => 2, 1 => 3, 2 => 3);
+        let values = [1, 10, 100, 1000];
+        // 3 is counted only once for 0.
+        assert_eq!(
+            get_descendant_aggregates(&dag, &values, 0, |a, b| a + b),
+            vec![1110, 1000, 1000, 0]
+        );
+        assert_eq!(
+            get_descendant_aggregates_idempotent(&dag, &values, 0, |a, b| *std::cmp::max(a, b)),
+            vec![1000, 1000, 1000, 0]
+        );
+    }
+
+    fn prop_descendant_aggregates_agree(dag: DirectedAcyclicGraph) -> bool {
+        let values: Vec<usize> = (0..dag.get_vertex_count())
+            .map(|u| (u * 7919) % 101)
+            .collect();
+        get_descendant_aggregates(&dag, &values, 0, |a, b| *std::cmp::max(a, b))
+            == get_descendant_aggregates_idempotent(&dag, &values, 0, |a, b| *std::cmp::max(a, b))
+    }
+
+    #[test]
+    fn descendant_aggregates_agree() {
+        quickcheck::QuickCheck::new()
+            .quickcheck(prop_descendant_aggregates_agree as fn(DirectedAcyclicGraph) -> bool);
+    }
+}
@@ -0,0 +1,128 @@
+//! The [digraph6](https://users.cecs.anu.edu.au/~bdm/data/formats.txt) text
+//! format of nauty's `showg` and `directg`: `&`, the vertex count and the
+//! bits of the full adjacency matrix in row-major order, 6 bits per printable
+//! character.
+
+/// An error returned when decoding digraph6.  See
+/// [`crate::DirectedGraph::from_digraph6`] and
+/// [`crate::DirectedAcyclicGraph::from_digraph6`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Digraph6Error {
+    /// The input doesn't start with `&`.
+    MissingHeader,
+    /// The byte at `position` isn't one of the 64 characters `?` to `~`.
+    InvalidByte { position: usize, byte: u8 },
+    /// There are `len` bytes after the vertex count instead of `expected`.
+    WrongLength { expected: usize, len: usize },
+    /// The edge `(u, v)` doesn't go from a smaller to a bigger vertex, so the
+    /// graph can't be decoded as a DAG as it is.
+    NotStrictlyUpper { u: usize, v: usize },
+}
+
+impl std::fmt::Display for Digraph6Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Digraph6Error::MissingHeader => write!(f, "digraph6 must start with '&'"),
+            Digraph6Error::InvalidByte { position, byte } => {
+                write!(f, "invalid byte {:#04x} at position {}", byte, position)
+            }
+            Digraph6Error::WrongLength { expected, len } => {
+                write!(f, "expected {} bytes of edges, got {}", expected, len)
+            }
+            Digraph6Error::NotStrictlyUpper { u, v } => {
+                write!(f, "edge ({}, {}) is not strictly upper triangular", u, v)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Digraph6Error {}
+
+const BIAS: u8 = 63;
+
+/// `has_edge(u, v)` is queried for every pair, including `u == v`.
+pub(crate) fn encode<F: Fn(usize, usize) -> bool>(vertex_count: usize, has_edge: F) -> String {
+    let mut result = String::from("&");
+    if vertex_count <= 62 {
+        result.push((vertex_count as u8 + BIAS) as char);
+    } else {
+        let digits = if vertex_count <= 258047 {
+            result.push('~');
+            3
+        } else {
+            result.push_str("~~");
+            6
+        };
+        for digit in (0..digits).rev() {
+            result.push((((vertex_count >> (6 * digit)) & 0x3f) as u8 + BIAS) as char);
+        }
+    }
+    let mut group = 0;
+    let mut group_len = 0;
+    for u in 0..vertex_count {
+        for v in 0..vertex_count {
+            group = (group << 1) | u8::from(has_edge(u, v));
+            group_len += 1;
+            if group_len == 6 {
+                result.push((group + BIAS) as char);
+                group = 0;
+                group_len = 0;
+            }
+        }
+    }
+    if group_len > 0 {
+        result.push(((group << (6 - group_len)) + BIAS) as char);
+    }
+    result
+}
+
+/// Returns the vertex count along with the edges in row-major order.  A
+/// single trailing newline is allowed.
+pub(crate) fn decode(input: &str) -> Result<(usize, Vec<(usize, usize)>), Digraph6Error> {
+    let bytes = input.strip_suffix('\n').unwrap_or(input).as_bytes();
+    let Some((b'&', rest)) = bytes.split_first() else {
+        return Err(Digraph6Error::MissingHeader);
+    };
+    for (i, byte) in rest.iter().enumerate() {
+        if !(BIAS..=BIAS + 63).contains(byte) {
+            return Err(Digraph6Error::InvalidByte {
+                position: i + 1,
+                byte: *byte,
+            });
+        }
+    }
+    let value = |bytes: &[u8]| {
+        bytes
+            .iter()
+            .fold(0, |value, byte| (value << 6) | usize::from(byte - BIAS))
+    };
+    let (vertex_count, data) = match rest {
+        [b'~', b'~', size @ ..] if size.len() >= 6 => (value(&size[..6]), &size[6..]),
+        [b'~', size @ ..] if size.len() >= 3 => (value(&size[..3]), &size[3..]),
+        [size, data @ ..] => (value(&[*size]), data),
+        [] => {
+            return Err(Digraph6Error::WrongLength {
+                expected: 1,
+                len: 0,
+            })
+        }
+    };
+    let bit_count = vertex_count
+        .checked_mul(vertex_count)
+        .ok_or(Digraph6Error::WrongLength {
+            expected: usize::MAX,
+            len: data.len(),
+        })?;
+    let expected = bit_count.div_ceil(6);
+    if data.len() != expected {
+        return Err(Digraph6Error::WrongLength {
+            expected,
+            len: data.len(),
+        });
+    }
+    let edges = (0..bit_count)
+        .filter(|bit| ((data[bit / 6] - BIAS) >> (5 - bit % 6)) & 1 == 1)
+        .map(|bit| (bit / vertex_count, bit % vertex_count))
+        .collect();
+    Ok((vertex_count, edges))
+}
@@ -0,0 +1,326 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use fixedbitset::FixedBitSet;
+
+use crate::traversal::count_incoming_edges;
+use crate::{digraph6, CycleError, Digraph6Error, DirectedAcyclicGraph};
+
+/// A general, possibly cyclic, directed graph with vertices `0..vertex_count`
+/// represented as a full square adjacency matrix stored in a bit set.  Self
+/// loops are allowed.
+///
+/// Meant as an on-ramp for data that isn't known to be acyclic: check it with
+/// [`DirectedGraph::find_cycle`] or
+/// [`DirectedGraph::get_strongly_connected_components`] and convert it with
+/// [`DirectedGraph::to_dag`].
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct DirectedGraph {
+    vertex_count: usize,
+    // Row-major: bit `u * vertex_count + v` is the edge `(u, v)`.
+    matrix: FixedBitSet,
+}
+
+impl std::fmt::Debug for DirectedGraph {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let edges: Vec<(usize, usize)> = self.iter_edges().collect();
+        write!(
+            f,
+            "DirectedGraph::from_edges_iter({}, vec!{:?}.into_iter())",
+            self.vertex_count, edges
+        )
+    }
+}
+
+impl DirectedGraph {
+    /// Panics if `vertex_count` is so big that the number of matrix elements
+    /// would overflow `usize`.  See [`Self::try_empty`] for a non-panicking
+    /// variant.
+    pub fn empty(vertex_count: usize) -> Self {
+        Self::try_empty(vertex_count)
+            .expect("the adjacency matrix of a graph that big would overflow usize")
+    }
+
+    /// Same as [`Self::empty`] but returns `None` instead of panicking if
+    /// `vertex_count` is too big for the adjacency matrix to be addressable.
+    pub fn try_empty(vertex_count: usize) -> Option<Self> {
+        Some(Self {
+            vertex_count,
+            matrix: FixedBitSet::with_capacity(vertex_count.checked_mul(vertex_count)?),
+        })
+    }
+
+    /// Requires `u < vertex_count && v < vertex_count` for every edge `(u, v)`
+    /// in `edges`.  Panics otherwise.
+    pub fn from_edges_iter<I: Iterator<Item = (usize, usize)>>(
+        vertex_count: usize,
+        edges: I,
+    ) -> Self {
+        let mut graph = Self::empty(vertex_count);
+        for (u, v) in edges {
+            graph.set_edge(u, v, true);
+        }
+        graph
+    }
+
+    #[inline]
+    pub fn get_vertex_count(&self) -> usize {
+        self.vertex_count
+    }
+
+    #[inline]
+    fn get_index(&self, u: usize, v: usize) -> usize {
+        assert!(u < self.vertex_count);
+        assert!(v < self.vertex_count);
+        u * self.vertex_count + v
+    }
+
+    pub fn get_edge(&self, u: usize, v: usize) -> bool {
+        self.matrix[self.get_index(u, v)]
+    }
+
+    pub fn set_edge(&mut self, u: usize, v: usize, exists: bool) {
+        let index = self.get_index(u, v);
+        self.matrix.set(index, exists);
+    }
+
+    /// Iterates over the edges in row-major order.
+    pub fn iter_edges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.matrix
+            .ones()
+            .map(|index| (index / self.vertex_count, index % self.vertex_count))
+    }
+
+    /// Iterates over vertices `v` such that there's an edge `(u, v)`.
+    pub fn iter_children(&self, u: usize) -> impl Iterator<Item = usize> + '_ {
+        assert!(u < self.vertex_count);
+        (0..self.vertex_count).filter(move |&v| self.matrix[u * self.vertex_count + v])
+    }
+
+    /// Encodes the graph in the
+    /// [digraph6](https://users.cecs.anu.edu.au/~bdm/data/formats.txt) format
+    /// used by nauty, without a trailing newline.
+    pub fn to_digraph6(&self) -> String {
+        digraph6::encode(self.vertex_count, |u, v| self.get_edge(u, v))
+    }
+
+    /// Decodes a graph from [digraph6](Self::to_digraph6).
+    pub fn from_digraph6(input: &str) -> Result<Self, Digraph6Error> {
+        let (vertex_count, edges) = digraph6::decode(input)?;
+        Ok(Self::from_edges_iter(vertex_count, edges.into_iter()))
+    }
+
+    /// Returns the vertices of some cycle in order, e.g. `[a, b, c]` for `a ->
+    /// b -> c -> a`, or `None` if the graph is acyclic.
+    pub fn find_cycle(&self) -> Option<Vec<usize>> {
+        // 0: not visited yet, 1: on the DFS stack, 2: done.
+        let mut state: Vec<u8> = vec![0; self.vertex_count];
+        // (vertex, the next child candidate to look at)
+        let mut stack: Vec<(usize, usize)> = Vec::new();
+        for root in 0..self.vertex_count {
+            if state[root] != 0 {
+                continue;
+            }
+            state[root] = 1;
+            stack.push((root, 0));
+            while let Some((u, next)) = stack.last_mut() {
+                let u = *u;
+                match (*next..self.vertex_count).find(|&v| self.get_edge(u, v)) {
+                    Some(v) => {
+                        *next = v + 1;
+                        match state[v] {
+                            0 => {
+                                state[v] = 1;
+                                stack.push((v, 0));
+                            }
+                            1 => {
+                                let start = stack.iter().position(|(w, _)| *w == v).unwrap();
+                                return Some(stack[start..].iter().map(|(w, _)| *w).collect());
+                            }
+                            _ => {}
+                        }
+                    }
+                    None => {
+                        state[u] = 2;
+                        stack.pop();
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    pub fn is_acyclic(&self) -> bool {
+        self.find_cycle().is_none()
+    }
+
+    /// Computes the strongly connected components with Tarjan's algorithm.
+    /// The components are in a topological order: edges between different
+    /// components only go from earlier to later ones.  Vertices within a
+    /// component are sorted.
+    pub fn get_strongly_connected_components(&self) -> Vec<Vec<usize>> {
+        let vertex_count = self.vertex_count;
+        let mut index: Vec<usize> = vec![usize::MAX; vertex_count];
+        let mut low_link: Vec<usize> = vec![0; vertex_count];
+        let mut on_stack = FixedBitSet::with_capacity(vertex_count);
+        let mut component_stack: Vec<usize> = Vec::new();
+        let mut components: Vec<Vec<usize>> = Vec::new();
+        let mut next_index = 0;
+        // (vertex, the next child candidate to look at)
+        let mut dfs_stack: Vec<(usize, usize)> = Vec::new();
+        for root in 0..vertex_count {
+            if index[root] != usize::MAX {
+                continue;
+            }
+            dfs_stack.push((root, 0));
+            index[root] = next_index;
+            low_link[root] = next_index;
+            next_index += 1;
+            component_stack.push(root);
+            on_stack.insert(root);
+            while let Some((u, next)) = dfs_stack.last_mut() {
+                let u = *u;
+                match (*next..vertex_count).find(|&v| self.get_edge(u, v)) {
+                    Some(v) => {
+                        *next = v + 1;
+                        if index[v] == usize::MAX {
+                            index[v] = next_index;
+                            low_link[v] = next_index;
+                            next_index += 1;
+                            component_stack.push(v);
+                            on_stack.insert(v);
+                            dfs_stack.push((v, 0));
+                        } else if on_stack[v] {
+                            low_link[u] = std::cmp::min(low_link[u], index[v]);
+                        }
+                    }
+                    None => {
+                        dfs_stack.pop();
+                        if let Some((parent, _)) = dfs_stack.last() {
+                            low_link[*parent] = std::cmp::min(low_link[*parent], low_link[u]);
+                        }
+                        if low_link[u] == index[u] {
+                            let mut component: Vec<usize> = Vec::new();
+                            loop {
+                                let w = component_stack.pop().unwrap();
+                                on_stack.set(w, false);
+                                component.push(w);
+                                if w == u {
+                                    break;
+                                }
+                            }
+                            component.sort_unstable();
+                            components.push(component);
+                        }
+                    }
+                }
+            }
+        }
+        // Tarjan's algorithm finds sink components first.
+        components.reverse();
+        components
+    }
+
+    /// Converts into a [`DirectedAcyclicGraph`] by renumbering the vertices
+    /// topologically, preferring smaller vertices first, so that a graph that
+    /// already satisfies `u < v` for every edge keeps its numbering.  Returns
+    /// the DAG along with the mapping: vertex -> DAG vertex, or an edge lying
+    /// on a cycle if there's one.
+    pub fn to_dag(&self) -> Result<(DirectedAcyclicGraph, Vec<usize>), CycleError> {
+        let vertex_count = self.vertex_count;
+        let mut incoming_edges_count = count_incoming_edges(vertex_count, self.iter_edges());
+        let mut ready: BinaryHeap<Reverse<usize>> = (0..vertex_count)
+            .filter(|&v| incoming_edges_count[v] == 0)
+            .map(Reverse)
+            .collect();
+        let mut mapping: Vec<usize> = vec![usize::MAX; vertex_count];
+        let mut position = 0;
+        while let Some(Reverse(u)) = ready.pop() {
+            mapping[u] = position;
+            position += 1;
+            for v in self.iter_children(u) {
+                incoming_edges_count[v] -= 1;
+                if incoming_edges_count[v] == 0 {
+                    ready.push(Reverse(v));
+                }
+            }
+        }
+        if position < vertex_count {
+            let cycle = self.find_cycle().unwrap();
+            return Err(CycleError {
+                edge: (*cycle.last().unwrap(), cycle[0]),
+            });
+        }
+        let dag = DirectedAcyclicGraph::from_edges_iter(
+            vertex_count,
+            self.iter_edges().map(|(u, v)| (mapping[u], mapping[v])),
+        );
+        Ok((dag, mapping))
+    }
+}
+
+impl From<&DirectedAcyclicGraph> for DirectedGraph {
+    fn from(dag: &DirectedAcyclicGraph) -> Self {
+        Self::from_edges_iter(dag.get_vertex_count(), dag.iter_edges())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prop_dag_roundtrips(dag: DirectedAcyclicGraph) -> bool {
+        let graph = DirectedGraph::from(&dag);
+        let components = graph.get_strongly_connected_components();
+        graph.is_acyclic()
+            && components.len() == dag.get_vertex_count()
+            && graph.to_dag() == Ok((dag.clone(), (0..dag.get_vertex_count()).collect()))
+    }
+
+    #[test]
+    fn dag_roundtrips() {
+        quickcheck::QuickCheck::new()
+            .quickcheck(prop_dag_roundtrips as fn(DirectedAcyclicGraph) -> bool);
+    }
+
+    #[test]
+    fn try_empty_rejects_unaddressable_sizes() {
+        assert_eq!(DirectedGraph::try_empty(3), Some(DirectedGraph::empty(3)));
+        assert_eq!(DirectedGraph::try_empty(usize::MAX), None);
+    }
+
+    #[test]
+    fn cyclic_graph() {
+        // 3 -> 0 -> 1 -> 2 -> 0, 2 -> 4 -> 4
+        let graph = DirectedGraph::from_edges_iter(
+            5,
+            [(3, 0), (0, 1), (1, 2), (2, 0), (2, 4), (4, 4)].into_iter(),
+        );
+        assert_eq!(graph.find_cycle(), Some(vec![0, 1, 2]));
+        assert_eq!(
+            graph.get_strongly_connected_components(),
+            vec![vec![3], vec![0, 1, 2], vec![4]]
+        );
+        assert_eq!(graph.to_dag(), Err(CycleError { edge: (2, 0) }));
+
+        let graph = DirectedGraph::from_edges_iter(3, [(2, 1), (1, 0)].into_iter());
+        let (dag, mapping) = graph.to_dag().unwrap();
+        assert_eq!(mapping, vec![2, 1, 0]);
+        assert_eq!(dag, crate::dag!(3; 0 => 1, 1 => 2));
+    }
+
+    #[test]
+    fn digraph6_example() {
+        // The example from nauty's formats.txt.
+        let graph = DirectedGraph::from_edges_iter(5, [(0, 2), (0, 4), (3, 1), (3, 4)].into_iter());
+        assert_eq!(graph.to_digraph6(), "&DI?AO?");
+        assert_eq!(DirectedGraph::from_digraph6("&DI?AO?\n"), Ok(graph));
+        assert_eq!(
+            DirectedGraph::from_digraph6("&D!?AO?"),
+            Err(Digraph6Error::InvalidByte {
+                position: 2,
+                byte: b'!'
+            })
+        );
+    }
+}
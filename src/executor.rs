@@ -0,0 +1,326 @@
+use std::collections::VecDeque;
+#[cfg(feature = "async")]
+use std::future::Future;
+#[cfg(feature = "rayon")]
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex};
+
+#[cfg(feature = "async")]
+use futures_util::stream::{FuturesUnordered, StreamExt};
+
+use crate::traversal::{get_incoming_edges_count, get_vertices_without_incoming_edges};
+use crate::DirectedAcyclicGraph;
+
+struct State<T> {
+    ready: VecDeque<usize>,
+    incoming_edges_count: Vec<usize>,
+    finished_count: usize,
+    panicked: bool,
+    results: Vec<Option<T>>,
+}
+
+// Wakes up all the other workers if a task panics so that they don't wait
+// forever for a vertex that will never finish.
+struct PanicGuard<'a, T> {
+    state: &'a Mutex<State<T>>,
+    condvar: &'a Condvar,
+}
+
+impl<'a, T> Drop for PanicGuard<'a, T> {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            if let Ok(mut state) = self.state.lock() {
+                state.panicked = true;
+            }
+            self.condvar.notify_all();
+        }
+    }
+}
+
+/// Runs `task` once for every vertex of a DAG on a pool of `thread_count`
+/// threads, starting a vertex only after the tasks of all its parents have
+/// finished.  Independent vertices are run in parallel.  Returns a mapping:
+/// vertex -> value returned by `task`.
+///
+/// If any of the tasks panics, no further tasks are started and the panic is
+/// propagated once the running ones finish.
+pub fn execute<T, F>(dag: &DirectedAcyclicGraph, thread_count: usize, task: F) -> Vec<T>
+where
+    T: Send,
+    F: Fn(usize) -> T + Sync,
+{
+    assert!(thread_count > 0);
+    let vertex_count = dag.get_vertex_count();
+
+    let state = Mutex::new(State {
+        ready: get_vertices_without_incoming_edges(dag).into(),
+        incoming_edges_count: get_incoming_edges_count(dag),
+        finished_count: 0,
+        panicked: false,
+        results: (0..vertex_count).map(|_| None).collect(),
+    });
+    let condvar = Condvar::new();
+
+    std::thread::scope(|scope| {
+        for _ in 0..thread_count {
+            scope.spawn(|| {
+                let _guard = PanicGuard {
+                    state: &state,
+                    condvar: &condvar,
+                };
+                loop {
+                    let u = {
+                        let mut state = state.lock().unwrap();
+                        loop {
+                            if state.panicked || state.finished_count == vertex_count {
+                                return;
+                            }
+                            if let Some(u) = state.ready.pop_front() {
+                                break u;
+                            }
+                            state = condvar.wait(state).unwrap();
+                        }
+                    };
+
+                    let result = task(u);
+
+                    let mut state = state.lock().unwrap();
+                    state.results[u] = Some(result);
+                    state.finished_count += 1;
+                    for v in dag.iter_children(u) {
+                        state.incoming_edges_count[v] -= 1;
+                        if state.incoming_edges_count[v] == 0 {
+                            state.ready.push_back(v);
+                        }
+                    }
+                    condvar.notify_all();
+                }
+            });
+        }
+    });
+
+    state
+        .into_inner()
+        .unwrap()
+        .results
+        .into_iter()
+        .map(|result| result.unwrap())
+        .collect()
+}
+
+#[cfg(feature = "rayon")]
+struct ParallelState<'a, T, F> {
+    dag: &'a DirectedAcyclicGraph,
+    task: F,
+    incoming_edges_count: Vec<AtomicUsize>,
+    results: Vec<Mutex<Option<T>>>,
+}
+
+#[cfg(feature = "rayon")]
+fn spawn_vertex<'s, T, F>(scope: &rayon::Scope<'s>, state: &'s ParallelState<'s, T, F>, u: usize)
+where
+    T: Send,
+    F: Fn(usize) -> T + Sync,
+{
+    scope.spawn(move |scope| {
+        let result = (state.task)(u);
+        *state.results[u].lock().unwrap() = Some(result);
+        for v in state.dag.iter_children(u) {
+            // The last parent to finish starts the child.
+            if state.incoming_edges_count[v].fetch_sub(1, Ordering::AcqRel) == 1 {
+                spawn_vertex(scope, state, v);
+            }
+        }
+    });
+}
+
+/// Same as [`execute`] but on the global [rayon](https://crates.io/crates/rayon)
+/// thread pool (or the one [`rayon::ThreadPool::install`]ed).  Every vertex is
+/// started as soon as all its parents have finished, without waiting for
+/// whole levels of the DAG.
+///
+/// If any of the tasks panics, the panic is propagated once all the tasks that
+/// don't depend on it finish.
+#[cfg(feature = "rayon")]
+pub fn execute_parallel<T, F>(dag: &DirectedAcyclicGraph, task: F) -> Vec<T>
+where
+    T: Send,
+    F: Fn(usize) -> T + Sync,
+{
+    let vertex_count = dag.get_vertex_count();
+    let state = ParallelState {
+        dag,
+        task,
+        incoming_edges_count: get_incoming_edges_count(dag)
+            .into_iter()
+            .map(AtomicUsize::new)
+            .collect(),
+        results: (0..vertex_count).map(|_| Mutex::new(None)).collect(),
+    };
+    rayon::scope(|scope| {
+        for u in get_vertices_without_incoming_edges(dag) {
+            spawn_vertex(scope, &state, u);
+        }
+    });
+    state
+        .results
+        .into_iter()
+        .map(|result| result.into_inner().unwrap().unwrap())
+        .collect()
+}
+
+/// Drives `task(u)` futures for every vertex `u` in dependency order: a
+/// vertex's future is created only after the futures of all its parents have
+/// completed.  At most `concurrency_limit` futures are in flight at a time.
+/// Returns a mapping: vertex -> output of its future.
+///
+/// The futures are polled concurrently from within the returned future, not
+/// spawned, so it works with any async runtime and suits I/O-bound tasks.  In
+/// order to use multiple threads, spawn within `task` on the runtime of choice.
+#[cfg(feature = "async")]
+pub async fn execute_async<T, F, Fut>(
+    dag: &DirectedAcyclicGraph,
+    concurrency_limit: usize,
+    mut task: F,
+) -> Vec<T>
+where
+    F: FnMut(usize) -> Fut,
+    Fut: Future<Output = T>,
+{
+    assert!(concurrency_limit > 0);
+    let vertex_count = dag.get_vertex_count();
+
+    let mut incoming_edges_count = get_incoming_edges_count(dag);
+    let mut ready: VecDeque<usize> = get_vertices_without_incoming_edges(dag).into();
+    let mut results: Vec<Option<T>> = (0..vertex_count).map(|_| None).collect();
+    let mut running = FuturesUnordered::new();
+    loop {
+        while running.len() < concurrency_limit {
+            let Some(u) = ready.pop_front() else {
+                break;
+            };
+            let future = task(u);
+            running.push(async move { (u, future.await) });
+        }
+        let Some((u, result)) = running.next().await else {
+            break;
+        };
+        results[u] = Some(result);
+        for v in dag.iter_children(u) {
+            incoming_edges_count[v] -= 1;
+            if incoming_edges_count[v] == 0 {
+                ready.push_back(v);
+            }
+        }
+    }
+
+    results.into_iter().map(|result| result.unwrap()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn prop_execute_respects_dependencies(dag: DirectedAcyclicGraph) -> bool {
+        let clock = AtomicUsize::new(0);
+        let finished_at: Vec<AtomicUsize> = (0..dag.get_vertex_count())
+            .map(|_| AtomicUsize::new(usize::MAX))
+            .collect();
+        let started_at = execute(&dag, 4, |u| {
+            let started_at = clock.fetch_add(1, Ordering::SeqCst);
+            finished_at[u].store(clock.fetch_add(1, Ordering::SeqCst), Ordering::SeqCst);
+            started_at
+        });
+        dag.iter_edges()
+            .all(|(u, v)| finished_at[u].load(Ordering::SeqCst) < started_at[v])
+    }
+
+    #[test]
+    fn execute_respects_dependencies() {
+        quickcheck::QuickCheck::new()
+            .quickcheck(prop_execute_respects_dependencies as fn(DirectedAcyclicGraph) -> bool);
+    }
+
+    #[test]
+    #[should_panic]
+    fn execute_propagates_panics() {
+        let dag = crate::dag!(3; 0 => 1, 1 => 2);
+        execute(&dag, 2, |u| {
+            if u == 1 {
+                panic!("task failed");
+            }
+        });
+    }
+
+    #[cfg(feature = "rayon")]
+    fn prop_execute_parallel_respects_dependencies(dag: DirectedAcyclicGraph) -> bool {
+        let clock = AtomicUsize::new(0);
+        let finished_at: Vec<AtomicUsize> = (0..dag.get_vertex_count())
+            .map(|_| AtomicUsize::new(usize::MAX))
+            .collect();
+        let started_at = execute_parallel(&dag, |u| {
+            let started_at = clock.fetch_add(1, Ordering::SeqCst);
+            finished_at[u].store(clock.fetch_add(1, Ordering::SeqCst), Ordering::SeqCst);
+            started_at
+        });
+        dag.iter_edges()
+            .all(|(u, v)| finished_at[u].load(Ordering::SeqCst) < started_at[v])
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn execute_parallel_respects_dependencies() {
+        quickcheck::QuickCheck::new().quickcheck(
+            prop_execute_parallel_respects_dependencies as fn(DirectedAcyclicGraph) -> bool,
+        );
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn execute_async_respects_dependencies_and_limit() {
+        use std::cell::Cell;
+        use std::pin::Pin;
+        use std::task::{Context, Poll};
+
+        // Suspends once so that other futures get a chance to run.
+        struct YieldNow(bool);
+
+        impl Future for YieldNow {
+            type Output = ();
+
+            fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+                if self.0 {
+                    return Poll::Ready(());
+                }
+                self.0 = true;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }
+
+        let dag = crate::dag!(6; 0 => 3, 1 => 3, 3 => 4, 3 => 5);
+        let clock = Cell::new(0);
+        let running = Cell::new(0);
+        let max_running = Cell::new(0);
+        let finished_at: Vec<Cell<usize>> = (0..6).map(|_| Cell::new(usize::MAX)).collect();
+        let started_at = futures::executor::block_on(execute_async(&dag, 2, |u| {
+            let (clock, running, max_running) = (&clock, &running, &max_running);
+            let finished_at = &finished_at[u];
+            async move {
+                let started_at = clock.replace(clock.get() + 1);
+                running.set(running.get() + 1);
+                max_running.set(std::cmp::max(max_running.get(), running.get()));
+                YieldNow(false).await;
+                running.set(running.get() - 1);
+                finished_at.set(clock.replace(clock.get() + 1));
+                started_at
+            }
+        }));
+        assert!(dag
+            .iter_edges()
+            .all(|(u, v)| finished_at[u].get() < started_at[v]));
+        assert_eq!(max_running.get(), 2);
+    }
+}
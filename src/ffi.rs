@@ -0,0 +1,165 @@
+//! A C API.  See `include/dograph.h` for the declarations.  Build the shared
+//! library with `cargo rustc --release --features ffi --crate-type cdylib`.
+//!
+//! DAGs are opaque pointers created with [`dograph_new`] and destroyed with
+//! [`dograph_free`].  Functions taking vertices report invalid ones through
+//! their return value instead of panicking across the FFI boundary.
+
+use std::ffi::c_void;
+
+use crate::traversal::get_topologically_ordered_vertices;
+use crate::DirectedAcyclicGraph;
+
+/// Creates an empty DAG.  The result has to be freed with [`dograph_free`].
+/// Returns a null pointer if `vertex_count` is too big (see
+/// [`DirectedAcyclicGraph::try_empty`]).
+#[no_mangle]
+pub extern "C" fn dograph_new(vertex_count: usize) -> *mut DirectedAcyclicGraph {
+    match DirectedAcyclicGraph::try_empty(vertex_count) {
+        Some(dag) => Box::into_raw(Box::new(dag)),
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// # Safety
+///
+/// `dag` has to come from [`dograph_new`] and not be freed already.  A null
+/// pointer is a no-op.
+#[no_mangle]
+pub unsafe extern "C" fn dograph_free(dag: *mut DirectedAcyclicGraph) {
+    if !dag.is_null() {
+        drop(Box::from_raw(dag));
+    }
+}
+
+/// # Safety
+///
+/// `dag` has to be a valid pointer returned by [`dograph_new`].
+#[no_mangle]
+pub unsafe extern "C" fn dograph_get_vertex_count(dag: *const DirectedAcyclicGraph) -> usize {
+    (*dag).get_vertex_count()
+}
+
+/// Returns 1 if the edge `(u, v)` exists, 0 if it doesn't and -1 if it's not a
+/// valid edge (see [`DirectedAcyclicGraph::try_get_edge`]).
+///
+/// # Safety
+///
+/// `dag` has to be a valid pointer returned by [`dograph_new`].
+#[no_mangle]
+pub unsafe extern "C" fn dograph_get_edge(
+    dag: *const DirectedAcyclicGraph,
+    u: usize,
+    v: usize,
+) -> i32 {
+    match (*dag).try_get_edge(u, v) {
+        Ok(exists) => exists as i32,
+        Err(_) => -1,
+    }
+}
+
+/// Adds (`exists != 0`) or removes the edge `(u, v)`.  Returns 0 on success and
+/// -1 if it's not a valid edge.
+///
+/// # Safety
+///
+/// `dag` has to be a valid pointer returned by [`dograph_new`].
+#[no_mangle]
+pub unsafe extern "C" fn dograph_set_edge(
+    dag: *mut DirectedAcyclicGraph,
+    u: usize,
+    v: usize,
+    exists: i32,
+) -> i32 {
+    match (*dag).try_set_edge(u, v, exists != 0) {
+        Ok(()) => 0,
+        Err(_) => -1,
+    }
+}
+
+/// Calls `callback(u, v, user_data)` for every edge `(u, v)` in an order that
+/// favors CPU cache locality.
+///
+/// # Safety
+///
+/// `dag` has to be a valid pointer returned by [`dograph_new`].  `callback`
+/// must not modify the DAG.
+#[no_mangle]
+pub unsafe extern "C" fn dograph_for_each_edge(
+    dag: *const DirectedAcyclicGraph,
+    callback: extern "C" fn(usize, usize, *mut c_void),
+    user_data: *mut c_void,
+) {
+    for (u, v) in (*dag).iter_edges() {
+        callback(u, v, user_data);
+    }
+}
+
+/// Writes the vertices in a topological order into `output`.  Returns the
+/// number of vertices written or -1 if `output_len` is smaller than the number
+/// of vertices or `output` is null while there are vertices to write.  A DAG
+/// without vertices doesn't touch `output`, so it can be `NULL, 0`.  The
+/// result is a `ptrdiff_t` on the C side, which has the same size as `isize`.
+///
+/// # Safety
+///
+/// `dag` has to be a valid pointer returned by [`dograph_new`] and `output`
+/// has to be null or point to at least `output_len` writable elements.
+#[no_mangle]
+pub unsafe extern "C" fn dograph_topological_sort(
+    dag: *const DirectedAcyclicGraph,
+    output: *mut usize,
+    output_len: usize,
+) -> isize {
+    let dag = &*dag;
+    if dag.get_vertex_count() == 0 {
+        return 0;
+    }
+    if output.is_null() || output_len < dag.get_vertex_count() {
+        return -1;
+    }
+    let order = get_topologically_ordered_vertices(dag);
+    std::slice::from_raw_parts_mut(output, order.len()).copy_from_slice(&order);
+    order.len() as isize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    extern "C" fn collect_edge(u: usize, v: usize, user_data: *mut c_void) {
+        let edges = unsafe { &mut *(user_data as *mut Vec<(usize, usize)>) };
+        edges.push((u, v));
+    }
+
+    #[test]
+    fn c_api_roundtrip() {
+        unsafe {
+            let dag = dograph_new(3);
+            assert_eq!(dograph_get_vertex_count(dag), 3);
+            assert_eq!(dograph_set_edge(dag, 1, 2, 1), 0);
+            assert_eq!(dograph_set_edge(dag, 0, 2, 1), 0);
+            assert_eq!(dograph_set_edge(dag, 2, 1, 1), -1);
+            assert_eq!(dograph_get_edge(dag, 0, 2), 1);
+            assert_eq!(dograph_get_edge(dag, 0, 1), 0);
+            assert_eq!(dograph_get_edge(dag, 0, 3), -1);
+
+            let mut edges: Vec<(usize, usize)> = Vec::new();
+            dograph_for_each_edge(dag, collect_edge, &mut edges as *mut _ as *mut c_void);
+            assert_eq!(edges, vec![(0, 2), (1, 2)]);
+
+            let mut order = [0; 3];
+            assert_eq!(dograph_topological_sort(dag, order.as_mut_ptr(), 2), -1);
+            assert_eq!(dograph_topological_sort(dag, order.as_mut_ptr(), 3), 3);
+            assert_eq!(dograph_topological_sort(dag, std::ptr::null_mut(), 3), -1);
+            assert_eq!(order[2], 2);
+
+            dograph_free(dag);
+
+            let empty = dograph_new(0);
+            assert_eq!(dograph_topological_sort(empty, std::ptr::null_mut(), 0), 0);
+            dograph_free(empty);
+        }
+        assert!(dograph_new(usize::MAX).is_null());
+    }
+}
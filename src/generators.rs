@@ -0,0 +1,539 @@
+use std::ops::RangeInclusive;
+
+#[cfg(feature = "num-bigint")]
+use num_bigint::BigUint;
+#[cfg(feature = "num-bigint")]
+use num_traits::One;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use rand_distr::Distribution;
+
+use crate::weighted::WeightedDag;
+use crate::DirectedAcyclicGraph;
+
+/// See [`iter_all_dags`].
+pub struct AllDagsIterator {
+    next: Option<DirectedAcyclicGraph>,
+}
+
+impl Iterator for AllDagsIterator {
+    type Item = DirectedAcyclicGraph;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.next.take()?;
+        // Treat the adjacency matrix as a binary counter and increment it.
+        let vertex_count = result.get_vertex_count();
+        let mut next = result.clone();
+        for u in 0..vertex_count {
+            for v in (u + 1)..vertex_count {
+                if !next.toggle_edge(u, v) {
+                    continue;
+                }
+                self.next = Some(next);
+                return Some(result);
+            }
+        }
+        Some(result)
+    }
+}
+
+/// Iterates over every DAG with `vertex_count` vertices, i.e. over all the
+/// `2^(|V|*(|V|-1)/2)` adjacency matrices, starting from the empty DAG.  Only
+/// feasible for small `vertex_count` but perfect for exhaustive testing of
+/// small cases to complement the random ones.
+///
+/// Since edges only go from smaller to bigger vertices, these are the DAGs
+/// for which increasing vertex order is topological, not all the labeled DAGs
+/// (each of which is isomorphic to at least one of these).
+///
+/// Use [`Iterator::filter`] to narrow down the DAGs, e.g.:
+///
+/// ```
+/// use dograph::algorithm::{is_transitively_reduced, is_weakly_connected};
+/// use dograph::generators::iter_all_dags;
+///
+/// assert_eq!(iter_all_dags(4).count(), 64);
+/// assert_eq!(iter_all_dags(4).filter(is_weakly_connected).count(), 38);
+/// assert_eq!(iter_all_dags(4).filter(is_transitively_reduced).count(), 40);
+/// ```
+pub fn iter_all_dags(vertex_count: usize) -> AllDagsIterator {
+    AllDagsIterator {
+        next: Some(DirectedAcyclicGraph::empty(vertex_count)),
+    }
+}
+
+/// The number of all the labeled DAGs with `vertex_count` vertices ([OEIS
+/// A003024](https://oeis.org/A003024)): 1, 1, 3, 25, 543, 29281, ...  Useful
+/// to sanity check samplers and enumerations.
+///
+/// Computed with Robinson's recurrence `a(n) = Σ (-1)^(k+1) C(n, k) 2^(k(n-k))
+/// a(n-k)` for `k = 1..=n`, where `k` is the number of sources.
+#[cfg(feature = "num-bigint")]
+pub fn count_labeled_dags(vertex_count: usize) -> BigUint {
+    let mut counts: Vec<BigUint> = vec![BigUint::one()];
+    for n in 1..=vertex_count {
+        // The terms alternate in sign, so sum them separately to stay unsigned.
+        let mut positive = BigUint::default();
+        let mut negative = BigUint::default();
+        let mut binomial = BigUint::one();
+        for k in 1..=n {
+            binomial = binomial * (n - k + 1) / k;
+            let term = &binomial * (BigUint::one() << (k * (n - k))) * &counts[n - k];
+            if k % 2 == 1 {
+                positive += term;
+            } else {
+                negative += term;
+            }
+        }
+        counts.push(positive - negative);
+    }
+    counts.pop().unwrap()
+}
+
+/// Same as [`DirectedAcyclicGraph::random`] but also draws a weight for every
+/// edge with `weight(u, v, rng)`, e.g. `|_, _, rng| rng.gen_range(1..=10)`.
+/// Every vertex weighs `W::default()`.
+pub fn random_weighted_dag<R, D, W, F>(
+    vertex_count: usize,
+    rng: &mut R,
+    edges_distribution: D,
+    mut weight: F,
+) -> WeightedDag<W>
+where
+    R: Rng,
+    D: Distribution<bool> + Copy,
+    W: Default,
+    F: FnMut(usize, usize, &mut R) -> W,
+{
+    let dag = DirectedAcyclicGraph::random(vertex_count, rng, edges_distribution);
+    WeightedDag::from_dag(dag, |u, v| weight(u, v, rng))
+}
+
+/// A random DAG with exactly `source_count` vertices without incoming edges
+/// and exactly `sink_count` vertices without outgoing edges.  Edges allowed by
+/// these constraints are drawn from `edges_distribution` first and the missing
+/// ones are then added at random.
+///
+/// Sources and sinks are disjoint, i.e. the result has no isolated vertices.
+/// Returns `None` if that's impossible, i.e. if either count is zero or they
+/// sum up to more than `vertex_count`.
+pub fn random_dag_with_sources_and_sinks<R: Rng, D: Distribution<bool> + Copy>(
+    vertex_count: usize,
+    source_count: usize,
+    sink_count: usize,
+    rng: &mut R,
+    edges_distribution: D,
+) -> Option<DirectedAcyclicGraph> {
+    if source_count == 0 || sink_count == 0 || source_count + sink_count > vertex_count {
+        return None;
+    }
+    // Vertex 0 is always a source and the last one always a sink.  Having
+    // both ensures every other vertex can get a parent and a child.
+    let mut middle: Vec<usize> = (1..vertex_count - 1).collect();
+    middle.shuffle(rng);
+    let mut is_source = vec![false; vertex_count];
+    let mut is_sink = vec![false; vertex_count];
+    is_source[0] = true;
+    is_sink[vertex_count - 1] = true;
+    for &u in &middle[..source_count - 1] {
+        is_source[u] = true;
+    }
+    for &u in &middle[source_count - 1..source_count + sink_count - 2] {
+        is_sink[u] = true;
+    }
+
+    let mut dag = DirectedAcyclicGraph::empty(vertex_count);
+    for u in (0..vertex_count).filter(|&u| !is_sink[u]) {
+        for v in ((u + 1)..vertex_count).filter(|&v| !is_source[v]) {
+            dag.set_edge(u, v, rng.sample(edges_distribution));
+        }
+    }
+    let mut has_parent = vec![false; vertex_count];
+    for (_, v) in dag.iter_edges() {
+        has_parent[v] = true;
+    }
+    for v in (0..vertex_count).filter(|&v| !is_source[v] && !has_parent[v]) {
+        let candidates: Vec<usize> = (0..v).filter(|&u| !is_sink[u]).collect();
+        dag.set_edge(*candidates.choose(rng).unwrap(), v, true);
+    }
+    for u in (0..vertex_count).filter(|&u| !is_sink[u]) {
+        if dag.iter_children(u).next().is_some() {
+            continue;
+        }
+        let candidates: Vec<usize> = ((u + 1)..vertex_count).filter(|&v| !is_source[v]).collect();
+        dag.set_edge(u, *candidates.choose(rng).unwrap(), true);
+    }
+    Some(dag)
+}
+
+/// A random DAG whose longest path has exactly `height` edges (see
+/// [`crate::paths::get_diameter`]).  A backbone path through `height + 1`
+/// randomly chosen vertices is built first, every other vertex is assigned a
+/// random level along it and the remaining edges, drawn from
+/// `edges_distribution`, only go between increasing levels, so that no path
+/// longer than the backbone can appear.
+///
+/// Returns `None` if `height >= vertex_count`.
+pub fn random_dag_with_height<R: Rng, D: Distribution<bool> + Copy>(
+    vertex_count: usize,
+    height: usize,
+    rng: &mut R,
+    edges_distribution: D,
+) -> Option<DirectedAcyclicGraph> {
+    if height >= vertex_count {
+        return None;
+    }
+    let mut backbone: Vec<usize> =
+        rand::seq::index::sample(rng, vertex_count, height + 1).into_vec();
+    backbone.sort_unstable();
+    let mut level: Vec<usize> = (0..vertex_count)
+        .map(|_| rng.gen_range(0..=height))
+        .collect();
+    for (i, &u) in backbone.iter().enumerate() {
+        level[u] = i;
+    }
+
+    let mut dag = DirectedAcyclicGraph::empty(vertex_count);
+    for window in backbone.windows(2) {
+        dag.set_edge(window[0], window[1], true);
+    }
+    for u in 0..vertex_count {
+        for v in ((u + 1)..vertex_count).filter(|&v| level[u] < level[v]) {
+            if rng.sample(edges_distribution) {
+                dag.set_edge(u, v, true);
+            }
+        }
+    }
+    Some(dag)
+}
+
+/// A growing-network (Barabási–Albert style) random DAG.  Vertices are added
+/// one by one and each new vertex `v` gets edges from `min(k, v)` distinct
+/// existing vertices chosen with probability proportional to their degree plus
+/// one, much like a new paper citing older ones.  Produces heavy-tailed degree
+/// distributions, unlike [`DirectedAcyclicGraph::random`].
+///
+/// Edges go from the older vertex to the newer one to keep `u < v`.
+pub fn random_preferential_attachment_dag<R: Rng>(
+    vertex_count: usize,
+    k: usize,
+    rng: &mut R,
+) -> DirectedAcyclicGraph {
+    let mut dag = DirectedAcyclicGraph::empty(vertex_count);
+    let mut degrees: Vec<usize> = vec![0; vertex_count];
+    let mut chosen: Vec<usize> = Vec::with_capacity(k);
+    for v in 1..vertex_count {
+        chosen.clear();
+        let mut total: usize = degrees[..v].iter().map(|degree| degree + 1).sum();
+        for _ in 0..std::cmp::min(k, v) {
+            let mut remaining = rng.gen_range(0..total);
+            let u = (0..v)
+                .filter(|u| !chosen.contains(u))
+                .find(|&u| {
+                    if remaining <= degrees[u] {
+                        return true;
+                    }
+                    remaining -= degrees[u] + 1;
+                    false
+                })
+                .unwrap();
+            total -= degrees[u] + 1;
+            chosen.push(u);
+        }
+        for &u in &chosen {
+            dag.set_edge(u, v, true);
+            degrees[u] += 1;
+            degrees[v] += 1;
+        }
+    }
+    dag
+}
+
+/// A random two-layer DAG: vertices `0..left_count` form the first layer,
+/// `left_count..left_count + right_count` the second one and every edge goes
+/// from the first layer to the second, drawn from `edges_distribution`.
+pub fn random_bipartite_dag<R: Rng, D: Distribution<bool> + Copy>(
+    left_count: usize,
+    right_count: usize,
+    rng: &mut R,
+    edges_distribution: D,
+) -> DirectedAcyclicGraph {
+    let mut dag = DirectedAcyclicGraph::empty(left_count + right_count);
+    for u in 0..left_count {
+        for v in left_count..(left_count + right_count) {
+            dag.set_edge(u, v, rng.sample(edges_distribution));
+        }
+    }
+    dag
+}
+
+/// See [`iter_random_dags`].
+#[derive(Clone, Debug)]
+pub struct RandomDagsIterator<R, D> {
+    rng: R,
+    vertex_counts: RangeInclusive<usize>,
+    edges_distribution: D,
+}
+
+impl<R, D> RandomDagsIterator<R, D> {
+    /// The random number generator in its current state.  Cloning it (or the
+    /// whole iterator) allows resuming the stream later on.
+    pub fn get_rng(&self) -> &R {
+        &self.rng
+    }
+}
+
+impl<R: Rng, D: Distribution<bool> + Copy> Iterator for RandomDagsIterator<R, D> {
+    type Item = DirectedAcyclicGraph;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let vertex_count = self.rng.gen_range(self.vertex_counts.clone());
+        Some(DirectedAcyclicGraph::random(
+            vertex_count,
+            &mut self.rng,
+            self.edges_distribution,
+        ))
+    }
+}
+
+/// An endless stream of [`DirectedAcyclicGraph::random`] DAGs, each with a
+/// vertex count drawn uniformly from `vertex_counts`.  The stream is fully
+/// determined by the initial state of `rng`, e.g. `StdRng::seed_from_u64(seed)`.
+///
+/// ```
+/// use dograph::generators::iter_random_dags;
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let bernoulli = rand_distr::Bernoulli::new(0.3).unwrap();
+/// let dags = iter_random_dags(StdRng::seed_from_u64(42), 5..=20, bernoulli);
+/// for dag in dags.take(3) {
+///     assert!((5..=20).contains(&dag.get_vertex_count()));
+/// }
+/// ```
+pub fn iter_random_dags<R: Rng, D: Distribution<bool> + Copy>(
+    rng: R,
+    vertex_counts: RangeInclusive<usize>,
+    edges_distribution: D,
+) -> RandomDagsIterator<R, D> {
+    assert!(!vertex_counts.is_empty());
+    RandomDagsIterator {
+        rng,
+        vertex_counts,
+        edges_distribution,
+    }
+}
+
+/// A curated list of edge-case DAGs with `vertex_count` vertices, each along
+/// with a short name, meant as a starting point of a test suite:
+///
+///  * `"empty"`: no edges, i.e. the maximal antichain,
+///  * `"complete"`: all the edges, i.e. a total order,
+///  * `"path"`: `0 -> 1 -> ... -> n-1`, the transitive reduction of a total order,
+///  * `"out-star"`: `0 -> v` for every other `v`,
+///  * `"in-star"`: `u -> n-1` for every other `u`,
+///  * `"two-components"`: paths through the first and the second half,
+///  * `"complete-bipartite"`: every edge from the first half to the second one,
+///  * `"isolated-vertex"`: complete save for the last vertex, which is isolated.
+///
+/// For small `vertex_count` some of these coincide.
+pub fn get_corner_case_dags(vertex_count: usize) -> Vec<(&'static str, DirectedAcyclicGraph)> {
+    let n = vertex_count;
+    let half = n / 2;
+    let all_pairs = move || (0..n).flat_map(move |u| ((u + 1)..n).map(move |v| (u, v)));
+    vec![
+        ("empty", DirectedAcyclicGraph::empty(n)),
+        (
+            "complete",
+            DirectedAcyclicGraph::from_edges_iter(n, all_pairs()),
+        ),
+        (
+            "path",
+            DirectedAcyclicGraph::from_edges_iter(n, (1..n).map(|v| (v - 1, v))),
+        ),
+        (
+            "out-star",
+            DirectedAcyclicGraph::from_edges_iter(n, (1..n).map(|v| (0, v))),
+        ),
+        (
+            "in-star",
+            DirectedAcyclicGraph::from_edges_iter(n, (0..n.saturating_sub(1)).map(|u| (u, n - 1))),
+        ),
+        (
+            "two-components",
+            DirectedAcyclicGraph::from_edges_iter(
+                n,
+                (1..n).filter(|&v| v != half).map(|v| (v - 1, v)),
+            ),
+        ),
+        (
+            "complete-bipartite",
+            DirectedAcyclicGraph::from_edges_iter(
+                n,
+                all_pairs().filter(|&(u, v)| u < half && v >= half),
+            ),
+        ),
+        (
+            "isolated-vertex",
+            DirectedAcyclicGraph::from_edges_iter(n, all_pairs().filter(|&(_, v)| v + 1 < n)),
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::{HashMap, HashSet};
+
+    #[test]
+    fn all_dags_are_distinct() {
+        for vertex_count in 0..6 {
+            let dags: HashSet<DirectedAcyclicGraph> = iter_all_dags(vertex_count).collect();
+            assert_eq!(
+                dags.len(),
+                1 << (vertex_count * vertex_count.saturating_sub(1) / 2)
+            );
+        }
+    }
+
+    #[cfg(feature = "num-bigint")]
+    #[test]
+    fn labeled_dag_counts() {
+        let counts: Vec<BigUint> = (0..8).map(count_labeled_dags).collect();
+        assert_eq!(
+            counts,
+            [1u64, 1, 3, 25, 543, 29281, 3781503, 1138779265]
+                .into_iter()
+                .map(BigUint::from)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn random_weighted_dag_has_weight_per_edge() {
+        use rand::SeedableRng;
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(5);
+        let dag = random_weighted_dag(
+            30,
+            &mut rng,
+            rand_distr::Bernoulli::new(0.3).unwrap(),
+            |_, _, rng| rng.gen_range(1..=10),
+        );
+        assert!(dag.iter_edges().count() > 0);
+        assert!(dag
+            .iter_weighted_edges()
+            .all(|(_, _, weight)| (1..=10).contains(weight)));
+    }
+
+    #[test]
+    fn random_dag_has_requested_sources_and_sinks() {
+        use rand::SeedableRng;
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+        for (vertex_count, source_count, sink_count) in [(2, 1, 1), (10, 1, 1), (20, 5, 3)] {
+            for probability in [0.0, 0.2, 1.0] {
+                let dag = random_dag_with_sources_and_sinks(
+                    vertex_count,
+                    source_count,
+                    sink_count,
+                    &mut rng,
+                    rand_distr::Bernoulli::new(probability).unwrap(),
+                )
+                .unwrap();
+                let sources = (0..vertex_count)
+                    .filter(|&v| dag.get_parents_bitset(v).is_clear())
+                    .count();
+                let sinks = (0..vertex_count)
+                    .filter(|&u| dag.iter_children(u).next().is_none())
+                    .count();
+                assert_eq!((sources, sinks), (source_count, sink_count));
+            }
+        }
+        let bernoulli = rand_distr::Bernoulli::new(0.5).unwrap();
+        assert!(random_dag_with_sources_and_sinks(3, 2, 2, &mut rng, bernoulli).is_none());
+        assert!(random_dag_with_sources_and_sinks(3, 0, 1, &mut rng, bernoulli).is_none());
+    }
+
+    #[test]
+    fn random_dag_has_requested_height() {
+        use rand::SeedableRng;
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(4);
+        for (vertex_count, height) in [(1, 0), (10, 0), (10, 9), (30, 3), (30, 20)] {
+            for probability in [0.0, 0.3, 1.0] {
+                let bernoulli = rand_distr::Bernoulli::new(probability).unwrap();
+                let dag =
+                    random_dag_with_height(vertex_count, height, &mut rng, bernoulli).unwrap();
+                assert_eq!(crate::paths::get_diameter(&dag), height);
+            }
+        }
+        let bernoulli = rand_distr::Bernoulli::new(0.5).unwrap();
+        assert!(random_dag_with_height(3, 3, &mut rng, bernoulli).is_none());
+    }
+
+    #[test]
+    fn preferential_attachment_edge_count() {
+        use rand::SeedableRng;
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(6);
+        let dag = random_preferential_attachment_dag(100, 3, &mut rng);
+        // Vertices 1 and 2 attach to all the previous ones.
+        assert_eq!(dag.iter_edges().count(), 1 + 2 + 97 * 3);
+        assert!((3..100).all(|v| dag.get_parents_bitset(v).count_ones(..) == 3));
+        assert_eq!(
+            random_preferential_attachment_dag(5, 10, &mut rng),
+            crate::algorithm::transitive_closure(&crate::dag!(5; 0 => 1, 1 => 2, 2 => 3, 3 => 4))
+        );
+    }
+
+    #[test]
+    fn bipartite_dag_edges_cross_layers() {
+        use rand::SeedableRng;
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let dag = random_bipartite_dag(4, 6, &mut rng, rand_distr::Bernoulli::new(0.5).unwrap());
+        assert_eq!(dag.get_vertex_count(), 10);
+        assert!(dag.iter_edges().all(|(u, v)| u < 4 && v >= 4));
+        let complete =
+            random_bipartite_dag(2, 3, &mut rng, rand_distr::Bernoulli::new(1.0).unwrap());
+        assert_eq!(complete.iter_edges().count(), 6);
+    }
+
+    #[test]
+    fn random_dags_stream_resumes() {
+        use rand::SeedableRng;
+
+        let bernoulli = rand_distr::Bernoulli::new(0.4).unwrap();
+        let mut dags = iter_random_dags(rand::rngs::StdRng::seed_from_u64(8), 0..=10, bernoulli);
+        let first: Vec<DirectedAcyclicGraph> = dags.by_ref().take(5).collect();
+        let resumed = dags.clone();
+        assert_eq!(
+            dags.take(5).collect::<Vec<_>>(),
+            resumed.take(5).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            iter_random_dags(rand::rngs::StdRng::seed_from_u64(8), 0..=10, bernoulli)
+                .take(5)
+                .collect::<Vec<_>>(),
+            first
+        );
+    }
+
+    #[test]
+    fn corner_case_dags() {
+        for vertex_count in 0..6 {
+            assert_eq!(get_corner_case_dags(vertex_count).len(), 8);
+        }
+        let dags: HashMap<&str, DirectedAcyclicGraph> =
+            get_corner_case_dags(4).into_iter().collect();
+        assert_eq!(dags["complete"].iter_edges().count(), 6);
+        assert_eq!(dags["path"], crate::dag!(4; 0 => 1, 1 => 2, 2 => 3));
+        assert_eq!(dags["in-star"], crate::dag!(4; 0 => 3, 1 => 3, 2 => 3));
+        assert_eq!(dags["two-components"], crate::dag!(4; 0 => 1, 2 => 3));
+        assert!(!crate::algorithm::is_weakly_connected(
+            &dags["isolated-vertex"]
+        ));
+    }
+}
@@ -0,0 +1,554 @@
+use std::sync::OnceLock;
+
+use fixedbitset::FixedBitSet;
+
+use crate::algorithm::get_descendants;
+use crate::{CycleError, DirectedAcyclicGraph, StrictlyLowerTriangularLogicalMatrix};
+
+/// A directed graph over vertices numbered in an *arbitrary* order that
+/// maintains a topological order of its vertices under edge insertions and
+/// rejects edges that would introduce a cycle.
+///
+/// Uses the [Pearce–Kelly
+/// algorithm](https://www.doc.ic.ac.uk/~phjk/Publications/DynamicTopoSortAlg-JEA-07.pdf):
+/// inserting an edge only touches the vertices lying between its endpoints in
+/// the current order, which is usually far cheaper than re-validating the whole
+/// graph after every edit.
+///
+/// Once the graph is complete, it can be turned into a [`DirectedAcyclicGraph`]
+/// with [`IncrementalTopologicalOrder::to_dag`].
+#[derive(Clone, Debug)]
+pub struct IncrementalTopologicalOrder {
+    children: Vec<Vec<usize>>,
+    parents: Vec<Vec<usize>>,
+    // vertex -> its position in the topological order
+    position: Vec<usize>,
+    // position in the topological order -> vertex
+    vertex_at: Vec<usize>,
+}
+
+impl IncrementalTopologicalOrder {
+    pub fn new(vertex_count: usize) -> Self {
+        Self {
+            children: vec![Vec::new(); vertex_count],
+            parents: vec![Vec::new(); vertex_count],
+            position: (0..vertex_count).collect(),
+            vertex_at: (0..vertex_count).collect(),
+        }
+    }
+
+    #[inline]
+    pub fn get_vertex_count(&self) -> usize {
+        self.position.len()
+    }
+
+    /// Adds a new isolated vertex and returns it.
+    pub fn add_vertex(&mut self) -> usize {
+        let vertex = self.get_vertex_count();
+        self.children.push(Vec::new());
+        self.parents.push(Vec::new());
+        self.position.push(vertex);
+        self.vertex_at.push(vertex);
+        vertex
+    }
+
+    pub fn get_edge(&self, u: usize, v: usize) -> bool {
+        self.children[u].contains(&v)
+    }
+
+    /// Iterates over vertices `v` such that there's an edge `(u, v)`.
+    pub fn iter_children(&self, u: usize) -> impl Iterator<Item = usize> + '_ {
+        self.children[u].iter().copied()
+    }
+
+    /// Returns the position of `vertex` in the current topological order.
+    pub fn get_position(&self, vertex: usize) -> usize {
+        self.position[vertex]
+    }
+
+    /// Visit all vertices in the current topological order.
+    pub fn iter_topological(&self) -> impl Iterator<Item = usize> + '_ {
+        self.vertex_at.iter().copied()
+    }
+
+    /// Adds an edge `(u, v)` unless it would introduce a cycle, in which case
+    /// the graph is left unchanged.  Returns `Ok(true)` if the edge is new and
+    /// `Ok(false)` if it was already present.
+    pub fn try_add_edge(&mut self, u: usize, v: usize) -> Result<bool, CycleError> {
+        assert!(u < self.get_vertex_count());
+        assert!(v < self.get_vertex_count());
+        if u == v {
+            return Err(CycleError { edge: (u, v) });
+        }
+        if self.get_edge(u, v) {
+            return Ok(false);
+        }
+
+        let lower_bound = self.position[v];
+        let upper_bound = self.position[u];
+        if lower_bound < upper_bound {
+            let forward = self
+                .search_forward(v, upper_bound)
+                .ok_or(CycleError { edge: (u, v) })?;
+            let backward = self.search_backward(u, lower_bound);
+            self.reorder(backward, forward);
+        }
+
+        self.children[u].push(v);
+        self.parents[v].push(u);
+        Ok(true)
+    }
+
+    // Vertices reachable from `start` that are positioned before
+    // `upper_bound`, or None if the vertex at `upper_bound` is reachable.
+    fn search_forward(&self, start: usize, upper_bound: usize) -> Option<Vec<usize>> {
+        let mut visited = FixedBitSet::with_capacity(self.get_vertex_count());
+        let mut result: Vec<usize> = Vec::new();
+        let mut to_visit: Vec<usize> = vec![start];
+        visited.insert(start);
+        while let Some(w) = to_visit.pop() {
+            result.push(w);
+            for &x in &self.children[w] {
+                if self.position[x] == upper_bound {
+                    return None;
+                }
+                if !visited[x] && self.position[x] < upper_bound {
+                    visited.insert(x);
+                    to_visit.push(x);
+                }
+            }
+        }
+        Some(result)
+    }
+
+    // Vertices from which `start` is reachable that are positioned after
+    // `lower_bound`.
+    fn search_backward(&self, start: usize, lower_bound: usize) -> Vec<usize> {
+        let mut visited = FixedBitSet::with_capacity(self.get_vertex_count());
+        let mut result: Vec<usize> = Vec::new();
+        let mut to_visit: Vec<usize> = vec![start];
+        visited.insert(start);
+        while let Some(w) = to_visit.pop() {
+            result.push(w);
+            for &x in &self.parents[w] {
+                if !visited[x] && self.position[x] > lower_bound {
+                    visited.insert(x);
+                    to_visit.push(x);
+                }
+            }
+        }
+        result
+    }
+
+    // Moves all of `backward` before all of `forward` reusing the positions
+    // they occupy.
+    fn reorder(&mut self, mut backward: Vec<usize>, mut forward: Vec<usize>) {
+        backward.sort_by_key(|w| self.position[*w]);
+        forward.sort_by_key(|w| self.position[*w]);
+        let mut positions: Vec<usize> = backward
+            .iter()
+            .chain(forward.iter())
+            .map(|w| self.position[*w])
+            .collect();
+        positions.sort_unstable();
+        for (w, position) in backward.into_iter().chain(forward).zip(positions) {
+            self.position[w] = position;
+            self.vertex_at[position] = w;
+        }
+    }
+
+    /// Converts into a [`DirectedAcyclicGraph`] by renumbering every vertex to
+    /// its position in the current topological order.  Returns the DAG along
+    /// with that mapping: vertex -> DAG vertex.
+    pub fn to_dag(&self) -> (DirectedAcyclicGraph, Vec<usize>) {
+        let edges = (0..self.get_vertex_count()).flat_map(|u| {
+            self.children[u]
+                .iter()
+                .map(move |v| (self.position[u], self.position[*v]))
+        });
+        let dag = DirectedAcyclicGraph::from_edges_iter(self.get_vertex_count(), edges);
+        (dag, self.position.clone())
+    }
+}
+
+/// A DAG along with its transitive closure that is kept up to date as edges
+/// are inserted so that reachability queries are always `O(1)`.
+///
+/// In the spirit of [Italiano's
+/// algorithm](https://doi.org/10.1016/0304-3975(86)90098-8), inserting an edge
+/// `(u, v)` only updates the closure rows of those ancestors of `u` that could
+/// not already reach `v`, each with a single bit set union.
+#[derive(Clone, Debug)]
+pub struct DynamicTransitiveClosure {
+    dag: DirectedAcyclicGraph,
+    descendants: Vec<FixedBitSet>,
+}
+
+impl DynamicTransitiveClosure {
+    pub fn new(dag: DirectedAcyclicGraph) -> Self {
+        let descendants = get_descendants(&dag)
+            .into_iter()
+            .map(|mut descendants| {
+                descendants.grow(dag.get_vertex_count());
+                descendants
+            })
+            .collect();
+        Self { dag, descendants }
+    }
+
+    pub fn empty(vertex_count: usize) -> Self {
+        Self::new(DirectedAcyclicGraph::empty(vertex_count))
+    }
+
+    /// The DAG whose closure is being maintained.
+    pub fn get_dag(&self) -> &DirectedAcyclicGraph {
+        &self.dag
+    }
+
+    // Calls `on_update(x, old_descendants_of_x, new_descendants_of_x)` for every
+    // `x` whose closure row changes.
+    fn add_edge_with<F: FnMut(usize, &FixedBitSet, &FixedBitSet)>(
+        &mut self,
+        u: usize,
+        v: usize,
+        mut on_update: F,
+    ) {
+        self.dag.set_edge(u, v, true);
+        if self.descendants[u][v] {
+            return;
+        }
+        let mut v_descendants = self.descendants[v].clone();
+        v_descendants.insert(v);
+        for x in 0..=u {
+            if (x == u || self.descendants[x][u]) && !self.descendants[x][v] {
+                on_update(x, &self.descendants[x], &v_descendants);
+                self.descendants[x].union_with(&v_descendants);
+            }
+        }
+    }
+
+    /// Requires `u < v`.  Panics otherwise.
+    pub fn add_edge(&mut self, u: usize, v: usize) {
+        self.add_edge_with(u, v, |_, _, _| {});
+    }
+
+    /// Same as [`Self::add_edge`] but also returns the pairs `(x, y)` that
+    /// became reachable because of the new edge, i.e. the closure delta, in
+    /// the row-major order.  Empty if `v` was reachable from `u` already.
+    pub fn add_edge_with_delta(&mut self, u: usize, v: usize) -> Vec<(usize, usize)> {
+        let mut delta: Vec<(usize, usize)> = Vec::new();
+        self.add_edge_with(u, v, |x, x_descendants, v_descendants| {
+            delta.extend(v_descendants.difference(x_descendants).map(|y| (x, y)));
+        });
+        delta
+    }
+
+    /// Answers whether there's a non-empty path from `u` to `v` in `O(1)`.
+    pub fn has_path(&self, u: usize, v: usize) -> bool {
+        self.descendants[u][v]
+    }
+
+    /// The set of vertices reachable from `u` by a non-empty path.
+    pub fn get_descendants(&self, u: usize) -> &FixedBitSet {
+        &self.descendants[u]
+    }
+}
+
+/// A DAG that computes its transitive closure on the first reachability query
+/// and caches it until the next mutation that could change it.  Unlike
+/// [`DynamicTransitiveClosure`], edges can also be removed, at the cost of
+/// recomputing the closure on the next query.
+///
+/// Suited for "build once, query many times, mutate occasionally" workloads:
+/// queries are `O(1)` amortized and adding an edge between vertices already
+/// connected by a path keeps the cache.
+#[derive(Clone, Debug)]
+pub struct LazyTransitiveClosure {
+    dag: DirectedAcyclicGraph,
+    descendants: OnceLock<Vec<FixedBitSet>>,
+}
+
+impl LazyTransitiveClosure {
+    pub fn new(dag: DirectedAcyclicGraph) -> Self {
+        Self {
+            dag,
+            descendants: OnceLock::new(),
+        }
+    }
+
+    pub fn empty(vertex_count: usize) -> Self {
+        Self::new(DirectedAcyclicGraph::empty(vertex_count))
+    }
+
+    pub fn get_dag(&self) -> &DirectedAcyclicGraph {
+        &self.dag
+    }
+
+    pub fn into_dag(self) -> DirectedAcyclicGraph {
+        self.dag
+    }
+
+    /// Whether the closure is currently cached.
+    pub fn is_cached(&self) -> bool {
+        self.descendants.get().is_some()
+    }
+
+    /// Requires `u < v`.  Panics otherwise.  Invalidates the cached closure
+    /// unless the change doesn't affect reachability.
+    pub fn set_edge(&mut self, u: usize, v: usize, exists: bool) {
+        let changed = self.dag.get_edge(u, v) != exists;
+        self.dag.set_edge(u, v, exists);
+        if !changed {
+            return;
+        }
+        match self.descendants.get() {
+            Some(descendants) if exists && descendants[u][v] => {}
+            _ => {
+                self.descendants.take();
+            }
+        }
+    }
+
+    fn get_closure(&self) -> &[FixedBitSet] {
+        self.descendants.get_or_init(|| get_descendants(&self.dag))
+    }
+
+    /// Answers whether there's a non-empty path from `u` to `v`.  `O(1)` if
+    /// the closure is cached.
+    pub fn has_path(&self, u: usize, v: usize) -> bool {
+        self.get_closure()[u][v]
+    }
+
+    /// The set of vertices reachable from `u` by a non-empty path.
+    pub fn get_descendants(&self, u: usize) -> &FixedBitSet {
+        &self.get_closure()[u]
+    }
+}
+
+/// Tracks which vertices of a DAG are dirty (out of date), the core of
+/// incremental build systems and caches: marking a vertex dirty marks all its
+/// descendants too.
+///
+/// The dirty set is always closed under taking descendants, so marking stops
+/// at vertices that are already dirty and every vertex is visited at most once
+/// between cleanings, no matter how many times its ancestors get marked.
+#[derive(Clone, Debug)]
+pub struct DirtyTracker {
+    dag: DirectedAcyclicGraph,
+    // The transposed adjacency matrix, for scanning parents a word at a time.
+    parents: StrictlyLowerTriangularLogicalMatrix,
+    dirty: FixedBitSet,
+}
+
+impl DirtyTracker {
+    /// Starts with all the vertices clean.
+    pub fn new(dag: DirectedAcyclicGraph) -> Self {
+        let parents = dag.get_adjacency_matrix().transpose();
+        let dirty = FixedBitSet::with_capacity(dag.get_vertex_count());
+        Self {
+            dag,
+            parents,
+            dirty,
+        }
+    }
+
+    pub fn get_dag(&self) -> &DirectedAcyclicGraph {
+        &self.dag
+    }
+
+    pub fn is_dirty(&self, vertex: usize) -> bool {
+        self.dirty[vertex]
+    }
+
+    /// Iterates over the dirty vertices in increasing, and thus topological,
+    /// order, i.e. the order to rebuild them in.
+    pub fn iter_dirty(&self) -> impl Iterator<Item = usize> + '_ {
+        self.dirty.ones()
+    }
+
+    /// Marks `vertex` and all its descendants dirty.  Returns the vertices
+    /// that were clean before, in increasing order.
+    pub fn mark_dirty(&mut self, vertex: usize) -> Vec<usize> {
+        let mut newly_dirty: Vec<usize> = Vec::new();
+        let mut to_visit: Vec<usize> = vec![vertex];
+        while let Some(u) = to_visit.pop() {
+            if self.dirty.put(u) {
+                continue;
+            }
+            newly_dirty.push(u);
+            to_visit.extend(self.dag.iter_children(u).filter(|&v| !self.dirty[v]));
+        }
+        newly_dirty.sort_unstable();
+        newly_dirty
+    }
+
+    /// Marks `vertex` clean again, e.g. after it's been rebuilt.  Requires all
+    /// its parents to be clean, which is the case when rebuilding in the
+    /// [`Self::iter_dirty`] order.  Panics otherwise.
+    pub fn mark_clean(&mut self, vertex: usize) {
+        assert!(
+            self.parents
+                .iter_ones_at_row(vertex)
+                .all(|parent| !self.dirty[parent]),
+            "a parent of {} is dirty",
+            vertex
+        );
+        self.dirty.set(vertex, false);
+    }
+
+    /// Marks all the vertices clean.
+    pub fn clear(&mut self) {
+        self.dirty.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::{prelude::StdRng, Rng, SeedableRng};
+
+    fn is_reachable(order: &IncrementalTopologicalOrder, from: usize, to: usize) -> bool {
+        let mut visited = FixedBitSet::with_capacity(order.get_vertex_count());
+        let mut to_visit = vec![from];
+        while let Some(w) = to_visit.pop() {
+            if w == to {
+                return true;
+            }
+            if !visited[w] {
+                visited.insert(w);
+                to_visit.extend(order.iter_children(w));
+            }
+        }
+        false
+    }
+
+    #[test]
+    fn rejects_cycles() {
+        let mut order = IncrementalTopologicalOrder::new(3);
+        assert_eq!(order.try_add_edge(2, 1), Ok(true));
+        assert_eq!(order.try_add_edge(1, 0), Ok(true));
+        assert_eq!(order.try_add_edge(1, 0), Ok(false));
+        assert_eq!(order.try_add_edge(0, 2), Err(CycleError { edge: (0, 2) }));
+        assert_eq!(order.try_add_edge(1, 1), Err(CycleError { edge: (1, 1) }));
+        assert_eq!(
+            order.iter_topological().collect::<Vec<usize>>(),
+            vec![2, 1, 0]
+        );
+
+        let (dag, mapping) = order.to_dag();
+        assert_eq!(mapping, vec![2, 1, 0]);
+        assert_eq!(dag.iter_edges().collect::<Vec<_>>(), vec![(0, 1), (1, 2)]);
+    }
+
+    #[test]
+    fn random_insertions_maintain_topological_order() {
+        let vertex_count = 30;
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut order = IncrementalTopologicalOrder::new(vertex_count);
+        for _ in 0..500 {
+            let u = rng.gen_range(0..vertex_count);
+            let v = rng.gen_range(0..vertex_count);
+            let would_close_cycle = is_reachable(&order, v, u);
+            assert_eq!(order.try_add_edge(u, v).is_err(), would_close_cycle);
+            for w in 0..vertex_count {
+                assert_eq!(order.vertex_at[order.get_position(w)], w);
+                for x in order.iter_children(w) {
+                    assert!(order.get_position(w) < order.get_position(x));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn dynamic_closure_matches_static_closure() {
+        let vertex_count = 25;
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut closure = DynamicTransitiveClosure::empty(vertex_count);
+        for _ in 0..60 {
+            let u = rng.gen_range(0..vertex_count - 1);
+            let v = rng.gen_range(u + 1..vertex_count);
+            closure.add_edge(u, v);
+            let expected = get_descendants(closure.get_dag());
+            for (x, x_descendants) in expected.iter().enumerate() {
+                for y in 0..vertex_count {
+                    assert_eq!(closure.has_path(x, y), x_descendants.contains(y));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn closure_delta_is_newly_reachable_pairs() {
+        let vertex_count = 25;
+        let mut rng = StdRng::seed_from_u64(17);
+        let mut closure = DynamicTransitiveClosure::empty(vertex_count);
+        for _ in 0..60 {
+            let u = rng.gen_range(0..vertex_count - 1);
+            let v = rng.gen_range(u + 1..vertex_count);
+            let before = crate::algorithm::transitive_closure(closure.get_dag());
+            let delta = closure.add_edge_with_delta(u, v);
+            let after = crate::algorithm::transitive_closure(closure.get_dag());
+            assert_eq!(
+                delta,
+                after
+                    .iter_edges()
+                    .filter(|(x, y)| !before.get_edge(*x, *y))
+                    .collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn lazy_closure_is_invalidated_by_mutations() {
+        let vertex_count = 20;
+        let mut rng = StdRng::seed_from_u64(13);
+        let mut closure = LazyTransitiveClosure::empty(vertex_count);
+        for _ in 0..200 {
+            let u = rng.gen_range(0..vertex_count - 1);
+            let v = rng.gen_range(u + 1..vertex_count);
+            closure.set_edge(u, v, rng.gen_bool(0.7));
+            let expected = get_descendants(closure.get_dag());
+            for (x, x_descendants) in expected.iter().enumerate() {
+                assert_eq!(closure.get_descendants(x), x_descendants);
+            }
+            assert!(closure.is_cached());
+        }
+
+        let mut path = LazyTransitiveClosure::new(crate::dag!(3; 0 => 1, 1 => 2));
+        assert!(path.has_path(0, 2));
+        path.set_edge(0, 2, true);
+        assert!(path.is_cached());
+        path.set_edge(1, 2, false);
+        assert!(!path.is_cached());
+        assert!(!path.has_path(1, 2));
+    }
+
+    #[test]
+    fn dirty_tracker_marks_descendants_once() {
+        //  0 -> 1 -> 3
+        //  2 -> 3 -> 4
+        let dag = crate::dag!(5; 0 => 1, 1 => 3, 2 => 3, 3 => 4);
+        let mut tracker = DirtyTracker::new(dag);
+        assert_eq!(tracker.mark_dirty(1), vec![1, 3, 4]);
+        assert_eq!(tracker.mark_dirty(0), vec![0]);
+        assert_eq!(tracker.mark_dirty(3), Vec::<usize>::new());
+        assert_eq!(tracker.iter_dirty().collect::<Vec<_>>(), vec![0, 1, 3, 4]);
+
+        tracker.mark_clean(0);
+        tracker.mark_clean(1);
+        tracker.mark_clean(3);
+        assert_eq!(tracker.mark_dirty(2), vec![2, 3]);
+        assert!(tracker.is_dirty(4));
+        tracker.clear();
+        assert_eq!(tracker.iter_dirty().count(), 0);
+    }
+
+    #[test]
+    #[should_panic = "a parent of 3 is dirty"]
+    fn dirty_tracker_requires_clean_parents() {
+        let mut tracker = DirtyTracker::new(crate::dag!(4; 1 => 3));
+        tracker.mark_dirty(1);
+        tracker.mark_clean(3);
+    }
+}
@@ -0,0 +1,288 @@
+use std::collections::HashMap;
+
+use crate::DirectedAcyclicGraph;
+
+/// An element of a [`Layout`] layer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LayoutNode {
+    /// A DAG vertex.
+    Vertex(usize),
+    /// A bend point of an edge that spans more than one layer.
+    Dummy { edge: (usize, usize) },
+}
+
+/// A layered drawing of a DAG: every vertex gets a layer (counted from the
+/// top) and a position within that layer (counted from the left).  Edges that
+/// span several layers are routed through dummy nodes, one per intermediate
+/// layer.  See [`get_sugiyama_layout`].
+#[derive(Clone, Debug)]
+pub struct Layout {
+    /// Layers from top to bottom, each ordered from left to right.
+    pub layers: Vec<Vec<LayoutNode>>,
+    /// A mapping: vertex -> (layer, position within the layer).
+    pub coordinates: Vec<(usize, usize)>,
+    edge_routes: HashMap<(usize, usize), Vec<(usize, usize)>>,
+}
+
+impl Layout {
+    /// The coordinates of the dummy nodes the edge `(u, v)` passes through, from
+    /// `u` to `v`, excluding `u` and `v` themselves.  Empty for edges between
+    /// adjacent layers and for non-edges.
+    pub fn get_edge_route(&self, u: usize, v: usize) -> &[(usize, usize)] {
+        self.edge_routes
+            .get(&(u, v))
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    /// The total number of edge crossings between adjacent layers.
+    pub fn count_crossings(&self, dag: &DirectedAcyclicGraph) -> usize {
+        let mut segments_between: Vec<Vec<(usize, usize)>> =
+            vec![Vec::new(); self.layers.len().saturating_sub(1)];
+        for (u, v) in dag.iter_edges() {
+            let mut route: Vec<(usize, usize)> = vec![self.coordinates[u]];
+            route.extend_from_slice(self.get_edge_route(u, v));
+            route.push(self.coordinates[v]);
+            for pair in route.windows(2) {
+                segments_between[pair[0].0].push((pair[0].1, pair[1].1));
+            }
+        }
+        segments_between
+            .iter()
+            .map(|segments| count_crossings_between(segments))
+            .sum()
+    }
+}
+
+fn count_crossings_between(segments: &[(usize, usize)]) -> usize {
+    let mut result = 0;
+    for (i, (a_top, a_bottom)) in segments.iter().enumerate() {
+        for (b_top, b_bottom) in &segments[i + 1..] {
+            if (a_top < b_top && a_bottom > b_bottom) || (a_top > b_top && a_bottom < b_bottom) {
+                result += 1;
+            }
+        }
+    }
+    result
+}
+
+// (layer, up neighbours, down neighbours) of every node of the layered graph
+// where nodes 0..|V| are the DAG vertices and the rest are dummies.
+struct LayeredGraph {
+    layers: Vec<Vec<usize>>,
+    up: Vec<Vec<usize>>,
+    down: Vec<Vec<usize>>,
+    node_kinds: Vec<LayoutNode>,
+}
+
+impl LayeredGraph {
+    fn new(dag: &DirectedAcyclicGraph) -> Self {
+        let vertex_count = dag.get_vertex_count();
+
+        // Longest path layering: each vertex sits just below its lowest parent.
+        let mut node_layer: Vec<usize> = vec![0; vertex_count];
+        for u in 0..vertex_count {
+            for v in dag.iter_children(u) {
+                node_layer[v] = std::cmp::max(node_layer[v], node_layer[u] + 1);
+            }
+        }
+
+        let mut node_kinds: Vec<LayoutNode> = (0..vertex_count).map(LayoutNode::Vertex).collect();
+        let mut up: Vec<Vec<usize>> = vec![Vec::new(); vertex_count];
+        let mut down: Vec<Vec<usize>> = vec![Vec::new(); vertex_count];
+        for (u, v) in dag.iter_edges() {
+            let mut previous = u;
+            for layer in (node_layer[u] + 1)..node_layer[v] {
+                let dummy = node_kinds.len();
+                node_kinds.push(LayoutNode::Dummy { edge: (u, v) });
+                node_layer.push(layer);
+                up.push(vec![previous]);
+                down.push(Vec::new());
+                down[previous].push(dummy);
+                previous = dummy;
+            }
+            down[previous].push(v);
+            up[v].push(previous);
+        }
+
+        let layer_count = node_layer.iter().max().map_or(0, |max| max + 1);
+        let mut layers: Vec<Vec<usize>> = vec![Vec::new(); layer_count];
+        for (node, layer) in node_layer.iter().enumerate() {
+            layers[*layer].push(node);
+        }
+
+        Self {
+            layers,
+            up,
+            down,
+            node_kinds,
+        }
+    }
+
+    fn get_positions(&self) -> Vec<usize> {
+        let mut positions: Vec<usize> = vec![0; self.node_kinds.len()];
+        for layer in &self.layers {
+            for (position, node) in layer.iter().enumerate() {
+                positions[*node] = position;
+            }
+        }
+        positions
+    }
+
+    fn count_crossings(&self) -> usize {
+        let positions = self.get_positions();
+        self.layers
+            .iter()
+            .map(|layer| {
+                let segments: Vec<(usize, usize)> = layer
+                    .iter()
+                    .flat_map(|u| self.down[*u].iter().map(|v| (positions[*u], positions[*v])))
+                    .collect();
+                count_crossings_between(&segments)
+            })
+            .sum()
+    }
+
+    // Reorders `layer` by the barycenters of the positions of the neighbours in
+    // the adjacent, already ordered layer.
+    fn reorder_by_barycenters(&mut self, layer: usize, downwards: bool) {
+        let positions = self.get_positions();
+        let neighbours = if downwards { &self.up } else { &self.down };
+        let barycenters: HashMap<usize, f64> = self.layers[layer]
+            .iter()
+            .map(|node| {
+                let barycenter = if neighbours[*node].is_empty() {
+                    positions[*node] as f64
+                } else {
+                    neighbours[*node]
+                        .iter()
+                        .map(|n| positions[*n] as f64)
+                        .sum::<f64>()
+                        / neighbours[*node].len() as f64
+                };
+                (*node, barycenter)
+            })
+            .collect();
+        self.layers[layer].sort_by(|a, b| barycenters[a].partial_cmp(&barycenters[b]).unwrap());
+    }
+}
+
+const SWEEPS: usize = 8;
+
+/// Computes a [layered
+/// drawing](https://en.wikipedia.org/wiki/Layered_graph_drawing) of a DAG in
+/// the style of Sugiyama et al. and returns it as plain data, leaving the
+/// actual rendering to the caller.
+///
+/// Vertices are assigned to layers by the length of the longest path leading
+/// to them, so that all the edges point downwards.  Then the number of edge
+/// crossings is reduced with the barycenter heuristic by repeatedly sweeping
+/// the layers up and down.
+pub fn get_sugiyama_layout(dag: &DirectedAcyclicGraph) -> Layout {
+    let mut graph = LayeredGraph::new(dag);
+
+    let mut best_layers = graph.layers.clone();
+    let mut best_crossings = graph.count_crossings();
+    for _ in 0..SWEEPS {
+        if best_crossings == 0 {
+            break;
+        }
+        for layer in 1..graph.layers.len() {
+            graph.reorder_by_barycenters(layer, true);
+        }
+        for layer in (0..graph.layers.len().saturating_sub(1)).rev() {
+            graph.reorder_by_barycenters(layer, false);
+        }
+        let crossings = graph.count_crossings();
+        if crossings < best_crossings {
+            best_crossings = crossings;
+            best_layers = graph.layers.clone();
+        }
+    }
+    graph.layers = best_layers;
+
+    let positions = graph.get_positions();
+    let mut coordinates: Vec<(usize, usize)> = vec![(0, 0); dag.get_vertex_count()];
+    let mut edge_routes: HashMap<(usize, usize), Vec<(usize, usize)>> = HashMap::new();
+    for (layer, nodes) in graph.layers.iter().enumerate() {
+        for node in nodes {
+            let coordinate = (layer, positions[*node]);
+            match graph.node_kinds[*node] {
+                LayoutNode::Vertex(u) => coordinates[u] = coordinate,
+                LayoutNode::Dummy { edge } => edge_routes.entry(edge).or_default().push(coordinate),
+            }
+        }
+    }
+
+    Layout {
+        layers: graph
+            .layers
+            .iter()
+            .map(|nodes| nodes.iter().map(|node| graph.node_kinds[*node]).collect())
+            .collect(),
+        coordinates,
+        edge_routes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layout_removes_avoidable_crossing() {
+        // Naively ordered, (0, 3) crosses (1, 2).
+        let dag = crate::dag!(4; 0 => 3, 1 => 2);
+        let layout = get_sugiyama_layout(&dag);
+        assert_eq!(layout.layers.len(), 2);
+        assert_eq!(layout.count_crossings(&dag), 0);
+        assert_eq!(layout.coordinates[0].0, 0);
+        assert_eq!(layout.coordinates[3].0, 1);
+    }
+
+    #[test]
+    fn long_edges_are_routed_through_dummies() {
+        let dag = crate::dag!(4; 0 => 1, 1 => 2, 0 => 3, 2 => 3);
+        let layout = get_sugiyama_layout(&dag);
+        assert_eq!(
+            layout
+                .coordinates
+                .iter()
+                .map(|(layer, _)| *layer)
+                .collect::<Vec<_>>(),
+            vec![0, 1, 2, 3]
+        );
+        let route = layout.get_edge_route(0, 3);
+        assert_eq!(
+            route.iter().map(|(layer, _)| *layer).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        assert!(layout.get_edge_route(0, 1).is_empty());
+        assert_eq!(
+            layout.layers[1]
+                .iter()
+                .filter(|node| matches!(node, LayoutNode::Dummy { edge: (0, 3) }))
+                .count(),
+            1
+        );
+    }
+
+    fn prop_layout_is_consistent(dag: DirectedAcyclicGraph) -> bool {
+        let layout = get_sugiyama_layout(&dag);
+        dag.iter_edges().all(|(u, v)| {
+            let route = layout.get_edge_route(u, v);
+            layout.coordinates[u].0 < layout.coordinates[v].0
+                && route.len() == layout.coordinates[v].0 - layout.coordinates[u].0 - 1
+        }) && (0..dag.get_vertex_count()).all(|u| {
+            let (layer, position) = layout.coordinates[u];
+            layout.layers[layer][position] == LayoutNode::Vertex(u)
+        })
+    }
+
+    #[test]
+    fn layout_is_consistent() {
+        quickcheck::QuickCheck::new()
+            .rng(quickcheck::Gen::new(20))
+            .quickcheck(prop_layout_is_consistent as fn(DirectedAcyclicGraph) -> bool);
+    }
+}
@@ -41,27 +41,110 @@
 //! * No serde impls.  Simply serialize/deserialize the list of edges with a
 //!   library of your choosing.
 //!
+//! ## Cargo features
+//!
+//! * `async`: `executor::execute_async`, running per-vertex futures in
+//!   dependency order.
+//! * `ffi`: A C API in `ffi`.  The declarations are in `include/dograph.h`.
+//! * `mmap`: `mmap::MmapDirectedAcyclicGraph`, a DAG stored in a memory-mapped
+//!   file.
+//! * `num-bigint`: Arbitrary precision variants of the path counting functions
+//!   in [`algorithm`], counting of antichains in [`poset`] and of labeled DAGs
+//!   in [`generators`].
+//! * `proptest`: proptest strategies in `strategies`, generating DAGs along
+//!   with e.g. a pair of their vertices.
+//! * `pyo3`: Python bindings in `python`, built e.g. with `maturin develop
+//!   --features pyo3`.
+//! * `rayon`: Parallel variants of some functions in [`algorithm`] and
+//!   [`executor`].
+//! * `rkyv`: `archive::ArchivedDirectedAcyclicGraph`, zero-copy
+//!   serialization with rkyv, e.g. to query DAGs straight from a mapped file.
+//! * `roaring`: `sparse::SparseDirectedAcyclicGraph`, a DAG backed by roaring
+//!   bitmaps for huge, very sparse graphs.
+//! * `wasm`: JavaScript bindings in `wasm` (see there for how to build them).
+//!   The crate itself compiles for `wasm32-unknown-unknown` without it.
+//!
 //! # Entry points
 //!
 //! See either [`DirectedAcyclicGraph::empty`],
 //! [`DirectedAcyclicGraph::from_edges_iter`], or
 //! [`DirectedAcyclicGraph::from_adjacency_matrix`] for the "entry point" to
 //! this crate.
+//! Data that may contain cycles can be loaded into a [`DirectedGraph`] first
+//! and converted with [`DirectedGraph::to_dag`].
 
 use std::io::Write;
 
+use fixedbitset::FixedBitSet;
 use quickcheck::{Arbitrary, Gen};
 
+mod digraph6;
+mod directed_graph;
+mod strictly_lower_triangular_logical_matrix;
 mod strictly_upper_triangular_logical_matrix;
+mod symmetric_logical_matrix;
+pub use digraph6::Digraph6Error;
+pub use directed_graph::DirectedGraph;
 use rand::{prelude::StdRng, Rng, SeedableRng};
 use rand_distr::{Bernoulli, Distribution};
-pub use strictly_upper_triangular_logical_matrix::StrictlyUpperTriangularLogicalMatrix;
+pub use strictly_lower_triangular_logical_matrix::StrictlyLowerTriangularLogicalMatrix;
+use strictly_upper_triangular_logical_matrix::strictly_upper_triangular_matrix_capacity;
+pub use strictly_upper_triangular_logical_matrix::{
+    FromDenseError, FromOnesError, IndexError, InvariantViolation, RowChunk, RowChunksIterator,
+    StrictlyUpperTriangularLogicalMatrix,
+};
+pub use symmetric_logical_matrix::SymmetricLogicalMatrix;
 
 pub mod algorithm;
+#[cfg(feature = "rkyv")]
+pub mod archive;
+pub mod atomic;
+pub mod dataflow;
+pub mod executor;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod generators;
+pub mod incremental;
+pub mod layout;
+#[cfg(feature = "mmap")]
+pub mod mmap;
+pub mod paths;
+pub mod persistent;
+pub mod poset;
+#[cfg(feature = "pyo3")]
+pub mod python;
+pub mod reachability;
+pub mod scheduling;
+pub mod shared;
+#[cfg(feature = "roaring")]
+pub mod sparse;
+#[cfg(feature = "proptest")]
+pub mod strategies;
+pub mod subgraph;
 pub mod traversal;
+pub mod undirected;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+pub mod weighted;
+
+/// An error signifying that an operation would introduce a cycle, which a DAG
+/// cannot represent.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CycleError {
+    /// An edge that closes the cycle.
+    pub edge: (usize, usize),
+}
+
+impl std::fmt::Display for CycleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "edge {:?} would introduce a cycle", self.edge)
+    }
+}
+
+impl std::error::Error for CycleError {}
 
 /// A mutable, single-threaded directed acyclic graph.
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Eq, Hash)]
 pub struct DirectedAcyclicGraph {
     adjacency_matrix: StrictlyUpperTriangularLogicalMatrix,
 }
@@ -71,7 +154,7 @@ impl std::fmt::Debug for DirectedAcyclicGraph {
         let ones: Vec<(usize, usize)> = self.iter_edges().collect();
         write!(
             f,
-            "DirectedAcyclicGraph::from_edges({}, vec!{:?}.iter().cloned())",
+            "DirectedAcyclicGraph::from_edges_iter({}, vec!{:?}.into_iter())",
             self.get_vertex_count(),
             ones
         )?;
@@ -86,6 +169,26 @@ impl DirectedAcyclicGraph {
         }
     }
 
+    /// Same as [`Self::from_edges_iter`] but returns an error identifying the
+    /// first invalid or duplicate edge instead of panicking.  See
+    /// [`StrictlyUpperTriangularLogicalMatrix::try_from_ones`].
+    pub fn try_from_edges<I: IntoIterator<Item = (usize, usize)>>(
+        vertex_count: usize,
+        edges: I,
+    ) -> Result<Self, FromOnesError> {
+        let adjacency_matrix =
+            StrictlyUpperTriangularLogicalMatrix::try_from_ones(vertex_count, edges)?;
+        Ok(Self { adjacency_matrix })
+    }
+
+    /// Same as [`Self::empty`] but returns `None` instead of panicking if
+    /// `vertex_count` is too big for the adjacency matrix to be addressable.
+    pub fn try_empty(vertex_count: usize) -> Option<Self> {
+        Some(Self {
+            adjacency_matrix: StrictlyUpperTriangularLogicalMatrix::try_zeroed(vertex_count)?,
+        })
+    }
+
     /// Constructs a DAG from an iterator of edges.
     ///
     /// Requires `u < vertex_count && v < vertex_count && u < v` for every edge
@@ -104,7 +207,7 @@ impl DirectedAcyclicGraph {
     /// an edge between any two of `321` vertices, do:
     ///
     /// ```
-    /// use crate::DirectedAcyclicGraph;
+    /// use dograph::DirectedAcyclicGraph;
     /// use rand::rngs::StdRng;
     /// use rand::SeedableRng;
     /// use rand::distributions::Bernoulli;
@@ -126,6 +229,71 @@ impl DirectedAcyclicGraph {
         dag
     }
 
+    /// Returns an edge chosen uniformly at random, or `None` if there are no
+    /// edges.  Takes time proportional to [`Self::get_vertex_count`] squared
+    /// divided by the word size.
+    pub fn get_random_edge<R: Rng>(&self, rng: &mut R) -> Option<(usize, usize)> {
+        let edge_count = self.adjacency_matrix.count_ones();
+        if edge_count == 0 {
+            return None;
+        }
+        self.adjacency_matrix
+            .get_nth_one(rng.gen_range(0..edge_count))
+    }
+
+    /// Returns a pair of vertices `u < v` chosen uniformly at random, whether
+    /// it's an edge or not, or `None` if there are fewer than 2 vertices.
+    pub fn get_random_vertex_pair<R: Rng>(&self, rng: &mut R) -> Option<(usize, usize)> {
+        let capacity = strictly_upper_triangular_matrix_capacity(self.get_vertex_count());
+        if capacity == 0 {
+            return None;
+        }
+        Some(
+            self.adjacency_matrix
+                .get_row_column_from_index(rng.gen_range(0..capacity)),
+        )
+    }
+
+    /// Returns `k` distinct edges chosen uniformly at random, or all of them if
+    /// there are fewer than `k`, in the row-major order.
+    pub fn sample_edges<R: Rng>(&self, k: usize, rng: &mut R) -> Vec<(usize, usize)> {
+        let edge_count = self.adjacency_matrix.count_ones();
+        let mut ranks = rand::seq::index::sample(rng, edge_count, k.min(edge_count)).into_vec();
+        ranks.sort_unstable();
+        let mut ranks = ranks.into_iter().peekable();
+        self.iter_edges()
+            .enumerate()
+            .filter(|(rank, _)| {
+                if ranks.peek() == Some(rank) {
+                    ranks.next();
+                    true
+                } else {
+                    false
+                }
+            })
+            .map(|(_, edge)| edge)
+            .collect()
+    }
+
+    /// Encodes the DAG in the
+    /// [digraph6](https://users.cecs.anu.edu.au/~bdm/data/formats.txt) format
+    /// used by nauty, without a trailing newline.
+    pub fn to_digraph6(&self) -> String {
+        digraph6::encode(self.get_vertex_count(), |u, v| u < v && self.get_edge(u, v))
+    }
+
+    /// Decodes a DAG from [digraph6](Self::to_digraph6).  Fails if any edge
+    /// doesn't go from a smaller to a bigger vertex, see
+    /// [`DirectedGraph::from_digraph6`] and [`DirectedGraph::to_dag`] for
+    /// graphs with a different numbering.
+    pub fn from_digraph6(input: &str) -> Result<Self, Digraph6Error> {
+        let (vertex_count, edges) = digraph6::decode(input)?;
+        if let Some((u, v)) = edges.iter().find(|(u, v)| u >= v) {
+            return Err(Digraph6Error::NotStrictlyUpper { u: *u, v: *v });
+        }
+        Ok(Self::from_edges_iter(vertex_count, edges.into_iter()))
+    }
+
     /// Construct a DAG from an pre-computed adjacency matrix.
     pub fn from_adjacency_matrix(adjacency_matrix: StrictlyUpperTriangularLogicalMatrix) -> Self {
         Self { adjacency_matrix }
@@ -152,21 +320,364 @@ impl DirectedAcyclicGraph {
         self.adjacency_matrix.set(u, v, exists);
     }
 
+    /// Same as [`Self::get_edge`] but only checks its requirements in debug
+    /// builds, for hot loops.
+    ///
+    /// # Safety
+    ///
+    /// Requires `u < v < self.get_vertex_count()`.
+    #[inline]
+    pub unsafe fn get_edge_unchecked(&self, u: usize, v: usize) -> bool {
+        self.adjacency_matrix.get_unchecked(u, v)
+    }
+
+    /// Same as [`Self::set_edge`] but only checks its requirements in debug
+    /// builds, for hot loops.
+    ///
+    /// # Safety
+    ///
+    /// Requires `u < v < self.get_vertex_count()`.
+    #[inline]
+    pub unsafe fn set_edge_unchecked(&mut self, u: usize, v: usize, exists: bool) {
+        self.adjacency_matrix.set_unchecked(u, v, exists)
+    }
+
+    /// Adds the edge `(u, v)` if it's absent and removes it otherwise.  Returns
+    /// whether the edge exists afterwards.
+    ///
+    /// Requires `u < v`.  Panics otherwise.
+    pub fn toggle_edge(&mut self, u: usize, v: usize) -> bool {
+        self.adjacency_matrix.toggle(u, v)
+    }
+
+    /// Adds the edge `(u, v)`.  Returns whether it was absent before.
+    ///
+    /// Requires `u < v`.  Panics otherwise.
+    pub fn set_edge_if_unset(&mut self, u: usize, v: usize) -> bool {
+        self.adjacency_matrix.set_if_unset(u, v)
+    }
+
+    /// Removes the edge `(u, v)`.  Returns whether it was present before.
+    ///
+    /// Requires `u < v`.  Panics otherwise.
+    pub fn unset_edge_if_set(&mut self, u: usize, v: usize) -> bool {
+        self.adjacency_matrix.unset_if_set(u, v)
+    }
+
+    /// Same as [`Self::set_edge`] but returns whether the edge existed before.
+    ///
+    /// Requires `u < v`.  Panics otherwise.
+    pub fn replace_edge(&mut self, u: usize, v: usize, exists: bool) -> bool {
+        self.adjacency_matrix.set(u, v, exists)
+    }
+
+    /// Removes all the edges.
+    pub fn clear(&mut self) {
+        self.adjacency_matrix.clear();
+    }
+
+    /// Adds every admissible edge, turning the DAG into the complete one, i.e.
+    /// the [total order](https://en.wikipedia.org/wiki/Total_order) of its
+    /// vertices.
+    pub fn fill(&mut self) {
+        self.adjacency_matrix.fill();
+    }
+
+    /// Returns the DAG with exactly the edges that could be legally added to
+    /// this one, i.e. the ones from [`Self::iter_non_edges`].  E.g. the
+    /// complement of a [`algorithm::transitive_closure`] is the
+    /// incomparability graph of the reachability order, oriented from smaller
+    /// to bigger vertices.
+    pub fn complement(&self) -> Self {
+        Self {
+            adjacency_matrix: self.adjacency_matrix.complement(),
+        }
+    }
+
+    /// Whether the reachability order of the DAG is a [total
+    /// order](https://en.wikipedia.org/wiki/Total_order), i.e. every two
+    /// vertices are connected by a path.
+    ///
+    /// Since edges only go from smaller to bigger vertices, that's the case iff
+    /// there's a path `0 -> 1 -> ... -> |V|-1`, so this is `O(|V|)`.
+    pub fn is_total_order(&self) -> bool {
+        (1..self.get_vertex_count()).all(|v| self.adjacency_matrix.get(v - 1, v))
+    }
+
+    /// Adds the edges `(v, v + 1)` missing so that the DAG becomes a total order
+    /// (see [`Self::is_total_order`]).  The resulting order contains the
+    /// original one and is its canonical linear extension: vertices ordered by
+    /// their numbers.
+    pub fn complete_to_total_order(&mut self) {
+        for v in 1..self.get_vertex_count() {
+            self.adjacency_matrix.set(v - 1, v, true);
+        }
+    }
+
+    /// The number of edges divided by the number of admissible edges, `|V| *
+    /// (|V| - 1) / 2`.  Zero for DAGs with fewer than two vertices.
+    pub fn density(&self) -> f64 {
+        let max_edge_count = strictly_upper_triangular_matrix_capacity(self.get_vertex_count());
+        if max_edge_count == 0 {
+            return 0.0;
+        }
+        self.adjacency_matrix.count_ones() as f64 / max_edge_count as f64
+    }
+
+    /// The fraction of the edges of the [transitive
+    /// closure](algorithm::transitive_closure) that are present explicitly in
+    /// the DAG.  It's 1 for a transitively closed DAG and approaches 0 the
+    /// more reachability is implied by longer paths.  One for DAGs with no
+    /// edges.
+    pub fn transitivity(&self) -> f64 {
+        let reachable_pair_count = algorithm::count_reachable_pairs(self);
+        if reachable_pair_count == 0 {
+            return 1.0;
+        }
+        self.iter_edges().count() as f64 / reachable_pair_count as f64
+    }
+
+    /// ORs the children of `u` into `target`, a word at a time.  Requires
+    /// `target.len() >= self.get_vertex_count()`.
+    pub(crate) fn union_children_into(&self, u: usize, target: &mut FixedBitSet) {
+        self.adjacency_matrix.union_row_into(u, target);
+    }
+
+    /// Same as [`Self::get_edge`] but returns an error instead of panicking.
+    pub fn try_get_edge(&self, u: usize, v: usize) -> Result<bool, IndexError> {
+        self.adjacency_matrix.try_get(u, v)
+    }
+
+    /// Same as [`Self::set_edge`] but returns an error instead of panicking.
+    pub fn try_set_edge(&mut self, u: usize, v: usize, exists: bool) -> Result<(), IndexError> {
+        self.adjacency_matrix.try_set(u, v, exists)?;
+        Ok(())
+    }
+
     /// Iterates over the edges in an order that favors CPU cache locality.
     pub fn iter_edges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
         self.adjacency_matrix.iter_ones()
     }
 
+    /// Iterates over the edges sorted by their target vertex first and by the
+    /// source vertex second.
+    pub fn iter_edges_by_target(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.adjacency_matrix.iter_ones_by_target()
+    }
+
+    /// Iterates over the edges incident to `vertex`: first the incoming ones,
+    /// `(u, vertex)`, in increasing order of `u`, then the outgoing ones,
+    /// `(vertex, v)`, in increasing order of `v`.
+    pub fn iter_edges_of(&self, vertex: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        assert!(vertex < self.get_vertex_count());
+        self.adjacency_matrix
+            .iter_ones_at_column(vertex)
+            .map(move |u| (u, vertex))
+            .chain(self.iter_children(vertex).map(move |v| (vertex, v)))
+    }
+
+    /// Iterates over all the pairs `(u, v)` with `u < v` such that there's *no*
+    /// edge `(u, v)` in the DAG, i.e. all the edges that could be legally added.
+    pub fn iter_non_edges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.adjacency_matrix.iter_zeros()
+    }
+
     /// Iterates over vertices `v` such that there's an edge `(u, v)` in the
     /// DAG.
     pub fn iter_children(&self, u: usize) -> impl Iterator<Item = usize> + '_ {
         self.adjacency_matrix.iter_ones_at_row(u)
     }
 
+    /// Same as [`Self::iter_children`] but returns an error instead of
+    /// panicking.
+    pub fn try_iter_children(
+        &self,
+        u: usize,
+    ) -> Result<impl Iterator<Item = usize> + '_, IndexError> {
+        self.adjacency_matrix.check_index(u)?;
+        Ok(self.adjacency_matrix.iter_ones_at_row(u))
+    }
+
+    /// The set of vertices `v` such that there's an edge `(u, v)` in the DAG.
+    /// Handy for set algebra on whole neighbourhoods, e.g. intersecting with
+    /// the result of [`algorithm::get_descendants`].
+    pub fn get_children_bitset(&self, u: usize) -> FixedBitSet {
+        let mut result = FixedBitSet::with_capacity(self.get_vertex_count());
+        result.extend(self.iter_children(u));
+        result
+    }
+
+    /// The set of vertices `u` such that there's an edge `(u, v)` in the DAG.
+    ///
+    /// Note that, unlike [`Self::get_children_bitset`], this needs to scan a
+    /// whole column of the adjacency matrix, a bit at a time.  For the parents
+    /// of many vertices, [transpose](StrictlyUpperTriangularLogicalMatrix::transpose)
+    /// the adjacency matrix once instead.
+    pub fn get_parents_bitset(&self, v: usize) -> FixedBitSet {
+        let mut result = FixedBitSet::with_capacity(self.get_vertex_count());
+        result.extend(self.adjacency_matrix.iter_ones_at_column(v));
+        result
+    }
+
+    /// A view of the underlying undirected graph, with neighbours, connected
+    /// components and bridges.
+    pub fn as_undirected(&self) -> undirected::UndirectedView<'_> {
+        undirected::UndirectedView::new(self)
+    }
+
+    /// Computes the differences between `self` (old) and `other` (new).
+    ///
+    /// Vertices are identified by their numbers, so if `other` has more
+    /// vertices, the extra ones are considered added, and if it has fewer, the
+    /// missing ones are considered removed along with their edges.
+    pub fn diff(&self, other: &DirectedAcyclicGraph) -> GraphDiff {
+        let mut added_edges: Vec<(usize, usize)> = Vec::new();
+        let mut removed_edges: Vec<(usize, usize)> = Vec::new();
+
+        // Both iterators yield edges in the same (row-major) order so they can
+        // be merged.
+        let mut old_edges = self.iter_edges().peekable();
+        let mut new_edges = other.iter_edges().peekable();
+        loop {
+            match (old_edges.peek(), new_edges.peek()) {
+                (Some(old), Some(new)) if old == new => {
+                    old_edges.next();
+                    new_edges.next();
+                }
+                (Some(old), Some(new)) if old < new => {
+                    removed_edges.push(*old);
+                    old_edges.next();
+                }
+                (_, Some(new)) => {
+                    added_edges.push(*new);
+                    new_edges.next();
+                }
+                (Some(old), None) => {
+                    removed_edges.push(*old);
+                    old_edges.next();
+                }
+                (None, None) => break,
+            }
+        }
+
+        let old_vertex_count = self.get_vertex_count();
+        let new_vertex_count = other.get_vertex_count();
+        GraphDiff {
+            added_vertices: old_vertex_count..std::cmp::max(old_vertex_count, new_vertex_count),
+            removed_vertices: new_vertex_count..std::cmp::max(old_vertex_count, new_vertex_count),
+            added_edges,
+            removed_edges,
+        }
+    }
+
     /// Consume self and return the underlying adjacency matrix.
     pub fn into_adjacency_matrix(self) -> StrictlyUpperTriangularLogicalMatrix {
         self.adjacency_matrix
     }
+
+    /// Checks the internal consistency of the DAG.  See
+    /// [`StrictlyUpperTriangularLogicalMatrix::validate`].  Every edge going
+    /// from a smaller to a bigger vertex is guaranteed by the matrix
+    /// representation itself.
+    pub fn validate(&self) -> Result<(), InvariantViolation> {
+        self.adjacency_matrix.validate()
+    }
+
+    /// The underlying adjacency matrix, e.g. for raw access to its blocks via
+    /// [`StrictlyUpperTriangularLogicalMatrix::as_blocks`].
+    pub fn get_adjacency_matrix(&self) -> &StrictlyUpperTriangularLogicalMatrix {
+        &self.adjacency_matrix
+    }
+}
+
+impl std::ops::Index<(usize, usize)> for DirectedAcyclicGraph {
+    type Output = bool;
+
+    /// Same as [`DirectedAcyclicGraph::get_edge`].
+    fn index(&self, (u, v): (usize, usize)) -> &Self::Output {
+        if self.get_edge(u, v) {
+            &true
+        } else {
+            &false
+        }
+    }
+}
+
+/// The result of [`DirectedAcyclicGraph::diff`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GraphDiff {
+    pub added_vertices: std::ops::Range<usize>,
+    pub removed_vertices: std::ops::Range<usize>,
+    /// Edges in the new DAG only, in row-major order.
+    pub added_edges: Vec<(usize, usize)>,
+    /// Edges in the old DAG only, in row-major order.
+    pub removed_edges: Vec<(usize, usize)>,
+}
+
+impl GraphDiff {
+    /// Whether both DAGs are the same.
+    pub fn is_empty(&self) -> bool {
+        self.added_vertices.is_empty()
+            && self.removed_vertices.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+    }
+}
+
+/// Collects edges into a DAG with the smallest vertex count that fits all of
+/// them, i.e. the biggest vertex plus one.  Use
+/// [`DirectedAcyclicGraph::from_edges_iter`] to specify the vertex count
+/// explicitly.
+///
+/// Requires `u < v` for every edge `(u, v)`.  Panics otherwise.
+impl FromIterator<(usize, usize)> for DirectedAcyclicGraph {
+    fn from_iter<I: IntoIterator<Item = (usize, usize)>>(iter: I) -> Self {
+        let edges: Vec<(usize, usize)> = iter.into_iter().collect();
+        let vertex_count = edges
+            .iter()
+            .map(|(u, v)| std::cmp::max(*u, *v) + 1)
+            .max()
+            .unwrap_or(0);
+        DirectedAcyclicGraph::from_edges_iter(vertex_count, edges.into_iter())
+    }
+}
+
+/// Adds edges to a DAG.  The vertex count stays unchanged.
+///
+/// Requires `u < vertex_count && v < vertex_count && u < v` for every edge
+/// `(u, v)`.  Panics otherwise.
+impl Extend<(usize, usize)> for DirectedAcyclicGraph {
+    fn extend<I: IntoIterator<Item = (usize, usize)>>(&mut self, iter: I) {
+        for (u, v) in iter {
+            self.set_edge(u, v, true);
+        }
+    }
+}
+
+/// Constructs a [`DirectedAcyclicGraph`] literal from a vertex count and a list
+/// of `u => v` edges.
+///
+/// ```
+/// use dograph::dag;
+///
+/// let dag = dag!(5; 0 => 1, 0 => 2, 2 => 4);
+/// assert_eq!(dag.get_vertex_count(), 5);
+/// assert_eq!(dag.iter_edges().collect::<Vec<_>>(), vec![(0, 1), (0, 2), (2, 4)]);
+///
+/// let empty = dag!(3);
+/// assert_eq!(empty.iter_edges().count(), 0);
+/// ```
+///
+/// Panics if any of the edges is not a valid DAG edge (see
+/// [`DirectedAcyclicGraph::from_edges_iter`]).
+#[macro_export]
+macro_rules! dag {
+    ($vertex_count:expr) => {
+        $crate::DirectedAcyclicGraph::empty($vertex_count)
+    };
+    ($vertex_count:expr; $($u:expr => $v:expr),+ $(,)?) => {
+        $crate::DirectedAcyclicGraph::from_edges_iter($vertex_count, [$(($u, $v)),+].into_iter())
+    };
 }
 
 /// Break a DAG into two halves at the vertex `vertex`.  Used as a shrinking
@@ -217,30 +728,461 @@ pub fn to_dot<W: Write>(
     dag: &DirectedAcyclicGraph,
     output: &mut W,
 ) -> std::result::Result<(), std::io::Error> {
+    to_dot_with_attributes(dag, output, |_| String::new(), |_, _| String::new())
+}
+
+/// Same as [`to_dot`] but with additional [Graphviz
+/// attributes](https://graphviz.org/doc/info/attrs.html) for every vertex and
+/// edge, e.g. to highlight the critical path.  `vertex_attributes(u)` and
+/// `edge_attributes(u, v)` return attribute lists like `color=red,
+/// shape=box`, or an empty string for no additional attributes.
+///
+/// ```
+/// use dograph::{dag, to_dot_with_attributes};
+/// let dag = dag!(2; 0 => 1);
+/// let mut output = Vec::new();
+/// to_dot_with_attributes(
+///     &dag,
+///     &mut output,
+///     |u| if u == 0 { "shape=box".to_string() } else { String::new() },
+///     |_, _| "color=red".to_string(),
+/// )
+/// .unwrap();
+/// let output = String::from_utf8(output).unwrap();
+/// assert!(output.contains("_0[label=\"0\", shape=box];"));
+/// assert!(output.contains("_0 -> _1[color=red];"));
+/// ```
+pub fn to_dot_with_attributes<W, V, E>(
+    dag: &DirectedAcyclicGraph,
+    output: &mut W,
+    vertex_attributes: V,
+    edge_attributes: E,
+) -> std::result::Result<(), std::io::Error>
+where
+    W: Write,
+    V: Fn(usize) -> String,
+    E: Fn(usize, usize) -> String,
+{
+    write_dot(
+        dag,
+        output,
+        |u| u.to_string(),
+        vertex_attributes,
+        edge_attributes,
+    )
+}
+
+/// Same as [`to_dot`] but with human readable labels: `vertex_label(u)` for
+/// every vertex and `edge_label(u, v)` for the edges that have one.
+///
+/// All the text exporters have such a `_labeled` variant taking the same
+/// labelers and escaping them as the format requires: [`to_dot_labeled`],
+/// [`to_mermaid_labeled`], [`to_plantuml_labeled`], [`to_tgf_labeled`],
+/// [`to_cytoscape_json_labeled`] and [`to_d3_json_labeled`].
+///
+/// ```
+/// use dograph::{dag, to_dot_labeled};
+/// let tasks = ["fetch", "build"];
+/// let mut output = Vec::new();
+/// to_dot_labeled(&dag!(2; 0 => 1), &mut output, |u| tasks[u].to_string(), |_, _| None).unwrap();
+/// assert!(String::from_utf8(output).unwrap().contains("_1[label=\"build\"];"));
+/// ```
+pub fn to_dot_labeled<W, V, E>(
+    dag: &DirectedAcyclicGraph,
+    output: &mut W,
+    vertex_label: V,
+    edge_label: E,
+) -> std::result::Result<(), std::io::Error>
+where
+    W: Write,
+    V: Fn(usize) -> String,
+    E: Fn(usize, usize) -> Option<String>,
+{
+    write_dot(
+        dag,
+        output,
+        |u| escape_dot_label(&vertex_label(u)),
+        |_| String::new(),
+        |u, v| match edge_label(u, v) {
+            Some(label) => format!("label=\"{}\"", escape_dot_label(&label)),
+            None => String::new(),
+        },
+    )
+}
+
+/// Escapes `label` for a double-quoted DOT string.  Backslashes go first so
+/// that they don't combine with the escaped quotes.
+fn escape_dot_label(label: &str) -> String {
+    label
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// `vertex_label` has to return an already escaped string.
+fn write_dot<W, L, V, E>(
+    dag: &DirectedAcyclicGraph,
+    output: &mut W,
+    vertex_label: L,
+    vertex_attributes: V,
+    edge_attributes: E,
+) -> std::result::Result<(), std::io::Error>
+where
+    W: Write,
+    L: Fn(usize) -> String,
+    V: Fn(usize) -> String,
+    E: Fn(usize, usize) -> String,
+{
     writeln!(output, "digraph dag_{} {{", dag.get_vertex_count())?;
 
     for elem in 0..dag.get_vertex_count() {
-        writeln!(output, "\t_{}[label=\"{}\"];", elem, elem)?;
+        let label = vertex_label(elem);
+        let attributes = vertex_attributes(elem);
+        if attributes.is_empty() {
+            writeln!(output, "\t_{}[label=\"{}\"];", elem, label)?;
+        } else {
+            writeln!(output, "\t_{}[label=\"{}\", {}];", elem, label, attributes)?;
+        }
     }
 
     writeln!(output, "\n")?;
 
     for (left, right) in dag.iter_edges() {
-        writeln!(output, "\t_{} -> _{};", left, right)?;
+        let attributes = edge_attributes(left, right);
+        if attributes.is_empty() {
+            writeln!(output, "\t_{} -> _{};", left, right)?;
+        } else {
+            writeln!(output, "\t_{} -> _{}[{}];", left, right, attributes)?;
+        }
     }
 
     writeln!(output, "}}")?;
     Ok(())
 }
 
+/// Outputs the DAG as a [Mermaid](https://mermaid.js.org/) flowchart, e.g. to
+/// embed it in Markdown.
+pub fn to_mermaid<W: Write>(
+    dag: &DirectedAcyclicGraph,
+    output: &mut W,
+) -> std::result::Result<(), std::io::Error> {
+    to_mermaid_labeled(dag, output, |u| u.to_string(), |_, _| None)
+}
+
+/// Same as [`to_mermaid`] but with labels, see [`to_dot_labeled`].
+pub fn to_mermaid_labeled<W, V, E>(
+    dag: &DirectedAcyclicGraph,
+    output: &mut W,
+    vertex_label: V,
+    edge_label: E,
+) -> std::result::Result<(), std::io::Error>
+where
+    W: Write,
+    V: Fn(usize) -> String,
+    E: Fn(usize, usize) -> Option<String>,
+{
+    writeln!(output, "flowchart TD")?;
+    for elem in 0..dag.get_vertex_count() {
+        writeln!(
+            output,
+            "\t_{}[\"{}\"]",
+            elem,
+            escape_mermaid_label(&vertex_label(elem))
+        )?;
+    }
+    for (left, right) in dag.iter_edges() {
+        match edge_label(left, right) {
+            Some(label) => writeln!(
+                output,
+                "\t_{} -->|\"{}\"| _{}",
+                left,
+                escape_mermaid_label(&label),
+                right
+            )?,
+            None => writeln!(output, "\t_{} --> _{}", left, right)?,
+        }
+    }
+    Ok(())
+}
+
+/// Mermaid has no escape sequences inside quoted labels, only entities.
+fn escape_mermaid_label(label: &str) -> String {
+    label.replace('"', "#quot;")
+}
+
+/// Outputs the DAG as a [PlantUML](https://plantuml.com/) component diagram,
+/// e.g. to embed generated dependency graphs in architecture docs.
+pub fn to_plantuml<W: Write>(
+    dag: &DirectedAcyclicGraph,
+    output: &mut W,
+) -> std::result::Result<(), std::io::Error> {
+    to_plantuml_labeled(dag, output, |u| u.to_string(), |_, _| None)
+}
+
+/// Same as [`to_plantuml`] but with labels, see [`to_dot_labeled`].
+pub fn to_plantuml_labeled<W, V, E>(
+    dag: &DirectedAcyclicGraph,
+    output: &mut W,
+    vertex_label: V,
+    edge_label: E,
+) -> std::result::Result<(), std::io::Error>
+where
+    W: Write,
+    V: Fn(usize) -> String,
+    E: Fn(usize, usize) -> Option<String>,
+{
+    writeln!(output, "@startuml")?;
+    for elem in 0..dag.get_vertex_count() {
+        writeln!(
+            output,
+            "component \"{}\" as _{}",
+            escape_plantuml_label(&vertex_label(elem)),
+            elem
+        )?;
+    }
+    for (left, right) in dag.iter_edges() {
+        match edge_label(left, right) {
+            Some(label) => writeln!(
+                output,
+                "_{} --> _{} : {}",
+                left,
+                right,
+                escape_plantuml_label(&label)
+            )?,
+            None => writeln!(output, "_{} --> _{}", left, right)?,
+        }
+    }
+    writeln!(output, "@enduml")?;
+    Ok(())
+}
+
+/// PlantUML has no escape sequences inside quoted names, only Unicode code
+/// points, and labels end at the end of the line.
+fn escape_plantuml_label(label: &str) -> String {
+    label.replace('"', "<U+0022>").replace('\n', "\\n")
+}
+
+/// Outputs the DAG in the [Trivial Graph
+/// Format](https://en.wikipedia.org/wiki/Trivial_Graph_Format): one `vertex
+/// label` line per vertex, a `#` line and one `u v` line per edge.
+pub fn to_tgf<W: Write>(
+    dag: &DirectedAcyclicGraph,
+    output: &mut W,
+) -> std::result::Result<(), std::io::Error> {
+    to_tgf_labeled(dag, output, |u| u.to_string(), |_, _| None)
+}
+
+/// Same as [`to_tgf`] but with labels, see [`to_dot_labeled`].  Edges with a
+/// label get `u v label` lines.
+pub fn to_tgf_labeled<W, V, E>(
+    dag: &DirectedAcyclicGraph,
+    output: &mut W,
+    vertex_label: V,
+    edge_label: E,
+) -> std::result::Result<(), std::io::Error>
+where
+    W: Write,
+    V: Fn(usize) -> String,
+    E: Fn(usize, usize) -> Option<String>,
+{
+    for elem in 0..dag.get_vertex_count() {
+        writeln!(output, "{} {}", elem, escape_tgf_label(&vertex_label(elem)))?;
+    }
+    writeln!(output, "#")?;
+    for (left, right) in dag.iter_edges() {
+        match edge_label(left, right) {
+            Some(label) => writeln!(output, "{} {} {}", left, right, escape_tgf_label(&label))?,
+            None => writeln!(output, "{} {}", left, right)?,
+        }
+    }
+    Ok(())
+}
+
+/// TGF has no escape sequences at all and labels end at the end of the line.
+fn escape_tgf_label(label: &str) -> String {
+    label.replace(['\r', '\n'], " ")
+}
+
+/// Outputs the DAG as [Cytoscape.js](https://js.cytoscape.org/) JSON, i.e. an
+/// object with `nodes` and `edges` element arrays under `elements`, ready to
+/// be passed to `cytoscape({ elements: ... })` or `cy.json(...)`.  Vertex ids
+/// are the vertex numbers as strings and edge ids are of the form `u-v`.
+pub fn to_cytoscape_json<W: Write>(
+    dag: &DirectedAcyclicGraph,
+    output: &mut W,
+) -> std::result::Result<(), std::io::Error> {
+    write_cytoscape_json(dag, output, |_| None, |_, _| None)
+}
+
+/// Same as [`to_cytoscape_json`] but with a `label` data field for every
+/// vertex and for the edges that have one, see [`to_dot_labeled`].
+pub fn to_cytoscape_json_labeled<W, V, E>(
+    dag: &DirectedAcyclicGraph,
+    output: &mut W,
+    vertex_label: V,
+    edge_label: E,
+) -> std::result::Result<(), std::io::Error>
+where
+    W: Write,
+    V: Fn(usize) -> String,
+    E: Fn(usize, usize) -> Option<String>,
+{
+    write_cytoscape_json(dag, output, |u| Some(vertex_label(u)), edge_label)
+}
+
+fn write_cytoscape_json<W, V, E>(
+    dag: &DirectedAcyclicGraph,
+    output: &mut W,
+    vertex_label: V,
+    edge_label: E,
+) -> std::result::Result<(), std::io::Error>
+where
+    W: Write,
+    V: Fn(usize) -> Option<String>,
+    E: Fn(usize, usize) -> Option<String>,
+{
+    write!(output, "{{\"elements\":{{\"nodes\":[")?;
+    for elem in 0..dag.get_vertex_count() {
+        if elem > 0 {
+            write!(output, ",")?;
+        }
+        write!(output, "{{\"data\":{{\"id\":\"{}\"", elem)?;
+        if let Some(label) = vertex_label(elem) {
+            write!(output, ",\"label\":")?;
+            write_json_string(output, &label)?;
+        }
+        write!(output, "}}}}")?;
+    }
+    write!(output, "],\"edges\":[")?;
+    for (i, (left, right)) in dag.iter_edges().enumerate() {
+        if i > 0 {
+            write!(output, ",")?;
+        }
+        write!(
+            output,
+            "{{\"data\":{{\"id\":\"{0}-{1}\",\"source\":\"{0}\",\"target\":\"{1}\"",
+            left, right
+        )?;
+        if let Some(label) = edge_label(left, right) {
+            write!(output, ",\"label\":")?;
+            write_json_string(output, &label)?;
+        }
+        write!(output, "}}}}")?;
+    }
+    writeln!(output, "]}}}}")?;
+    Ok(())
+}
+
+/// Writes `s` as a JSON string literal, quotes included.
+fn write_json_string<W: Write>(output: &mut W, s: &str) -> std::result::Result<(), std::io::Error> {
+    write!(output, "\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => write!(output, "\\\"")?,
+            '\\' => write!(output, "\\\\")?,
+            c if c.is_control() => write!(output, "\\u{:04x}", c as u32)?,
+            c => write!(output, "{}", c)?,
+        }
+    }
+    write!(output, "\"")
+}
+
+/// Outputs the DAG in the `{"nodes": [...], "links": [...]}` JSON shape
+/// expected by [d3-force](https://d3js.org/d3-force) and
+/// [d3-dag](https://github.com/erikbrinkman/d3-dag).  Nodes have numeric
+/// `id`s and links refer to them by `source` and `target`.
+pub fn to_d3_json<W: Write>(
+    dag: &DirectedAcyclicGraph,
+    output: &mut W,
+) -> std::result::Result<(), std::io::Error> {
+    write_d3_json(dag, output, |_| None, |_, _| None, |_| None)
+}
+
+/// Same as [`to_d3_json`] but with a `label` property for every node and for
+/// the links that have one, see [`to_dot_labeled`].
+pub fn to_d3_json_labeled<W, V, E>(
+    dag: &DirectedAcyclicGraph,
+    output: &mut W,
+    vertex_label: V,
+    edge_label: E,
+) -> std::result::Result<(), std::io::Error>
+where
+    W: Write,
+    V: Fn(usize) -> String,
+    E: Fn(usize, usize) -> Option<String>,
+{
+    write_d3_json(dag, output, |u| Some(vertex_label(u)), edge_label, |_| None)
+}
+
+/// Same as [`to_d3_json_labeled`] but with an additional `group` property for
+/// the nodes that have one, e.g. to color nodes by group in d3-force.
+pub fn to_d3_json_with<W, V, E, G>(
+    dag: &DirectedAcyclicGraph,
+    output: &mut W,
+    vertex_label: V,
+    edge_label: E,
+    group: G,
+) -> std::result::Result<(), std::io::Error>
+where
+    W: Write,
+    V: Fn(usize) -> String,
+    E: Fn(usize, usize) -> Option<String>,
+    G: Fn(usize) -> Option<String>,
+{
+    write_d3_json(dag, output, |u| Some(vertex_label(u)), edge_label, group)
+}
+
+fn write_d3_json<W, V, E, G>(
+    dag: &DirectedAcyclicGraph,
+    output: &mut W,
+    vertex_label: V,
+    edge_label: E,
+    group: G,
+) -> std::result::Result<(), std::io::Error>
+where
+    W: Write,
+    V: Fn(usize) -> Option<String>,
+    E: Fn(usize, usize) -> Option<String>,
+    G: Fn(usize) -> Option<String>,
+{
+    write!(output, "{{\"nodes\":[")?;
+    for elem in 0..dag.get_vertex_count() {
+        if elem > 0 {
+            write!(output, ",")?;
+        }
+        write!(output, "{{\"id\":{}", elem)?;
+        if let Some(label) = vertex_label(elem) {
+            write!(output, ",\"label\":")?;
+            write_json_string(output, &label)?;
+        }
+        if let Some(group) = group(elem) {
+            write!(output, ",\"group\":")?;
+            write_json_string(output, &group)?;
+        }
+        write!(output, "}}")?;
+    }
+    write!(output, "],\"links\":[")?;
+    for (i, (left, right)) in dag.iter_edges().enumerate() {
+        if i > 0 {
+            write!(output, ",")?;
+        }
+        write!(output, "{{\"source\":{},\"target\":{}", left, right)?;
+        if let Some(label) = edge_label(left, right) {
+            write!(output, ",\"label\":")?;
+            write_json_string(output, &label)?;
+        }
+        write!(output, "}}")?;
+    }
+    writeln!(output, "]}}")?;
+    Ok(())
+}
+
 impl Arbitrary for DirectedAcyclicGraph {
     fn arbitrary(g: &mut Gen) -> Self {
         let vertex_count = g.size();
         let seed = u64::arbitrary(g);
         let mut rng = StdRng::seed_from_u64(seed);
-        let dag =
-            DirectedAcyclicGraph::random(vertex_count, &mut rng, Bernoulli::new(0.75).unwrap());
-        dag
+        DirectedAcyclicGraph::random(vertex_count, &mut rng, Bernoulli::new(0.75).unwrap())
     }
 
     fn shrink(&self) -> Box<dyn Iterator<Item = DirectedAcyclicGraph>> {
@@ -255,7 +1197,7 @@ impl Arbitrary for DirectedAcyclicGraph {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::{BTreeMap, HashSet};
+    use std::collections::{BTreeMap, HashMap, HashSet};
 
     use super::*;
 
@@ -265,7 +1207,7 @@ mod tests {
     #[should_panic = "assertion failed: u < v"]
     fn negative_test_smallest_dag() {
         let mut dag = DirectedAcyclicGraph::empty(2);
-        assert_eq!(dag.get_edge(0, 0), false);
+        assert!(!dag.get_edge(0, 0));
         dag.set_edge(0, 0, true);
     }
 
@@ -353,7 +1295,7 @@ mod tests {
                 let divisors_of_n: Vec<usize> =
                     (1..n / 2 + 1).filter(|d| n % d == 0).rev().collect();
                 for divisor in &divisors_of_n {
-                    if !result.contains_key(&divisor) {
+                    if !result.contains_key(divisor) {
                         numbers.push(*divisor);
                     }
                 }
@@ -374,7 +1316,7 @@ mod tests {
 
             for divisor in self.divisors_of.keys() {
                 result.extend(
-                    self.divisors_of[&divisor]
+                    self.divisors_of[divisor]
                         .iter()
                         .map(|dividend| (*dividend, *divisor)),
                 );
@@ -422,6 +1364,7 @@ mod tests {
 
         for (left, right) in pairs {
             assert!(dag.get_edge(left, right), "({}, {})", left, right);
+            assert!(dag[(left, right)], "({}, {})", left, right);
         }
 
         for (left, right) in dag.iter_edges() {
@@ -434,7 +1377,7 @@ mod tests {
     #[test]
     fn integer_divisibility_poset_isomorphism() {
         let gen = quickcheck::Gen::new(1000);
-        quickcheck::QuickCheck::new().gen(gen).quickcheck(
+        quickcheck::QuickCheck::new().rng(gen).quickcheck(
             prop_integer_divisibility_poset_isomorphism as fn(IntegerDivisibilityPoset) -> bool,
         );
     }
@@ -468,11 +1411,26 @@ mod tests {
         ];
         let dag =
             DirectedAcyclicGraph::from_edges_iter(12 + 1, divisibility_poset_pairs.into_iter());
-        assert_eq!(dag.iter_children(12).collect::<Vec<usize>>(), vec![]);
-        assert_eq!(dag.iter_children(11).collect::<Vec<usize>>(), vec![]);
-        assert_eq!(dag.iter_children(9).collect::<Vec<usize>>(), vec![]);
-        assert_eq!(dag.iter_children(8).collect::<Vec<usize>>(), vec![]);
-        assert_eq!(dag.iter_children(7).collect::<Vec<usize>>(), vec![]);
+        assert_eq!(
+            dag.iter_children(12).collect::<Vec<usize>>(),
+            Vec::<usize>::new()
+        );
+        assert_eq!(
+            dag.iter_children(11).collect::<Vec<usize>>(),
+            Vec::<usize>::new()
+        );
+        assert_eq!(
+            dag.iter_children(9).collect::<Vec<usize>>(),
+            Vec::<usize>::new()
+        );
+        assert_eq!(
+            dag.iter_children(8).collect::<Vec<usize>>(),
+            Vec::<usize>::new()
+        );
+        assert_eq!(
+            dag.iter_children(7).collect::<Vec<usize>>(),
+            Vec::<usize>::new()
+        );
         assert_eq!(dag.iter_children(6).collect::<Vec<usize>>(), vec![12]);
         assert_eq!(dag.iter_children(5).collect::<Vec<usize>>(), vec![10]);
         assert_eq!(dag.iter_children(4).collect::<Vec<usize>>(), vec![8, 12]);
@@ -486,4 +1444,319 @@ mod tests {
             vec![2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]
         );
     }
+
+    #[test]
+    fn dag_macro() {
+        assert_eq!(
+            dag!(4; 0 => 1, 1 => 3,),
+            DirectedAcyclicGraph::from_edges_iter(4, vec![(0, 1), (1, 3)].into_iter())
+        );
+        assert_eq!(dag!(4), DirectedAcyclicGraph::empty(4));
+    }
+
+    #[test]
+    fn collect_and_extend() {
+        let mut dag: DirectedAcyclicGraph = vec![(0, 2), (1, 4)].into_iter().collect();
+        assert_eq!(dag, dag!(5; 0 => 2, 1 => 4));
+        dag.extend(vec![(0, 1), (3, 4)]);
+        assert_eq!(dag, dag!(5; 0 => 1, 0 => 2, 1 => 4, 3 => 4));
+
+        let empty: DirectedAcyclicGraph = std::iter::empty().collect();
+        assert_eq!(empty.get_vertex_count(), 0);
+    }
+
+    #[test]
+    fn diff_between_releases() {
+        let old = dag!(4; 0 => 1, 1 => 2, 2 => 3);
+        let new = dag!(5; 0 => 1, 0 => 2, 2 => 3, 3 => 4);
+        let diff = old.diff(&new);
+        assert_eq!(diff.added_vertices, 4..5);
+        assert!(diff.removed_vertices.is_empty());
+        assert_eq!(diff.added_edges, vec![(0, 2), (3, 4)]);
+        assert_eq!(diff.removed_edges, vec![(1, 2)]);
+
+        let reverse = new.diff(&old);
+        assert_eq!(reverse.removed_vertices, 4..5);
+        assert_eq!(reverse.removed_edges, vec![(0, 2), (3, 4)]);
+        assert!(old.diff(&old).is_empty());
+    }
+
+    fn prop_neighbour_bitsets_match_edges(dag: DirectedAcyclicGraph) -> bool {
+        let vertex_count = dag.get_vertex_count();
+        (0..vertex_count).all(|u| {
+            let children = dag.get_children_bitset(u);
+            let parents = dag.get_parents_bitset(u);
+            children.len() == vertex_count
+                && parents.len() == vertex_count
+                && children.ones().eq(dag.iter_children(u))
+                && parents.ones().all(|p| dag.get_edge(p, u))
+                && dag.iter_edges().filter(|(_, v)| *v == u).count() == parents.count_ones(..)
+        })
+    }
+
+    #[test]
+    fn neighbour_bitsets_match_edges() {
+        quickcheck::QuickCheck::new()
+            .quickcheck(prop_neighbour_bitsets_match_edges as fn(DirectedAcyclicGraph) -> bool);
+    }
+
+    #[test]
+    fn conditional_edge_updates() {
+        let mut dag = DirectedAcyclicGraph::empty(3);
+        assert!(dag.toggle_edge(0, 2));
+        assert!(dag.get_edge(0, 2));
+        assert!(!dag.toggle_edge(0, 2));
+        assert!(!dag.get_edge(0, 2));
+
+        assert!(dag.set_edge_if_unset(1, 2));
+        assert!(!dag.set_edge_if_unset(1, 2));
+        assert!(dag.get_edge(1, 2));
+        assert!(dag.unset_edge_if_set(1, 2));
+        assert!(!dag.unset_edge_if_set(1, 2));
+        assert_eq!(dag, DirectedAcyclicGraph::empty(3));
+    }
+
+    fn prop_completion_is_total_order(dag: DirectedAcyclicGraph) -> bool {
+        let mut completed = dag.clone();
+        completed.complete_to_total_order();
+        completed.is_total_order()
+            && dag.iter_edges().all(|(u, v)| completed.get_edge(u, v))
+            && completed.iter_edges().count() - dag.iter_edges().count()
+                == (1..dag.get_vertex_count())
+                    .filter(|v| !dag.get_edge(v - 1, *v))
+                    .count()
+    }
+
+    #[test]
+    fn completion_is_total_order() {
+        quickcheck::QuickCheck::new()
+            .quickcheck(prop_completion_is_total_order as fn(DirectedAcyclicGraph) -> bool);
+        assert!(!dag!(3; 0 => 1, 0 => 2).is_total_order());
+        assert!(dag!(3; 0 => 1, 1 => 2).is_total_order());
+    }
+
+    #[test]
+    fn density_and_transitivity() {
+        let path = dag!(4; 0 => 1, 1 => 2, 2 => 3);
+        assert_eq!(path.density(), 0.5);
+        assert_eq!(path.transitivity(), 0.5);
+
+        let mut complete = DirectedAcyclicGraph::empty(4);
+        complete.fill();
+        assert_eq!(complete.density(), 1.0);
+        assert_eq!(complete.transitivity(), 1.0);
+
+        assert_eq!(DirectedAcyclicGraph::empty(4).density(), 0.0);
+        assert_eq!(DirectedAcyclicGraph::empty(4).transitivity(), 1.0);
+    }
+
+    #[test]
+    fn replace_edge_returns_previous() {
+        let mut dag = DirectedAcyclicGraph::empty(3);
+        assert!(!dag.replace_edge(0, 1, true));
+        assert!(dag.replace_edge(0, 1, true));
+        assert!(!dag.replace_edge(1, 2, true));
+        assert!(dag.replace_edge(1, 2, false));
+        assert_eq!(dag, dag!(3; 0 => 1));
+    }
+
+    fn prop_alternative_edge_orders_agree(dag: DirectedAcyclicGraph) -> bool {
+        let mut by_target: Vec<(usize, usize)> = dag.iter_edges().collect();
+        by_target.sort_by_key(|(u, v)| (*v, *u));
+        let middle = dag.get_vertex_count() / 2;
+        dag.iter_edges_by_target().eq(by_target)
+            && dag.iter_edges_of(middle).eq(dag
+                .iter_edges()
+                .filter(|(u, v)| *u == middle || *v == middle))
+    }
+
+    #[test]
+    fn alternative_edge_orders_agree() {
+        quickcheck::QuickCheck::new()
+            .quickcheck(prop_alternative_edge_orders_agree as fn(DirectedAcyclicGraph) -> bool);
+    }
+
+    #[test]
+    fn tgf_and_cytoscape_export() {
+        let dag = dag!(3; 0 => 1, 0 => 2);
+        let mut output = Vec::new();
+        to_tgf(&dag, &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "0 0\n1 1\n2 2\n#\n0 1\n0 2\n"
+        );
+
+        let mut output = Vec::new();
+        to_cytoscape_json(&dag!(2; 0 => 1), &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            concat!(
+                r#"{"elements":{"nodes":[{"data":{"id":"0"}},{"data":{"id":"1"}}],"#,
+                r#""edges":[{"data":{"id":"0-1","source":"0","target":"1"}}]}}"#,
+                "\n"
+            )
+        );
+    }
+
+    #[test]
+    fn d3_export() {
+        let dag = dag!(2; 0 => 1);
+        let mut output = Vec::new();
+        to_d3_json(&dag, &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "{\"nodes\":[{\"id\":0},{\"id\":1}],\"links\":[{\"source\":0,\"target\":1}]}\n"
+        );
+
+        let mut output = Vec::new();
+        to_d3_json_with(
+            &dag,
+            &mut output,
+            |u| if u == 0 { "say \"hi\"" } else { "bye" }.to_string(),
+            |_, _| None,
+            |u| (u == 0).then(|| "g0".to_string()),
+        )
+        .unwrap();
+        assert!(String::from_utf8(output).unwrap().starts_with(
+            r#"{"nodes":[{"id":0,"label":"say \"hi\"","group":"g0"},{"id":1,"label":"bye"}]"#
+        ));
+    }
+
+    #[test]
+    fn labeled_export() {
+        let dag = dag!(3; 0 => 1, 1 => 2);
+        let names = ["a", "b \"quoted\"", "c:\\"];
+        let vertex_label = |u: usize| names[u].to_string();
+        let edge_label = |u: usize, _| (u == 0).then(|| "first".to_string());
+
+        let mut output = Vec::new();
+        to_dot_labeled(&dag, &mut output, vertex_label, edge_label).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("_1[label=\"b \\\"quoted\\\"\"];"));
+        assert!(output.contains("_0 -> _1[label=\"first\"];"));
+        assert!(output.contains("_1 -> _2;"));
+        assert!(output.contains(r#"_2[label="c:\\"];"#));
+
+        let mut output = Vec::new();
+        to_mermaid_labeled(&dag, &mut output, vertex_label, edge_label).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "flowchart TD\n\t_0[\"a\"]\n\t_1[\"b #quot;quoted#quot;\"]\n\t_2[\"c:\\\"]\n\t_0 -->|\"first\"| _1\n\t_1 --> _2\n"
+        );
+
+        let mut output = Vec::new();
+        to_plantuml_labeled(&dag, &mut output, vertex_label, edge_label).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "@startuml\ncomponent \"a\" as _0\ncomponent \"b <U+0022>quoted<U+0022>\" as _1\ncomponent \"c:\\\" as _2\n_0 --> _1 : first\n_1 --> _2\n@enduml\n"
+        );
+
+        let mut output = Vec::new();
+        to_tgf_labeled(&dag, &mut output, vertex_label, edge_label).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "0 a\n1 b \"quoted\"\n2 c:\\\n#\n0 1 first\n1 2\n"
+        );
+
+        let mut output = Vec::new();
+        to_d3_json_labeled(&dag, &mut output, vertex_label, edge_label).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains(r#"{"id":2,"label":"c:\\"}"#));
+        assert!(output.contains(r#"{"source":0,"target":1,"label":"first"}"#));
+
+        let mut output = Vec::new();
+        to_cytoscape_json_labeled(&dag, &mut output, vertex_label, edge_label).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains(r#"{"data":{"id":"1","label":"b \"quoted\""}}"#));
+        assert!(
+            output.contains(r#"{"data":{"id":"0-1","source":"0","target":"1","label":"first"}}"#)
+        );
+    }
+
+    #[test]
+    fn random_sampling() {
+        let mut rng = StdRng::seed_from_u64(7);
+        assert_eq!(
+            DirectedAcyclicGraph::empty(5).get_random_edge(&mut rng),
+            None
+        );
+        assert_eq!(
+            DirectedAcyclicGraph::empty(1).get_random_vertex_pair(&mut rng),
+            None
+        );
+
+        // Edges spread over multiple blocks.
+        let edges = [(0, 1), (0, 40), (3, 17), (38, 39)];
+        let dag = DirectedAcyclicGraph::from_edges_iter(41, edges.into_iter());
+        let mut counts: HashMap<(usize, usize), usize> = HashMap::new();
+        for _ in 0..4000 {
+            *counts
+                .entry(dag.get_random_edge(&mut rng).unwrap())
+                .or_default() += 1;
+        }
+        assert_eq!(counts.len(), edges.len());
+        assert!(counts.values().all(|count| *count > 800));
+
+        for _ in 0..100 {
+            let (u, v) = dag.get_random_vertex_pair(&mut rng).unwrap();
+            assert!(u < v && v < dag.get_vertex_count());
+        }
+
+        let sample = dag.sample_edges(3, &mut rng);
+        assert_eq!(sample.len(), 3);
+        assert!(sample.windows(2).all(|w| w[0] < w[1]));
+        assert!(sample.iter().all(|edge| edges.contains(edge)));
+        assert_eq!(dag.sample_edges(10, &mut rng), edges.to_vec());
+    }
+
+    fn prop_complement_has_non_edges(dag: DirectedAcyclicGraph) -> bool {
+        let complement = dag.complement();
+        complement.iter_edges().eq(dag.iter_non_edges()) && complement.complement() == dag
+    }
+
+    #[test]
+    fn complement_has_non_edges() {
+        quickcheck::QuickCheck::new()
+            .quickcheck(prop_complement_has_non_edges as fn(DirectedAcyclicGraph) -> bool);
+    }
+
+    fn prop_digraph6_roundtrips(dag: DirectedAcyclicGraph) -> bool {
+        let encoded = dag.to_digraph6();
+        encoded
+            .bytes()
+            .skip(1)
+            .all(|byte| (63..=126).contains(&byte))
+            && DirectedAcyclicGraph::from_digraph6(&encoded) == Ok(dag)
+    }
+
+    #[test]
+    fn digraph6_roundtrips() {
+        quickcheck::QuickCheck::new()
+            .quickcheck(prop_digraph6_roundtrips as fn(DirectedAcyclicGraph) -> bool);
+
+        let dag = dag!(5; 0 => 2, 0 => 4, 1 => 3);
+        assert_eq!(dag.to_digraph6(), "&DIG???");
+        assert_eq!(DirectedAcyclicGraph::from_digraph6("&DIG???\n"), Ok(dag));
+        let big = DirectedAcyclicGraph::empty(63);
+        assert!(big.to_digraph6().starts_with("&~??~"));
+        assert_eq!(
+            DirectedAcyclicGraph::from_digraph6(&big.to_digraph6()),
+            Ok(big)
+        );
+        assert_eq!(
+            DirectedAcyclicGraph::from_digraph6("&DI?AO?"),
+            Err(Digraph6Error::NotStrictlyUpper { u: 3, v: 1 })
+        );
+        assert_eq!(
+            DirectedAcyclicGraph::from_digraph6("DI?AO?"),
+            Err(Digraph6Error::MissingHeader)
+        );
+        assert_eq!(
+            DirectedAcyclicGraph::from_digraph6("&DI?AO"),
+            Err(Digraph6Error::WrongLength {
+                expected: 5,
+                len: 4
+            })
+        );
+    }
 }
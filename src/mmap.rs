@@ -0,0 +1,269 @@
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::path::Path;
+
+use memmap2::{Mmap, MmapMut};
+
+use crate::strictly_upper_triangular_logical_matrix::{
+    checked_strictly_upper_triangular_matrix_capacity, unchecked_get_index_from_row_column,
+};
+use crate::DirectedAcyclicGraph;
+
+const MAGIC: &[u8; 8] = b"DOGRAPH1";
+const HEADER_LEN: usize = 16;
+
+enum Storage {
+    ReadOnly(Mmap),
+    ReadWrite(MmapMut),
+}
+
+impl Storage {
+    fn as_slice(&self) -> &[u8] {
+        match self {
+            Storage::ReadOnly(mmap) => mmap,
+            Storage::ReadWrite(mmap) => mmap,
+        }
+    }
+}
+
+/// A directed acyclic graph whose adjacency matrix lives in a memory-mapped
+/// file, so that graphs bigger than the available RAM can be queried without
+/// loading them first.  The operating system pages in only the parts of the
+/// matrix that are actually accessed.
+///
+/// The file consists of a 16 bytes header (the magic `DOGRAPH1` followed by
+/// the vertex count as a little-endian `u64`) and then the bits of the [row-major
+/// packed](crate::StrictlyUpperTriangularLogicalMatrix) matrix, least
+/// significant bit first.
+///
+/// The graph is either read-only ([`Self::open`], [`Self::from_mmap`]) or
+/// read-write ([`Self::create`], [`Self::open_mut`], [`Self::from_mmap_mut`]).
+/// Changes made to a read-write graph reach the file once they're
+/// [flushed](Self::flush) or the graph is dropped.
+pub struct MmapDirectedAcyclicGraph {
+    vertex_count: usize,
+    storage: Storage,
+}
+
+// `None` if the file would be too big to address.
+fn get_file_len(vertex_count: usize) -> Option<usize> {
+    checked_strictly_upper_triangular_matrix_capacity(vertex_count)?
+        .div_ceil(8)
+        .checked_add(HEADER_LEN)
+}
+
+fn read_vertex_count(bytes: &[u8]) -> io::Result<usize> {
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+    if bytes.len() < HEADER_LEN || &bytes[..MAGIC.len()] != MAGIC {
+        return Err(invalid("not a dograph adjacency matrix file"));
+    }
+    let vertex_count = u64::from_le_bytes(bytes[MAGIC.len()..HEADER_LEN].try_into().unwrap());
+    let vertex_count =
+        usize::try_from(vertex_count).map_err(|_| invalid("vertex count out of range"))?;
+    let file_len =
+        get_file_len(vertex_count).ok_or_else(|| invalid("vertex count out of range"))?;
+    if bytes.len() < file_len {
+        return Err(invalid("truncated adjacency matrix"));
+    }
+    Ok(vertex_count)
+}
+
+impl MmapDirectedAcyclicGraph {
+    /// Creates (or truncates) the file at `path` holding an empty DAG and maps
+    /// it read-write.  Fails with [`io::ErrorKind::InvalidInput`] if the matrix
+    /// wouldn't be addressable.
+    pub fn create<P: AsRef<Path>>(path: P, vertex_count: usize) -> io::Result<Self> {
+        // Before touching the file so that an existing one is left intact.
+        let file_len = get_file_len(vertex_count).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "vertex count out of range")
+        })?;
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        file.set_len(file_len as u64)?;
+        // SAFETY: See `from_mmap`.
+        let mut mmap = unsafe { MmapMut::map_mut(&file)? };
+        mmap[..MAGIC.len()].copy_from_slice(MAGIC);
+        mmap[MAGIC.len()..HEADER_LEN].copy_from_slice(&(vertex_count as u64).to_le_bytes());
+        Self::from_mmap_mut(mmap)
+    }
+
+    /// Writes `dag` to a new file at `path` and maps it read-write.
+    pub fn create_from_dag<P: AsRef<Path>>(
+        path: P,
+        dag: &DirectedAcyclicGraph,
+    ) -> io::Result<Self> {
+        let mut result = Self::create(path, dag.get_vertex_count())?;
+        for (u, v) in dag.iter_edges() {
+            result.set_edge(u, v, true);
+        }
+        Ok(result)
+    }
+
+    /// Maps the file at `path` read-only.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = File::open(path)?;
+        // SAFETY: See `from_mmap`.
+        Self::from_mmap(unsafe { Mmap::map(&file)? })
+    }
+
+    /// Maps the file at `path` read-write.
+    pub fn open_mut<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        // SAFETY: See `from_mmap`.
+        Self::from_mmap_mut(unsafe { MmapMut::map_mut(&file)? })
+    }
+
+    /// Wraps an existing read-only mapping.  Fails with
+    /// [`io::ErrorKind::InvalidData`] if it doesn't hold a DAG.
+    ///
+    /// Memory maps are inherently unsafe: the caller must make sure the
+    /// underlying file isn't modified by anyone else while it's mapped.
+    pub fn from_mmap(mmap: Mmap) -> io::Result<Self> {
+        Ok(Self {
+            vertex_count: read_vertex_count(&mmap)?,
+            storage: Storage::ReadOnly(mmap),
+        })
+    }
+
+    /// Same as [`Self::from_mmap`] but for a read-write mapping.
+    pub fn from_mmap_mut(mmap: MmapMut) -> io::Result<Self> {
+        Ok(Self {
+            vertex_count: read_vertex_count(&mmap)?,
+            storage: Storage::ReadWrite(mmap),
+        })
+    }
+
+    #[inline]
+    pub fn get_vertex_count(&self) -> usize {
+        self.vertex_count
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        matches!(self.storage, Storage::ReadOnly(_))
+    }
+
+    #[inline]
+    fn index_from_row_column(&self, u: usize, v: usize) -> usize {
+        assert!(u < self.get_vertex_count());
+        assert!(v < self.get_vertex_count());
+        assert!(u < v);
+        unchecked_get_index_from_row_column(u, v, self.vertex_count)
+    }
+
+    #[inline]
+    fn get_bit(&self, index: usize) -> bool {
+        (self.storage.as_slice()[HEADER_LEN + index / 8] >> (index % 8)) & 1 == 1
+    }
+
+    /// Requires `u < v`.  Panics otherwise.
+    pub fn get_edge(&self, u: usize, v: usize) -> bool {
+        self.get_bit(self.index_from_row_column(u, v))
+    }
+
+    /// Requires `u < v` and a read-write mapping.  Panics otherwise.
+    pub fn set_edge(&mut self, u: usize, v: usize, exists: bool) {
+        let index = self.index_from_row_column(u, v);
+        let mmap = match &mut self.storage {
+            Storage::ReadWrite(mmap) => mmap,
+            Storage::ReadOnly(_) => panic!("cannot modify a read-only memory-mapped DAG"),
+        };
+        let byte = &mut mmap[HEADER_LEN + index / 8];
+        if exists {
+            *byte |= 1 << (index % 8);
+        } else {
+            *byte &= !(1 << (index % 8));
+        }
+    }
+
+    /// Iterates over the edges in an order that favors CPU cache locality.
+    pub fn iter_edges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..self.vertex_count).flat_map(move |u| self.iter_children(u).map(move |v| (u, v)))
+    }
+
+    /// Iterates over vertices `v` such that there's an edge `(u, v)` in the
+    /// DAG.
+    pub fn iter_children(&self, u: usize) -> impl Iterator<Item = usize> + '_ {
+        assert!(u < self.get_vertex_count());
+        // Row `u` occupies consecutive bits.
+        let start = unchecked_get_index_from_row_column(u, u + 1, self.vertex_count);
+        ((u + 1)..self.vertex_count)
+            .zip(start..)
+            .filter(move |(_, index)| self.get_bit(*index))
+            .map(|(v, _)| v)
+    }
+
+    /// Writes outstanding changes to the file.  A no-op for read-only
+    /// mappings.
+    pub fn flush(&self) -> io::Result<()> {
+        match &self.storage {
+            Storage::ReadOnly(_) => Ok(()),
+            Storage::ReadWrite(mmap) => mmap.flush(),
+        }
+    }
+
+    /// Loads the whole graph into memory.
+    pub fn to_dag(&self) -> DirectedAcyclicGraph {
+        DirectedAcyclicGraph::from_edges_iter(self.vertex_count, self.iter_edges())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("dograph-{}-{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn roundtrips_through_file() {
+        let path = get_temp_path("roundtrip");
+        let dag = crate::dag!(5; 0 => 1, 0 => 4, 2 => 3, 3 => 4);
+        let mut mapped = MmapDirectedAcyclicGraph::create_from_dag(&path, &dag).unwrap();
+        mapped.set_edge(1, 2, true);
+        mapped.flush().unwrap();
+        drop(mapped);
+
+        let reopened = MmapDirectedAcyclicGraph::open(&path).unwrap();
+        assert!(reopened.is_read_only());
+        assert_eq!(reopened.get_vertex_count(), 5);
+        assert!(reopened.get_edge(1, 2));
+        assert_eq!(reopened.iter_children(0).collect::<Vec<_>>(), vec![1, 4]);
+        assert_eq!(
+            reopened.to_dag(),
+            crate::dag!(5; 0 => 1, 0 => 4, 1 => 2, 2 => 3, 3 => 4)
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn rejects_foreign_files() {
+        let path = get_temp_path("foreign");
+        std::fs::write(&path, b"definitely not a DAG").unwrap();
+        let error = MmapDirectedAcyclicGraph::open(&path).err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+        let mut header = MAGIC.to_vec();
+        header.extend_from_slice(&u64::MAX.to_le_bytes());
+        std::fs::write(&path, header).unwrap();
+        let error = MmapDirectedAcyclicGraph::open(&path).err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn create_leaves_file_intact_on_invalid_input() {
+        let path = get_temp_path("invalid-input");
+        std::fs::write(&path, b"precious").unwrap();
+        let error = MmapDirectedAcyclicGraph::create(&path, usize::MAX)
+            .err()
+            .unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(std::fs::read(&path).unwrap(), b"precious");
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,545 @@
+use std::ops::{Add, RangeInclusive};
+
+use crate::weighted::WeightedDag;
+use crate::DirectedAcyclicGraph;
+
+// Depth-first enumeration of the `from` -> `to` paths that only ever visit
+// vertices for which `is_feasible(weight of the path up to and including the
+// vertex, vertex)` holds.  Paths are returned in lexicographical order.
+fn get_paths_pruned<T, W, F>(
+    dag: &DirectedAcyclicGraph,
+    from: usize,
+    to: usize,
+    zero: T,
+    weight: W,
+    is_feasible: F,
+) -> Vec<Vec<usize>>
+where
+    T: Copy + Add<Output = T>,
+    W: Fn(usize, usize) -> T,
+    F: Fn(T, usize) -> bool,
+{
+    let mut result: Vec<Vec<usize>> = Vec::new();
+    if !is_feasible(zero, from) {
+        return result;
+    }
+    let mut stack: Vec<(Vec<usize>, T)> = vec![(vec![from], zero)];
+    while let Some((path, path_weight)) = stack.pop() {
+        let u = *path.last().unwrap();
+        if u == to {
+            result.push(path);
+            continue;
+        }
+        let mut next: Vec<(Vec<usize>, T)> = dag
+            .iter_children(u)
+            .take_while(|v| *v <= to)
+            .filter_map(|v| {
+                let extended_weight = path_weight + weight(u, v);
+                if !is_feasible(extended_weight, v) {
+                    return None;
+                }
+                let mut extended = path.clone();
+                extended.push(v);
+                Some((extended, extended_weight))
+            })
+            .collect();
+        next.reverse();
+        stack.extend(next);
+    }
+    result
+}
+
+// A mapping: vertex -> the result of `combine` over all the `vertex` -> `to`
+// paths, or `None` for vertices from which `to` is unreachable.  `extend(u, v,
+// x)` extends the value `x` of a `v` -> `to` path by the edge `(u, v)`.
+fn get_distances_to<T, E, C>(
+    dag: &DirectedAcyclicGraph,
+    to: usize,
+    zero: T,
+    extend: E,
+    combine: C,
+) -> Vec<Option<T>>
+where
+    T: Copy,
+    E: Fn(usize, usize, T) -> T,
+    C: Fn(T, T) -> T,
+{
+    let mut distances: Vec<Option<T>> = vec![None; dag.get_vertex_count()];
+    distances[to] = Some(zero);
+    for u in (0..to).rev() {
+        distances[u] = dag
+            .iter_children(u)
+            .take_while(|v| *v <= to)
+            .filter_map(|v| distances[v].map(|distance| extend(u, v, distance)))
+            .reduce(&combine);
+    }
+    distances
+}
+
+/// Returns all the paths from `from` to `to` whose length (number of edges)
+/// is within `lengths`, in lexicographical order.
+///
+/// The search is pruned with the precomputed shortest and longest distances
+/// to `to`, so prefixes that are already too long or can no longer become long
+/// enough aren't explored.  That keeps bounded queries tractable even if there
+/// are exponentially many paths in total.
+pub fn get_paths_with_length_in(
+    dag: &DirectedAcyclicGraph,
+    from: usize,
+    to: usize,
+    lengths: RangeInclusive<usize>,
+) -> Vec<Vec<usize>> {
+    assert!(from < dag.get_vertex_count());
+    assert!(to < dag.get_vertex_count());
+    let shortest = get_distances_to(dag, to, 0, |_, _, d| d + 1, std::cmp::min);
+    let longest = get_distances_to(dag, to, 0, |_, _, d| d + 1, std::cmp::max);
+    get_paths_pruned(
+        dag,
+        from,
+        to,
+        0,
+        |_, _| 1,
+        |length, u| match (shortest[u], longest[u]) {
+            (Some(shortest), Some(longest)) => {
+                length + shortest <= *lengths.end() && length + longest >= *lengths.start()
+            }
+            _ => false,
+        },
+    )
+}
+
+/// Returns all the paths from `from` to `to` whose total weight is at most
+/// `max_weight`, in lexicographical order.  `weight(u, v)` is the weight of
+/// the edge `(u, v)` and must not be negative.  `T::default()` is assumed to
+/// be zero.
+///
+/// Like [`get_paths_with_length_in`], the search is pruned with the
+/// precomputed lightest distances to `to`.
+pub fn get_paths_with_weight_at_most<T, W>(
+    dag: &DirectedAcyclicGraph,
+    from: usize,
+    to: usize,
+    max_weight: T,
+    weight: W,
+) -> Vec<Vec<usize>>
+where
+    T: Copy + PartialOrd + Add<Output = T> + Default,
+    W: Fn(usize, usize) -> T,
+{
+    assert!(from < dag.get_vertex_count());
+    assert!(to < dag.get_vertex_count());
+    let lightest = get_distances_to(
+        dag,
+        to,
+        T::default(),
+        |u, v, d| weight(u, v) + d,
+        |a, b| if b < a { b } else { a },
+    );
+    get_paths_pruned(dag, from, to, T::default(), &weight, |path_weight, u| {
+        lightest[u].is_some_and(|lightest| path_weight + lightest <= max_weight)
+    })
+}
+
+/// Returns the path from `from` to `to` with the most edges, or `None` if
+/// `to` isn't reachable from `from`.  Ties are broken by the lexicographically
+/// smallest path.
+pub fn get_longest_path_between(
+    dag: &DirectedAcyclicGraph,
+    from: usize,
+    to: usize,
+) -> Option<Vec<usize>> {
+    get_heaviest_path_between(dag, from, to, |_, _| 1).map(|(path, _)| path)
+}
+
+/// Same as [`get_longest_path_between`] but maximizes the total weight of the
+/// path instead of its length.  `weight(u, v)` is the weight of the edge `(u,
+/// v)`.  Returns the path along with its weight.  `T::default()` is assumed to
+/// be zero.
+pub fn get_heaviest_path_between<T, W>(
+    dag: &DirectedAcyclicGraph,
+    from: usize,
+    to: usize,
+    weight: W,
+) -> Option<(Vec<usize>, T)>
+where
+    T: Copy + PartialOrd + Add<Output = T> + Default,
+    W: Fn(usize, usize) -> T,
+{
+    assert!(from < dag.get_vertex_count());
+    assert!(to < dag.get_vertex_count());
+    if from > to {
+        return None;
+    }
+    // A mapping: vertex -> (weight of the heaviest path to `to`, next vertex
+    // on that path)
+    let mut heaviest: Vec<Option<(T, usize)>> = vec![None; dag.get_vertex_count()];
+    heaviest[to] = Some((T::default(), to));
+    for u in (from..to).rev() {
+        for v in dag.iter_children(u).take_while(|v| *v <= to) {
+            if let Some((distance, _)) = heaviest[v] {
+                let candidate = weight(u, v) + distance;
+                match heaviest[u] {
+                    Some((best, _)) if best >= candidate => {}
+                    _ => heaviest[u] = Some((candidate, v)),
+                }
+            }
+        }
+    }
+    let (total_weight, _) = heaviest[from]?;
+    let mut path: Vec<usize> = vec![from];
+    while *path.last().unwrap() != to {
+        let (_, next) = heaviest[*path.last().unwrap()].unwrap();
+        path.push(next);
+    }
+    Some((path, total_weight))
+}
+
+/// Finds a path from `from` to `to` maximizing the smallest weight of its
+/// edges (its bottleneck), e.g. the bandwidth along a chain of dependencies.
+/// Returns the path along with its bottleneck, or `None` if there's no path
+/// with at least one edge.
+pub fn get_widest_path_between<T: Copy + PartialOrd>(
+    dag: &WeightedDag<T>,
+    from: usize,
+    to: usize,
+) -> Option<(Vec<usize>, T)> {
+    let weight = |u, v| dag[(u, v)];
+    assert!(from < dag.get_vertex_count());
+    assert!(to < dag.get_vertex_count());
+    if from >= to {
+        return None;
+    }
+    // A mapping: vertex -> (bottleneck of the widest path to `to`, next vertex
+    // on that path)
+    let mut widest: Vec<Option<(T, usize)>> = vec![None; dag.get_vertex_count()];
+    for u in (from..to).rev() {
+        for v in dag.iter_children(u).take_while(|v| *v <= to) {
+            let candidate = match widest[v] {
+                _ if v == to => weight(u, v),
+                Some((width, _)) => {
+                    let w = weight(u, v);
+                    if w < width {
+                        w
+                    } else {
+                        width
+                    }
+                }
+                None => continue,
+            };
+            match widest[u] {
+                Some((best, _)) if best >= candidate => {}
+                _ => widest[u] = Some((candidate, v)),
+            }
+        }
+    }
+    let (bottleneck, _) = widest[from]?;
+    let mut path: Vec<usize> = vec![from];
+    while *path.last().unwrap() != to {
+        let (_, next) = widest[*path.last().unwrap()].unwrap();
+        path.push(next);
+    }
+    Some((path, bottleneck))
+}
+
+/// Returns up to `k` source-to-sink paths with the most edges, longest first,
+/// e.g. the critical path of a build along with the runner-ups.  Isolated
+/// vertices count as paths with no edges.  See [`get_heaviest_paths`].
+pub fn get_longest_paths(dag: &DirectedAcyclicGraph, k: usize) -> Vec<Vec<usize>> {
+    get_heaviest_paths(dag, k, |_, _| 1usize)
+        .into_iter()
+        .map(|(path, _)| path)
+        .collect()
+}
+
+/// Same as [`get_longest_paths`] but maximizes the total weight of the paths
+/// instead of their lengths.  `weight(u, v)` is the weight of the edge `(u,
+/// v)`.  Returns the paths along with their weights, heaviest first.  Ties are
+/// broken deterministically.  `T::default()` is assumed to be zero.
+///
+/// For every vertex, the `k` heaviest paths from it to a sink are kept, each
+/// as the weight, the next vertex and the rank of the path's remainder among
+/// the next vertex's paths, so it takes `O(|E| * k * log k)` time.
+pub fn get_heaviest_paths<T, W>(
+    dag: &DirectedAcyclicGraph,
+    k: usize,
+    weight: W,
+) -> Vec<(Vec<usize>, T)>
+where
+    T: Copy + PartialOrd + Add<Output = T> + Default,
+    W: Fn(usize, usize) -> T,
+{
+    let by_weight_descending = |a: &(T, usize, usize), b: &(T, usize, usize)| {
+        b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal)
+    };
+    let vertex_count = dag.get_vertex_count();
+    // A mapping: vertex -> the heaviest paths to a sink as (weight, next
+    // vertex, rank among the next vertex's paths), with the vertex itself as
+    // the next one for sinks.
+    let mut heaviest: Vec<Vec<(T, usize, usize)>> = vec![Vec::new(); vertex_count];
+    let mut has_parents: Vec<bool> = vec![false; vertex_count];
+    for u in (0..vertex_count).rev() {
+        let mut candidates: Vec<(T, usize, usize)> = Vec::new();
+        for v in dag.iter_children(u) {
+            has_parents[v] = true;
+            let edge_weight = weight(u, v);
+            candidates.extend(
+                heaviest[v]
+                    .iter()
+                    .enumerate()
+                    .map(|(rank, (w, _, _))| (edge_weight + *w, v, rank)),
+            );
+        }
+        if candidates.is_empty() {
+            candidates.push((T::default(), u, 0));
+        }
+        candidates.sort_by(by_weight_descending);
+        candidates.truncate(k);
+        heaviest[u] = candidates;
+    }
+
+    let mut best: Vec<(T, usize, usize)> = (0..vertex_count)
+        .filter(|u| !has_parents[*u])
+        .flat_map(|u| {
+            heaviest[u]
+                .iter()
+                .enumerate()
+                .map(move |(rank, (w, _, _))| (*w, u, rank))
+        })
+        .collect();
+    best.sort_by(by_weight_descending);
+    best.truncate(k);
+    best.into_iter()
+        .map(|(total_weight, source, rank)| {
+            let mut path: Vec<usize> = vec![source];
+            let (mut u, mut rank) = (source, rank);
+            loop {
+                let (_, next, next_rank) = heaviest[u][rank];
+                if next == u {
+                    break;
+                }
+                path.push(next);
+                (u, rank) = (next, next_rank);
+            }
+            (path, total_weight)
+        })
+        .collect()
+}
+
+/// Computes a mapping: vertex -> length (number of edges) of the longest path
+/// starting at vertex, i.e. its out-eccentricity with respect to the longest
+/// distance.
+pub fn get_out_eccentricities(dag: &DirectedAcyclicGraph) -> Vec<usize> {
+    let mut result: Vec<usize> = vec![0; dag.get_vertex_count()];
+    for u in (0..dag.get_vertex_count()).rev() {
+        result[u] = dag
+            .iter_children(u)
+            .map(|v| result[v] + 1)
+            .max()
+            .unwrap_or(0);
+    }
+    result
+}
+
+/// Computes a mapping: vertex -> length (number of edges) of the longest path
+/// ending at vertex, i.e. its in-eccentricity with respect to the longest
+/// distance.  Also known as the depth of the vertex.
+pub fn get_in_eccentricities(dag: &DirectedAcyclicGraph) -> Vec<usize> {
+    let mut result: Vec<usize> = vec![0; dag.get_vertex_count()];
+    for u in 0..dag.get_vertex_count() {
+        for v in dag.iter_children(u) {
+            result[v] = std::cmp::max(result[v], result[u] + 1);
+        }
+    }
+    result
+}
+
+/// The length (number of edges) of the longest path in the DAG.  0 for DAGs
+/// without edges.
+pub fn get_diameter(dag: &DirectedAcyclicGraph) -> usize {
+    get_out_eccentricities(dag).into_iter().max().unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paths_of_bounded_length() {
+        //  0 -> 1 -> 2 -> 3
+        //  0 ------> 2
+        //  0 -----------> 3
+        let dag = crate::dag!(4; 0 => 1, 1 => 2, 2 => 3, 0 => 2, 0 => 3);
+        assert_eq!(
+            get_paths_with_length_in(&dag, 0, 3, 0..=2),
+            vec![vec![0, 2, 3], vec![0, 3]]
+        );
+        assert_eq!(
+            get_paths_with_length_in(&dag, 0, 3, 2..=3),
+            vec![vec![0, 1, 2, 3], vec![0, 2, 3]]
+        );
+        assert_eq!(
+            get_paths_with_length_in(&dag, 1, 3, 0..=1),
+            Vec::<Vec<usize>>::new()
+        );
+        assert_eq!(get_paths_with_length_in(&dag, 2, 2, 0..=0), vec![vec![2]]);
+    }
+
+    #[test]
+    fn paths_of_bounded_weight() {
+        let dag = crate::dag!(4; 0 => 1, 1 => 2, 2 => 3, 0 => 2, 0 => 3);
+        let weight = |u: usize, v: usize| (v - u) as f64 * 1.5;
+        assert_eq!(
+            get_paths_with_weight_at_most(&dag, 0, 3, 4.5, weight),
+            vec![vec![0, 1, 2, 3], vec![0, 2, 3], vec![0, 3]]
+        );
+        let weight = |u: usize, v: usize| if (u, v) == (0, 3) { 10 } else { 1 };
+        assert_eq!(
+            get_paths_with_weight_at_most(&dag, 0, 3, 2, weight),
+            vec![vec![0, 2, 3]]
+        );
+    }
+
+    fn prop_bounded_paths_are_paths(dag: DirectedAcyclicGraph) -> bool {
+        let to = dag.get_vertex_count() - 1;
+        get_paths_with_length_in(&dag, 0, to, 1..=3)
+            .iter()
+            .all(|path| {
+                (2..=4).contains(&path.len())
+                    && path[0] == 0
+                    && *path.last().unwrap() == to
+                    && path.windows(2).all(|edge| dag.get_edge(edge[0], edge[1]))
+            })
+    }
+
+    #[test]
+    fn bounded_paths_are_paths() {
+        quickcheck::QuickCheck::new()
+            .quickcheck(prop_bounded_paths_are_paths as fn(DirectedAcyclicGraph) -> bool);
+    }
+
+    #[test]
+    fn eccentricities_and_diameter() {
+        //  0 -> 1 -> 2 -> 3 -> 4
+        //  0 -----------> 3
+        let dag = crate::dag!(5; 0 => 1, 1 => 2, 2 => 3, 0 => 3, 3 => 4);
+        assert_eq!(get_out_eccentricities(&dag), vec![4, 3, 2, 1, 0]);
+        assert_eq!(get_in_eccentricities(&dag), vec![0, 1, 2, 3, 4]);
+        assert_eq!(get_diameter(&dag), 4);
+        assert_eq!(get_diameter(&DirectedAcyclicGraph::empty(3)), 0);
+    }
+
+    #[test]
+    fn longest_path_between_pair() {
+        //  0 -> 1 -> 2 -> 3 -> 4
+        //  0 -----------> 3
+        let dag = crate::dag!(5; 0 => 1, 1 => 2, 2 => 3, 0 => 3, 3 => 4);
+        assert_eq!(get_longest_path_between(&dag, 0, 3), Some(vec![0, 1, 2, 3]));
+        assert_eq!(get_longest_path_between(&dag, 4, 4), Some(vec![4]));
+        assert_eq!(get_longest_path_between(&dag, 1, 0), None);
+        assert_eq!(
+            get_heaviest_path_between(&dag, 0, 4, |u, v| if (u, v) == (0, 3) { 5.0 } else { 1.0 }),
+            Some((vec![0, 3, 4], 6.0))
+        );
+    }
+
+    fn prop_longest_path_between_is_longest(dag: DirectedAcyclicGraph) -> bool {
+        let to = dag.get_vertex_count() - 1;
+        let longest = get_paths_with_length_in(&dag, 0, to, 0..=to)
+            .into_iter()
+            .map(|path| path.len())
+            .max();
+        match get_longest_path_between(&dag, 0, to) {
+            Some(path) => {
+                path.first() == Some(&0)
+                    && path.last() == Some(&to)
+                    && path.windows(2).all(|edge| dag.get_edge(edge[0], edge[1]))
+                    && Some(path.len()) == longest
+            }
+            None => longest.is_none(),
+        }
+    }
+
+    #[test]
+    fn longest_path_between_is_longest() {
+        quickcheck::QuickCheck::new()
+            .rng(quickcheck::Gen::new(10))
+            .quickcheck(prop_longest_path_between_is_longest as fn(DirectedAcyclicGraph) -> bool);
+    }
+
+    #[test]
+    fn widest_path_between_pair() {
+        //  0 -(5)-> 1 -(1)-> 3
+        //  0 -(2)-> 2 -(3)-> 3
+        let dag =
+            WeightedDag::from_dag(
+                crate::dag!(4; 0 => 1, 1 => 3, 0 => 2, 2 => 3),
+                |u, v| match (u, v) {
+                    (0, 1) => 5,
+                    (1, 3) => 1,
+                    (0, 2) => 2,
+                    (2, 3) => 3,
+                    _ => unreachable!(),
+                },
+            );
+        assert_eq!(
+            get_widest_path_between(&dag, 0, 3),
+            Some((vec![0, 2, 3], 2))
+        );
+        assert_eq!(get_widest_path_between(&dag, 0, 1), Some((vec![0, 1], 5)));
+        assert_eq!(get_widest_path_between(&dag, 3, 3), None);
+        assert_eq!(get_widest_path_between(&dag, 1, 2), None);
+    }
+
+    #[test]
+    fn longest_paths_of_diamond() {
+        //  0 -> 1 -> 2 -> 3
+        //  0 -------> 2
+        //  4
+        let dag = crate::dag!(5; 0 => 1, 1 => 2, 2 => 3, 0 => 2);
+        assert_eq!(
+            get_longest_paths(&dag, 5),
+            vec![vec![0, 1, 2, 3], vec![0, 2, 3], vec![4]]
+        );
+        assert_eq!(get_longest_paths(&dag, 1), vec![vec![0, 1, 2, 3]]);
+        assert_eq!(
+            get_heaviest_paths(&dag, 2, |u, v| if (u, v) == (0, 2) { 5 } else { 1 }),
+            vec![(vec![0, 2, 3], 6), (vec![0, 1, 2, 3], 3)]
+        );
+    }
+
+    fn prop_heaviest_paths_are_heaviest(dag: DirectedAcyclicGraph) -> bool {
+        let weight = |u: usize, v: usize| (u * 3 + v) % 5;
+        let vertex_count = dag.get_vertex_count();
+        let is_source = |u: usize| (0..u).all(|w| !dag.get_edge(w, u));
+        let is_sink = |u: usize| dag.iter_children(u).next().is_none();
+        let mut all_weights: Vec<usize> = Vec::new();
+        for from in (0..vertex_count).filter(|u| is_source(*u)) {
+            for to in (from..vertex_count).filter(|v| is_sink(*v)) {
+                let paths = get_paths_with_length_in(&dag, from, to, 0..=vertex_count);
+                all_weights.extend(
+                    paths
+                        .iter()
+                        .map(|path| path.windows(2).map(|w| weight(w[0], w[1])).sum::<usize>()),
+                );
+            }
+        }
+        all_weights.sort_unstable_by(|a, b| b.cmp(a));
+        let k = 4;
+        let heaviest = get_heaviest_paths(&dag, k, weight);
+        all_weights.truncate(k);
+        heaviest.iter().map(|(_, w)| *w).eq(all_weights)
+            && heaviest.iter().all(|(path, w)| {
+                is_source(path[0])
+                    && is_sink(*path.last().unwrap())
+                    && path.windows(2).all(|p| dag.get_edge(p[0], p[1]))
+                    && path.windows(2).map(|p| weight(p[0], p[1])).sum::<usize>() == *w
+            })
+    }
+
+    #[test]
+    fn heaviest_paths_are_heaviest() {
+        quickcheck::QuickCheck::new()
+            .rng(quickcheck::Gen::new(12))
+            .quickcheck(prop_heaviest_paths_are_heaviest as fn(DirectedAcyclicGraph) -> bool);
+    }
+}
@@ -0,0 +1,172 @@
+use std::sync::Arc;
+
+use fixedbitset::FixedBitSet;
+
+use crate::strictly_upper_triangular_logical_matrix::{
+    iter_matrix, strictly_upper_triangular_matrix_capacity, unchecked_get_index_from_row_column,
+};
+use crate::DirectedAcyclicGraph;
+
+const CHUNK_BITS: usize = 4096;
+
+/// An immutable directed acyclic graph where "modifications" return a new DAG
+/// that shares all of the unchanged storage with the original one.
+///
+/// The adjacency matrix is split into fixed-size chunks of bits, each behind
+/// an [`Arc`].  [`PersistentDirectedAcyclicGraph::with_edge`] copies only the
+/// one chunk containing the edge plus the (much smaller) list of chunk
+/// pointers, making it cheap to keep many historical snapshots of an evolving
+/// graph.
+#[derive(Clone)]
+pub struct PersistentDirectedAcyclicGraph {
+    vertex_count: usize,
+    chunks: Vec<Arc<FixedBitSet>>,
+}
+
+impl std::fmt::Debug for PersistentDirectedAcyclicGraph {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ones: Vec<(usize, usize)> = self.iter_edges().collect();
+        write!(
+            f,
+            "PersistentDirectedAcyclicGraph({}, {:?})",
+            self.get_vertex_count(),
+            ones
+        )?;
+        Ok(())
+    }
+}
+
+impl PersistentDirectedAcyclicGraph {
+    pub fn empty(vertex_count: usize) -> Self {
+        let capacity = strictly_upper_triangular_matrix_capacity(vertex_count);
+        let chunk_count = capacity.div_ceil(CHUNK_BITS);
+        // All the chunks of an empty DAG can share a single allocation.
+        let zeroed = Arc::new(FixedBitSet::with_capacity(CHUNK_BITS));
+        Self {
+            vertex_count,
+            chunks: vec![zeroed; chunk_count],
+        }
+    }
+
+    #[inline]
+    pub fn get_vertex_count(&self) -> usize {
+        self.vertex_count
+    }
+
+    #[inline]
+    fn index_from_row_column(&self, u: usize, v: usize) -> usize {
+        assert!(u < self.get_vertex_count());
+        assert!(v < self.get_vertex_count());
+        assert!(u < v);
+        unchecked_get_index_from_row_column(u, v, self.vertex_count)
+    }
+
+    /// Requires `u < v`.  Panics otherwise.
+    pub fn get_edge(&self, u: usize, v: usize) -> bool {
+        let index = self.index_from_row_column(u, v);
+        self.chunks[index / CHUNK_BITS][index % CHUNK_BITS]
+    }
+
+    /// Returns a new DAG that differs from `self` only in the edge `(u, v)`.
+    /// `self` is left unchanged.
+    ///
+    /// Requires `u < v`.  Panics otherwise.
+    pub fn with_edge_set(&self, u: usize, v: usize, exists: bool) -> Self {
+        let index = self.index_from_row_column(u, v);
+        let mut result = self.clone();
+        let chunk = Arc::make_mut(&mut result.chunks[index / CHUNK_BITS]);
+        chunk.set(index % CHUNK_BITS, exists);
+        result
+    }
+
+    /// Returns a new DAG with the edge `(u, v)` added.  See
+    /// [`Self::with_edge_set`].
+    pub fn with_edge(&self, u: usize, v: usize) -> Self {
+        self.with_edge_set(u, v, true)
+    }
+
+    /// Returns a new DAG with the edge `(u, v)` removed.  See
+    /// [`Self::with_edge_set`].
+    pub fn without_edge(&self, u: usize, v: usize) -> Self {
+        self.with_edge_set(u, v, false)
+    }
+
+    /// Iterates over the edges in an order that favors CPU cache locality.
+    pub fn iter_edges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        iter_matrix(self.vertex_count)
+            .filter(move |(_, _, index)| self.chunks[index / CHUNK_BITS][index % CHUNK_BITS])
+            .map(|(u, v, _)| (u, v))
+    }
+
+    /// Iterates over vertices `v` such that there's an edge `(u, v)` in the
+    /// DAG.
+    pub fn iter_children(&self, u: usize) -> impl Iterator<Item = usize> + '_ {
+        assert!(u < self.get_vertex_count());
+        ((u + 1)..self.vertex_count).filter(move |v| self.get_edge(u, *v))
+    }
+
+    /// Returns `true` iff `self` and `other` share the storage of the chunk that
+    /// contains the edge `(u, v)`.
+    pub fn shares_storage_of_edge(&self, other: &Self, u: usize, v: usize) -> bool {
+        let index = self.index_from_row_column(u, v);
+        let chunk = index / CHUNK_BITS;
+        other.vertex_count == self.vertex_count
+            && Arc::ptr_eq(&self.chunks[chunk], &other.chunks[chunk])
+    }
+
+    /// Copy the edges into a mutable [`DirectedAcyclicGraph`].
+    pub fn to_dag(&self) -> DirectedAcyclicGraph {
+        DirectedAcyclicGraph::from_edges_iter(self.vertex_count, self.iter_edges())
+    }
+}
+
+impl From<&DirectedAcyclicGraph> for PersistentDirectedAcyclicGraph {
+    fn from(dag: &DirectedAcyclicGraph) -> Self {
+        let mut chunks: Vec<FixedBitSet> =
+            PersistentDirectedAcyclicGraph::empty(dag.get_vertex_count())
+                .chunks
+                .iter()
+                .map(|chunk| (**chunk).clone())
+                .collect();
+        for (u, v) in dag.iter_edges() {
+            let index = unchecked_get_index_from_row_column(u, v, dag.get_vertex_count());
+            chunks[index / CHUNK_BITS].insert(index % CHUNK_BITS);
+        }
+        Self {
+            vertex_count: dag.get_vertex_count(),
+            chunks: chunks.into_iter().map(Arc::new).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshots_share_unchanged_chunks() {
+        let vertex_count = 200;
+        let original =
+            PersistentDirectedAcyclicGraph::from(&DirectedAcyclicGraph::from_edges_iter(
+                vertex_count,
+                vec![(0, 1), (150, 199)].into_iter(),
+            ));
+        let modified = original.with_edge(0, 2).without_edge(150, 199);
+
+        assert_eq!(
+            original.iter_edges().collect::<Vec<_>>(),
+            vec![(0, 1), (150, 199)]
+        );
+        assert_eq!(
+            modified.iter_edges().collect::<Vec<_>>(),
+            vec![(0, 1), (0, 2)]
+        );
+        assert_eq!(modified.iter_children(0).collect::<Vec<_>>(), vec![1, 2]);
+        assert!(!original.shares_storage_of_edge(&modified, 0, 2));
+        assert!(original.shares_storage_of_edge(&modified, 100, 101));
+        assert_eq!(
+            modified.to_dag(),
+            DirectedAcyclicGraph::from_edges_iter(vertex_count, vec![(0, 1), (0, 2)].into_iter())
+        );
+    }
+}
@@ -0,0 +1,1433 @@
+use std::collections::{HashMap, HashSet};
+
+use std::ops::{Add, Sub};
+
+use fixedbitset::FixedBitSet;
+#[cfg(feature = "num-bigint")]
+use num_bigint::BigUint;
+#[cfg(feature = "num-bigint")]
+use num_traits::{One, Zero};
+
+use crate::algorithm::{get_descendants, get_induced_subgraph, transitive_reduction};
+use crate::{DirectedAcyclicGraph, StrictlyLowerTriangularLogicalMatrix};
+
+/// See [`Poset::iter_maximal_antichains`].
+pub struct MaximalAntichainsIterator {
+    incomparable: Vec<FixedBitSet>,
+    // Pending Bron–Kerbosch calls: (antichain, candidates, excluded)
+    stack: Vec<(Vec<usize>, FixedBitSet, FixedBitSet)>,
+}
+
+impl Iterator for MaximalAntichainsIterator {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((mut antichain, mut candidates, mut excluded)) = self.stack.pop() {
+            // Any maximal antichain extending `antichain` contains either the
+            // pivot or one of the elements comparable with it.
+            let pivot = candidates
+                .union(&excluded)
+                .max_by_key(|u| candidates.intersection(&self.incomparable[*u]).count());
+            let Some(pivot) = pivot else {
+                antichain.sort_unstable();
+                return Some(antichain);
+            };
+            let branches: Vec<usize> = candidates.difference(&self.incomparable[pivot]).collect();
+            let mut calls = Vec::with_capacity(branches.len());
+            for v in branches {
+                let mut extended = antichain.clone();
+                extended.push(v);
+                let mut next_candidates = candidates.clone();
+                next_candidates.intersect_with(&self.incomparable[v]);
+                let mut next_excluded = excluded.clone();
+                next_excluded.intersect_with(&self.incomparable[v]);
+                calls.push((extended, next_candidates, next_excluded));
+                candidates.set(v, false);
+                excluded.insert(v);
+            }
+            self.stack.extend(calls.into_iter().rev());
+        }
+        None
+    }
+}
+
+/// See [`Poset::iter_order_ideals`].
+pub struct OrderIdealsIterator {
+    ancestors: Vec<FixedBitSet>,
+    // Partial ideals along with the next element to decide on.
+    stack: Vec<(FixedBitSet, usize)>,
+}
+
+impl Iterator for OrderIdealsIterator {
+    type Item = FixedBitSet;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Elements are decided on in a topological order, so an element can be
+        // included iff all the elements smaller than it already are, and every
+        // branch ends with an ideal.
+        while let Some((ideal, u)) = self.stack.pop() {
+            if u == self.ancestors.len() {
+                return Some(ideal);
+            }
+            if self.ancestors[u].is_subset(&ideal) {
+                let mut with_u = ideal.clone();
+                with_u.insert(u);
+                self.stack.push((with_u, u + 1));
+            }
+            self.stack.push((ideal, u + 1));
+        }
+        None
+    }
+}
+
+// Number of antichains consisting of `allowed` elements only.  Either the
+// smallest allowed element is left out or it's taken and everything comparable
+// with it is no longer allowed.
+#[cfg(feature = "num-bigint")]
+fn count_antichains_within(
+    comparable: &[FixedBitSet],
+    allowed: FixedBitSet,
+    memo: &mut HashMap<FixedBitSet, BigUint>,
+) -> BigUint {
+    let Some(u) = allowed.ones().next() else {
+        return BigUint::one();
+    };
+    if let Some(count) = memo.get(&allowed) {
+        return count.clone();
+    }
+    let mut without_u = allowed.clone();
+    without_u.set(u, false);
+    let mut with_u = without_u.clone();
+    with_u.difference_with(&comparable[u]);
+    let count = count_antichains_within(comparable, without_u, memo)
+        + count_antichains_within(comparable, with_u, memo);
+    memo.insert(allowed, count.clone());
+    count
+}
+
+// Orients the edges of an undirected graph (given as adjacency sets) so that
+// the result is transitive: mapping u -> v -> whether the edge is oriented u ->
+// v.  Golumbic's TRO algorithm: repeatedly take a remaining edge, orient it,
+// propagate the orientation to all the edges it forces (its implication
+// class), and remove the class from the graph.  An edge forced both ways means
+// there's no transitive orientation.
+fn get_transitive_orientation(mut adjacent: Vec<FixedBitSet>) -> Option<Vec<Vec<bool>>> {
+    let vertex_count = adjacent.len();
+    let mut result: Vec<Vec<bool>> = vec![vec![false; vertex_count]; vertex_count];
+    let mut class: Vec<Vec<bool>> = vec![vec![false; vertex_count]; vertex_count];
+    for a in 0..vertex_count {
+        while let Some(b) = adjacent[a].ones().next() {
+            let mut class_edges: Vec<(usize, usize)> = vec![(a, b)];
+            class[a][b] = true;
+            let mut next = 0;
+            while next < class_edges.len() {
+                let (x, y) = class_edges[next];
+                next += 1;
+                // x -> y forces x -> z for all z adjacent to x but not to y,
+                // and z -> y for all z adjacent to y but not to x.
+                let forced_from_x = adjacent[x]
+                    .difference(&adjacent[y])
+                    .filter(|z| *z != y)
+                    .map(|z| (x, z));
+                let forced_to_y = adjacent[y]
+                    .difference(&adjacent[x])
+                    .filter(|z| *z != x)
+                    .map(|z| (z, y));
+                let forced: Vec<(usize, usize)> = forced_from_x.chain(forced_to_y).collect();
+                for (p, q) in forced {
+                    if class[q][p] {
+                        return None;
+                    }
+                    if !class[p][q] {
+                        class[p][q] = true;
+                        class_edges.push((p, q));
+                    }
+                }
+            }
+            for (p, q) in class_edges {
+                class[p][q] = false;
+                result[p][q] = true;
+                adjacent[p].set(q, false);
+                adjacent[q].set(p, false);
+            }
+        }
+    }
+    Some(result)
+}
+
+/// A view of a DAG as the [partially ordered
+/// set](https://en.wikipedia.org/wiki/Partially_ordered_set) of its vertices
+/// ordered by reachability: `u <= v` iff `u == v` or there's a path from `u` to
+/// `v`.
+///
+/// Both the transitive closure and the transitive reduction are computed
+/// upfront so that all the queries are `O(1)`.
+#[derive(Clone, Debug)]
+pub struct Poset {
+    descendants: Vec<FixedBitSet>,
+    ancestors: Vec<FixedBitSet>,
+    hasse_diagram: DirectedAcyclicGraph,
+}
+
+impl Poset {
+    pub fn new(dag: &DirectedAcyclicGraph) -> Self {
+        let vertex_count = dag.get_vertex_count();
+        let descendants: Vec<FixedBitSet> = get_descendants(dag)
+            .into_iter()
+            .map(|mut descendants| {
+                descendants.grow(vertex_count);
+                descendants
+            })
+            .collect();
+        let mut ancestors: Vec<FixedBitSet> =
+            vec![FixedBitSet::with_capacity(vertex_count); vertex_count];
+        for (u, u_descendants) in descendants.iter().enumerate() {
+            for v in u_descendants.ones() {
+                ancestors[v].insert(u);
+            }
+        }
+        Self {
+            descendants,
+            ancestors,
+            hasse_diagram: transitive_reduction(dag),
+        }
+    }
+
+    #[inline]
+    pub fn get_element_count(&self) -> usize {
+        self.descendants.len()
+    }
+
+    /// `u <= v`, i.e. either `u == v` or `v` is reachable from `u`.
+    pub fn le(&self, u: usize, v: usize) -> bool {
+        u == v || self.descendants[u][v]
+    }
+
+    /// `u < v`, i.e. `v` is reachable from `u` by a non-empty path.
+    pub fn lt(&self, u: usize, v: usize) -> bool {
+        self.descendants[u][v]
+    }
+
+    /// Whether `v` covers `u`, i.e. `u < v` and there's no `w` such that `u <
+    /// w < v`.  Equivalently, `(u, v)` is an edge of the transitive reduction.
+    pub fn covers(&self, u: usize, v: usize) -> bool {
+        u < v && self.hasse_diagram.get_edge(u, v)
+    }
+
+    /// Whether either `u <= v` or `v <= u`.
+    pub fn comparable(&self, u: usize, v: usize) -> bool {
+        self.le(u, v) || self.le(v, u)
+    }
+
+    /// The set of all `v` such that `u < v`.
+    pub fn get_strictly_greater(&self, u: usize) -> &FixedBitSet {
+        &self.descendants[u]
+    }
+
+    /// The set of all `v` such that `v < u`.
+    pub fn get_strictly_less(&self, u: usize) -> &FixedBitSet {
+        &self.ancestors[u]
+    }
+
+    /// Iterates over the minimal elements, i.e. the ones with nothing smaller.
+    /// Those are the sources of the DAG.  Note that isolated vertices are both
+    /// minimal and maximal, see [`Self::iter_isolated`].
+    pub fn iter_minimal(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.get_element_count()).filter(move |u| self.ancestors[*u].is_clear())
+    }
+
+    /// Iterates over the maximal elements, i.e. the ones with nothing greater.
+    /// Those are the sinks of the DAG.
+    pub fn iter_maximal(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.get_element_count()).filter(move |u| self.descendants[*u].is_clear())
+    }
+
+    /// Iterates over the elements comparable only with themselves, i.e. the
+    /// isolated vertices of the DAG.
+    pub fn iter_isolated(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.get_element_count())
+            .filter(move |u| self.ancestors[*u].is_clear() && self.descendants[*u].is_clear())
+    }
+
+    // The set of all `v` such that `v <= u`.
+    fn get_less_or_equal(&self, u: usize) -> FixedBitSet {
+        let mut result = self.ancestors[u].clone();
+        result.insert(u);
+        result
+    }
+
+    // The set of all `v` such that `u <= v`.
+    fn get_greater_or_equal(&self, u: usize) -> FixedBitSet {
+        let mut result = self.descendants[u].clone();
+        result.insert(u);
+        result
+    }
+
+    // The set of all `v` such that neither `u <= v` nor `v <= u`.
+    fn get_incomparable(&self, u: usize) -> FixedBitSet {
+        let mut result = self.get_less_or_equal(u);
+        result.union_with(&self.descendants[u]);
+        result.toggle_range(..);
+        result
+    }
+
+    /// Iterates over all the pairs `(u, v)` with `u < v` (as numbers) such that
+    /// neither `u <= v` nor `v <= u`, e.g. pairs of tasks that may be freely
+    /// reordered.
+    pub fn iter_incomparable_pairs(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let element_count = self.get_element_count();
+        // `v <= u` can't hold for `u < v`.
+        (0..element_count).flat_map(move |u| {
+            ((u + 1)..element_count)
+                .filter(move |v| !self.descendants[u][*v])
+                .map(move |v| (u, v))
+        })
+    }
+
+    /// The [Möbius function](https://en.wikipedia.org/wiki/Incidence_algebra#Special_elements)
+    /// `μ(u, v)` of the poset: `μ(u, u) = 1`, `μ(u, v) = -Σ μ(u, w)` over `u <=
+    /// w < v` if `u < v`, and 0 otherwise.
+    pub fn get_mobius(&self, u: usize, v: usize) -> i64 {
+        if !self.le(u, v) {
+            return 0;
+        }
+        let mut interval = self.get_greater_or_equal(u);
+        interval.intersect_with(&self.get_less_or_equal(v));
+        let mut mobius: Vec<i64> = vec![0; self.get_element_count()];
+        // Elements are visited in a topological order, so all the `μ(u, w)`
+        // on the right hand side are already known.
+        for x in interval.ones() {
+            mobius[x] = if x == u {
+                1
+            } else {
+                -self.ancestors[x]
+                    .intersection(&interval)
+                    .map(|w| mobius[w])
+                    .sum::<i64>()
+            };
+        }
+        mobius[v]
+    }
+
+    /// The Möbius function for all the pairs of elements at once, as a
+    /// mapping: u -> v -> `μ(u, v)`.  See [`Self::get_mobius`].
+    pub fn get_mobius_matrix(&self) -> Vec<Vec<i64>> {
+        let element_count = self.get_element_count();
+        let mut result: Vec<Vec<i64>> = vec![vec![0; element_count]; element_count];
+        for (u, row) in result.iter_mut().enumerate() {
+            row[u] = 1;
+            let greater_or_equal = self.get_greater_or_equal(u);
+            for v in self.descendants[u].ones() {
+                row[v] = -self.ancestors[v]
+                    .intersection(&greater_or_equal)
+                    .map(|w| row[w])
+                    .sum::<i64>();
+            }
+        }
+        result
+    }
+
+    /// The [zeta transform](https://en.wikipedia.org/wiki/Incidence_algebra)
+    /// of a mapping: element -> value, summing over down-sets: `result[v] = Σ
+    /// values[u]` over `u <= v`.
+    pub fn zeta_transform_down<T>(&self, values: &[T]) -> Vec<T>
+    where
+        T: Copy + Add<Output = T>,
+    {
+        assert_eq!(values.len(), self.get_element_count());
+        (0..values.len())
+            .map(|v| {
+                self.ancestors[v]
+                    .ones()
+                    .fold(values[v], |sum, u| sum + values[u])
+            })
+            .collect()
+    }
+
+    /// Same as [`Self::zeta_transform_down`] but summing over up-sets:
+    /// `result[u] = Σ values[v]` over `u <= v`.
+    pub fn zeta_transform_up<T>(&self, values: &[T]) -> Vec<T>
+    where
+        T: Copy + Add<Output = T>,
+    {
+        assert_eq!(values.len(), self.get_element_count());
+        (0..values.len())
+            .map(|u| {
+                self.descendants[u]
+                    .ones()
+                    .fold(values[u], |sum, v| sum + values[v])
+            })
+            .collect()
+    }
+
+    /// The inverse of [`Self::zeta_transform_down`], i.e. recovers the values
+    /// from their sums over down-sets.  `T::default()` is assumed to be zero.
+    pub fn mobius_transform_down<T>(&self, sums: &[T]) -> Vec<T>
+    where
+        T: Copy + Add<Output = T> + Sub<Output = T> + Default,
+    {
+        assert_eq!(sums.len(), self.get_element_count());
+        let mut result: Vec<T> = vec![T::default(); sums.len()];
+        // Ancestors are always smaller, so their values are known already.
+        for v in 0..sums.len() {
+            result[v] = sums[v]
+                - self.ancestors[v]
+                    .ones()
+                    .fold(T::default(), |sum, u| sum + result[u]);
+        }
+        result
+    }
+
+    /// The inverse of [`Self::zeta_transform_up`], i.e. recovers the values
+    /// from their sums over up-sets.
+    pub fn mobius_transform_up<T>(&self, sums: &[T]) -> Vec<T>
+    where
+        T: Copy + Add<Output = T> + Sub<Output = T> + Default,
+    {
+        assert_eq!(sums.len(), self.get_element_count());
+        let mut result: Vec<T> = vec![T::default(); sums.len()];
+        for u in (0..sums.len()).rev() {
+            result[u] = sums[u]
+                - self.descendants[u]
+                    .ones()
+                    .fold(T::default(), |sum, v| sum + result[v]);
+        }
+        result
+    }
+
+    /// Whether the poset has [order
+    /// dimension](https://en.wikipedia.org/wiki/Order_dimension) at most 2,
+    /// i.e. is the intersection of two linear orders.  See
+    /// [`Self::get_two_dimensional_realizer`].
+    pub fn is_two_dimensional(&self) -> bool {
+        self.get_two_dimensional_realizer().is_some()
+    }
+
+    /// Returns two linear extensions (as sequences of elements) such that `u <
+    /// v` iff `u` precedes `v` in both, if there are such.
+    ///
+    /// That's the case iff the incomparability graph has a transitive
+    /// orientation, which is searched for with Golumbic's implication classes
+    /// algorithm in `O(|V|^3)`.  Adding that orientation to the order, or its
+    /// reverse, gives the two linear extensions.
+    pub fn get_two_dimensional_realizer(&self) -> Option<(Vec<usize>, Vec<usize>)> {
+        let element_count = self.get_element_count();
+        let orientation = get_transitive_orientation(
+            (0..element_count)
+                .map(|u| self.get_incomparable(u))
+                .collect(),
+        )?;
+        let get_linear_extension = |reversed: bool| -> Vec<usize> {
+            // In a linear order, the position of an element is the number of
+            // the elements that precede it.
+            let mut predecessor_counts: Vec<(usize, usize)> = (0..element_count)
+                .map(|v| {
+                    let oriented = (0..element_count)
+                        .filter(|u| {
+                            if reversed {
+                                orientation[v][*u]
+                            } else {
+                                orientation[*u][v]
+                            }
+                        })
+                        .count();
+                    (self.ancestors[v].count_ones(..) + oriented, v)
+                })
+                .collect();
+            predecessor_counts.sort_unstable();
+            predecessor_counts.into_iter().map(|(_, v)| v).collect()
+        };
+        Some((get_linear_extension(false), get_linear_extension(true)))
+    }
+
+    /// Whether the poset is an [interval
+    /// order](https://en.wikipedia.org/wiki/Interval_order).  See
+    /// [`Self::get_interval_representation`].
+    pub fn is_interval_order(&self) -> bool {
+        self.get_interval_representation().is_some()
+    }
+
+    /// Returns a mapping: element -> interval `(left, right)` with `left <=
+    /// right` such that `u < v` iff `u`'s interval lies entirely to the left of
+    /// `v`'s one, i.e. `right(u) < left(v)`, if there is one.
+    ///
+    /// That's the case iff the sets of the elements smaller than each element
+    /// form a chain under inclusion (the poset has no induced `2 + 2`).  Left
+    /// endpoints are then the positions of those sets in the chain.
+    pub fn get_interval_representation(&self) -> Option<Vec<(usize, usize)>> {
+        let element_count = self.get_element_count();
+        let mut by_predecessor_count: Vec<usize> = (0..element_count).collect();
+        by_predecessor_count.sort_by_key(|u| self.ancestors[*u].count_ones(..));
+        let mut chain: Vec<&FixedBitSet> = Vec::new();
+        for u in by_predecessor_count {
+            match chain.last() {
+                Some(last) if **last == self.ancestors[u] => {}
+                Some(last) if !last.is_subset(&self.ancestors[u]) => return None,
+                _ => chain.push(&self.ancestors[u]),
+            }
+        }
+        let result = (0..element_count)
+            .map(|u| {
+                let left = chain
+                    .iter()
+                    .position(|predecessors| **predecessors == self.ancestors[u])
+                    .unwrap();
+                // `u` precedes exactly the elements whose sets of predecessors
+                // contain it.
+                let right = chain
+                    .iter()
+                    .position(|predecessors| predecessors[u])
+                    .unwrap_or(chain.len())
+                    - 1;
+                (left, right)
+            })
+            .collect();
+        Some(result)
+    }
+
+    /// Whether the poset is a
+    /// [semiorder](https://en.wikipedia.org/wiki/Semiorder).  See
+    /// [`Self::get_unit_interval_representation`].
+    pub fn is_semiorder(&self) -> bool {
+        self.get_unit_interval_representation().is_some()
+    }
+
+    /// Same as [`Self::get_interval_representation`] but with all the
+    /// intervals of the same length, if possible.
+    ///
+    /// The interval starts are found as a solution of the system of difference
+    /// constraints `start(v) - start(u) > length` for `u < v` and `|start(v) -
+    /// start(u)| <= length` for incomparable `u` and `v` with the Bellman–Ford
+    /// algorithm in `O(|V|^3)`.  No solution means it's not a semiorder.
+    pub fn get_unit_interval_representation(&self) -> Option<Vec<(usize, usize)>> {
+        let element_count = self.get_element_count();
+        // It's enough for the strict inequalities to hold by 1 / |V| of the
+        // length, so scaling by |V| + 1 keeps everything integer.
+        let length = element_count as i64 + 1;
+        // Edges (u, v, w) of the constraint graph mean `start(v) - start(u) <=
+        // w`.
+        let mut constraints: Vec<(usize, usize, i64)> = Vec::new();
+        for u in 0..element_count {
+            for v in (u + 1)..element_count {
+                if self.lt(u, v) {
+                    constraints.push((v, u, -(length + 1)));
+                } else {
+                    constraints.push((u, v, length));
+                    constraints.push((v, u, length));
+                }
+            }
+        }
+        // An implicit source connected to everything with weight 0.
+        let mut starts: Vec<i64> = vec![0; element_count];
+        for _ in 0..=element_count {
+            let mut changed = false;
+            for (u, v, w) in &constraints {
+                if starts[*u] + w < starts[*v] {
+                    starts[*v] = starts[*u] + w;
+                    changed = true;
+                }
+            }
+            if !changed {
+                let min = starts.iter().copied().min().unwrap_or(0);
+                let result = starts
+                    .iter()
+                    .map(|start| {
+                        let start = (start - min) as usize;
+                        (start, start + length as usize)
+                    })
+                    .collect();
+                return Some(result);
+            }
+        }
+        // A negative cycle.
+        None
+    }
+
+    /// Computes the [Dedekind–MacNeille
+    /// completion](https://en.wikipedia.org/wiki/Dedekind%E2%80%93MacNeille_completion),
+    /// i.e. the smallest lattice containing the poset.  Returns its Hasse
+    /// diagram along with the embedding: poset element -> lattice element.  The
+    /// embedding preserves both `<=` and all the existing meets and joins.
+    ///
+    /// The lattice elements are the cuts of the poset: the intersections of
+    /// the principal down-sets `{x | x <= u}` (including the empty
+    /// intersection, i.e. the whole poset), ordered by inclusion.
+    pub fn get_dedekind_macneille_completion(&self) -> (DirectedAcyclicGraph, Vec<usize>) {
+        let element_count = self.get_element_count();
+        let principal: Vec<FixedBitSet> = (0..element_count)
+            .map(|u| self.get_less_or_equal(u))
+            .collect();
+        let mut everything = FixedBitSet::with_capacity(element_count);
+        everything.insert_range(..);
+
+        let mut cuts: HashSet<FixedBitSet> = HashSet::new();
+        let mut to_visit: Vec<FixedBitSet> = vec![everything];
+        to_visit.extend(principal.iter().cloned());
+        while let Some(cut) = to_visit.pop() {
+            if cuts.contains(&cut) {
+                continue;
+            }
+            for down_set in &principal {
+                let mut intersection = cut.clone();
+                intersection.intersect_with(down_set);
+                if !cuts.contains(&intersection) {
+                    to_visit.push(intersection);
+                }
+            }
+            cuts.insert(cut);
+        }
+
+        // A strict subset is smaller, so ordering by size makes all the edges
+        // go forward.
+        let mut cuts: Vec<FixedBitSet> = cuts.into_iter().collect();
+        cuts.sort_by(|a, b| {
+            a.count_ones(..)
+                .cmp(&b.count_ones(..))
+                .then_with(|| a.ones().cmp(b.ones()))
+        });
+        let mut lattice = DirectedAcyclicGraph::empty(cuts.len());
+        for (i, smaller) in cuts.iter().enumerate() {
+            for (j, bigger) in cuts.iter().enumerate().skip(i + 1) {
+                if smaller.is_subset(bigger) {
+                    lattice.set_edge(i, j, true);
+                }
+            }
+        }
+        let embedding = principal
+            .iter()
+            .map(|down_set| cuts.iter().position(|cut| cut == down_set).unwrap())
+            .collect();
+        (transitive_reduction(&lattice), embedding)
+    }
+
+    /// Iterates over all the maximal
+    /// [antichains](https://en.wikipedia.org/wiki/Antichain), i.e. sets of
+    /// pairwise incomparable elements that can't be extended by any other
+    /// element.  Each antichain is sorted.
+    ///
+    /// This is the Bron–Kerbosch algorithm run on the incomparability graph.
+    /// Note that there may be exponentially many maximal antichains.
+    pub fn iter_maximal_antichains(&self) -> MaximalAntichainsIterator {
+        let element_count = self.get_element_count();
+        let mut candidates = FixedBitSet::with_capacity(element_count);
+        candidates.insert_range(..);
+        MaximalAntichainsIterator {
+            incomparable: (0..element_count)
+                .map(|u| self.get_incomparable(u))
+                .collect(),
+            stack: vec![(
+                Vec::new(),
+                candidates,
+                FixedBitSet::with_capacity(element_count),
+            )],
+        }
+    }
+
+    /// The number of antichains, including the empty one.  Equivalently, the
+    /// number of down-sets (order ideals), as every down-set is generated by
+    /// the antichain of its maximal elements.
+    ///
+    /// Takes exponential time in the worst case, but the memoization over the
+    /// sets of still allowed elements makes it practical for posets that are
+    /// not too wide.
+    #[cfg(feature = "num-bigint")]
+    pub fn count_antichains(&self) -> BigUint {
+        let element_count = self.get_element_count();
+        let comparable: Vec<FixedBitSet> = (0..element_count)
+            .map(|u| {
+                let mut result = self.get_incomparable(u);
+                result.toggle_range(..);
+                result
+            })
+            .collect();
+        let mut allowed = FixedBitSet::with_capacity(element_count);
+        allowed.insert_range(..);
+        count_antichains_within(&comparable, allowed, &mut HashMap::new())
+    }
+
+    /// The number of down-sets (order ideals), including the empty one.  Same
+    /// as [`Self::count_antichains`] but usually much faster.
+    ///
+    /// This is a dynamic programming over the elements in the topological
+    /// order, i.e. over the path decomposition of the Hasse diagram it
+    /// induces.  The state is which of the already decided elements that
+    /// still have undecided covers are in the ideal, so the running time is
+    /// exponential only in the number of such elements at a time rather than
+    /// in the width of the poset.
+    #[cfg(feature = "num-bigint")]
+    pub fn count_order_ideals(&self) -> BigUint {
+        let element_count = self.get_element_count();
+        let last_cover: Vec<Option<usize>> = (0..element_count)
+            .map(|u| self.hasse_diagram.iter_children(u).last())
+            .collect();
+        let mut expiring: Vec<Vec<usize>> = vec![Vec::new(); element_count];
+        for (u, last) in last_cover.iter().enumerate() {
+            if let Some(v) = last {
+                expiring[*v].push(u);
+            }
+        }
+        // Frontier elements that are in the ideal -> number of ways to decide
+        // the elements so far.
+        let lower_covers = self.hasse_diagram.get_adjacency_matrix().transpose();
+        let mut counts: HashMap<FixedBitSet, BigUint> = HashMap::new();
+        counts.insert(FixedBitSet::with_capacity(element_count), BigUint::one());
+        for v in 0..element_count {
+            let mut parents = FixedBitSet::with_capacity(element_count);
+            parents.extend(lower_covers.iter_ones_at_row(v));
+            let mut next: HashMap<FixedBitSet, BigUint> = HashMap::new();
+            for (mut included, count) in counts {
+                let can_include = parents.is_subset(&included);
+                for u in &expiring[v] {
+                    included.set(*u, false);
+                }
+                if can_include {
+                    // Elements without covers never constrain later ones.
+                    let mut with_v = included.clone();
+                    if last_cover[v].is_some() {
+                        with_v.insert(v);
+                    }
+                    *next.entry(with_v).or_insert_with(BigUint::zero) += &count;
+                }
+                *next.entry(included).or_insert_with(BigUint::zero) += count;
+            }
+            counts = next;
+        }
+        counts.into_values().sum()
+    }
+
+    /// The [down-set](https://en.wikipedia.org/wiki/Upper_set) (order ideal)
+    /// generated by `elements`, i.e. all `x` such that `x <= e` for some `e` in
+    /// `elements`.
+    pub fn get_down_set<I: IntoIterator<Item = usize>>(&self, elements: I) -> FixedBitSet {
+        let mut result = FixedBitSet::with_capacity(self.get_element_count());
+        for e in elements {
+            result.union_with(&self.ancestors[e]);
+            result.insert(e);
+        }
+        result
+    }
+
+    /// The [up-set](https://en.wikipedia.org/wiki/Upper_set) (order filter)
+    /// generated by `elements`, i.e. all `x` such that `e <= x` for some `e` in
+    /// `elements`.
+    pub fn get_up_set<I: IntoIterator<Item = usize>>(&self, elements: I) -> FixedBitSet {
+        let mut result = FixedBitSet::with_capacity(self.get_element_count());
+        for e in elements {
+            result.union_with(&self.descendants[e]);
+            result.insert(e);
+        }
+        result
+    }
+
+    /// Iterates over all the down-sets (order ideals), i.e. the sets of
+    /// elements that contain every element smaller than one of theirs,
+    /// including the empty one and the whole poset.  They can be counted
+    /// without enumerating them with `count_order_ideals` (requires the
+    /// `num-bigint` feature).
+    ///
+    /// Note that there may be exponentially many order ideals.
+    pub fn iter_order_ideals(&self) -> OrderIdealsIterator {
+        OrderIdealsIterator {
+            ancestors: self.ancestors.clone(),
+            stack: vec![(FixedBitSet::with_capacity(self.get_element_count()), 0)],
+        }
+    }
+
+    /// The distributive lattice of the order ideals ordered by inclusion, as
+    /// in [Birkhoff's representation
+    /// theorem](https://en.wikipedia.org/wiki/Birkhoff%27s_representation_theorem).
+    /// Returns its Hasse diagram along with a mapping: lattice element ->
+    /// order ideal.  The first element is the empty ideal and the last one is
+    /// the whole poset.
+    ///
+    /// An ideal covers another iff it has exactly one more element.
+    pub fn get_lattice_of_ideals(&self) -> (DirectedAcyclicGraph, Vec<FixedBitSet>) {
+        // A strict subset is smaller, so ordering by size makes all the edges
+        // go forward.
+        let mut ideals: Vec<FixedBitSet> = self.iter_order_ideals().collect();
+        ideals.sort_by(|a, b| {
+            a.count_ones(..)
+                .cmp(&b.count_ones(..))
+                .then_with(|| a.ones().cmp(b.ones()))
+        });
+        let index: HashMap<&FixedBitSet, usize> = ideals
+            .iter()
+            .enumerate()
+            .map(|(i, ideal)| (ideal, i))
+            .collect();
+        let mut lattice = DirectedAcyclicGraph::empty(ideals.len());
+        for (i, ideal) in ideals.iter().enumerate() {
+            for u in (0..self.get_element_count()).filter(|u| !ideal[*u]) {
+                if self.ancestors[u].is_subset(ideal) {
+                    let mut bigger = ideal.clone();
+                    bigger.insert(u);
+                    lattice.set_edge(i, index[&bigger], true);
+                }
+            }
+        }
+        (lattice, ideals)
+    }
+
+    /// The [interval](https://en.wikipedia.org/wiki/Interval_(mathematics)#Generalizations)
+    /// `[u, v]`, i.e. all `x` such that `u <= x <= v`, as a DAG along with a
+    /// mapping: interval DAG vertex -> poset element.  Empty unless `u <= v`.
+    ///
+    /// The returned DAG is the Hasse diagram of the interval and so it has the
+    /// same reachability as the sub-DAG of the original DAG induced by the
+    /// interval.
+    pub fn get_interval(&self, u: usize, v: usize) -> (DirectedAcyclicGraph, Vec<usize>) {
+        let mut elements = self.get_greater_or_equal(u);
+        elements.intersect_with(&self.get_less_or_equal(v));
+        get_induced_subgraph(&self.hasse_diagram, &elements)
+    }
+
+    /// The greatest lower bound of `u` and `v`, if there is one.
+    pub fn meet(&self, u: usize, v: usize) -> Option<usize> {
+        let mut lower_bounds = self.get_less_or_equal(u);
+        lower_bounds.intersect_with(&self.get_less_or_equal(v));
+        // All the other lower bounds must be smaller than the greatest one and
+        // edges only go from smaller to bigger vertices.
+        let candidate = lower_bounds.ones().last()?;
+        if lower_bounds.is_subset(&self.get_less_or_equal(candidate)) {
+            Some(candidate)
+        } else {
+            None
+        }
+    }
+
+    /// The least upper bound of `u` and `v`, if there is one.
+    pub fn join(&self, u: usize, v: usize) -> Option<usize> {
+        let mut upper_bounds = self.get_greater_or_equal(u);
+        upper_bounds.intersect_with(&self.get_greater_or_equal(v));
+        let candidate = upper_bounds.ones().next()?;
+        if upper_bounds.is_subset(&self.get_greater_or_equal(candidate)) {
+            Some(candidate)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the poset is a [lattice](https://en.wikipedia.org/wiki/Lattice_(order)),
+    /// i.e. it's non-empty and every pair of elements has both a [`Self::meet`]
+    /// and a [`Self::join`].
+    pub fn is_lattice(&self) -> bool {
+        let element_count = self.get_element_count();
+        element_count > 0
+            && (0..element_count).all(|u| {
+                ((u + 1)..element_count)
+                    .all(|v| self.meet(u, v).is_some() && self.join(u, v).is_some())
+            })
+    }
+
+    /// The [Hasse diagram](https://en.wikipedia.org/wiki/Hasse_diagram) of the
+    /// poset, i.e. the transitive reduction of the DAG.
+    pub fn get_hasse_diagram(&self) -> &DirectedAcyclicGraph {
+        &self.hasse_diagram
+    }
+}
+
+/// A DAG kept [transitively reduced](transitive_reduction) under mutation,
+/// i.e. the [Hasse diagram](https://en.wikipedia.org/wiki/Hasse_diagram) of
+/// the poset of its vertices, with an edge `(u, v)` iff `v` covers `u`.
+///
+/// Unlike [`Poset`], nothing is computed upfront, so mutations are cheap but
+/// reachability queries take a traversal.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct HasseDiagram {
+    dag: DirectedAcyclicGraph,
+    // The transposed adjacency matrix of `dag`, kept in sync with it, for
+    // scanning lower covers a word at a time.
+    lower_covers: StrictlyLowerTriangularLogicalMatrix,
+}
+
+impl HasseDiagram {
+    pub fn new(dag: &DirectedAcyclicGraph) -> Self {
+        let dag = transitive_reduction(dag);
+        let lower_covers = dag.get_adjacency_matrix().transpose();
+        Self { dag, lower_covers }
+    }
+
+    fn set_cover(&mut self, u: usize, v: usize, exists: bool) {
+        self.dag.set_edge(u, v, exists);
+        self.lower_covers.set(v, u, exists);
+    }
+
+    #[inline]
+    pub fn get_vertex_count(&self) -> usize {
+        self.dag.get_vertex_count()
+    }
+
+    /// Whether `v` covers `u`, i.e. `(u, v)` is an edge of the diagram.
+    pub fn covers(&self, u: usize, v: usize) -> bool {
+        u < v && self.dag.get_edge(u, v)
+    }
+
+    /// Iterates over the vertices covering `u`, in increasing order.
+    pub fn iter_upper_covers(&self, u: usize) -> impl Iterator<Item = usize> + '_ {
+        self.dag.iter_children(u)
+    }
+
+    /// Iterates over the vertices covered by `v`, in increasing order.
+    pub fn iter_lower_covers(&self, v: usize) -> impl Iterator<Item = usize> + '_ {
+        self.lower_covers.iter_ones_at_row(v)
+    }
+
+    // `u` along with all the vertices reachable from it.
+    fn get_reachable_from(&self, u: usize) -> FixedBitSet {
+        let mut result = FixedBitSet::with_capacity(self.get_vertex_count());
+        result.insert(u);
+        for w in u..self.get_vertex_count() {
+            if result[w] {
+                self.dag.union_children_into(w, &mut result);
+            }
+        }
+        result
+    }
+
+    // `v` along with all the vertices it's reachable from.
+    fn get_reaching(&self, v: usize) -> FixedBitSet {
+        let mut result = FixedBitSet::with_capacity(self.get_vertex_count());
+        result.insert(v);
+        for w in (0..=v).rev() {
+            if result[w] {
+                result.extend(self.iter_lower_covers(w));
+            }
+        }
+        result
+    }
+
+    /// Whether `u < v` in the poset, i.e. there's a path from `u` to `v`.
+    pub fn lt(&self, u: usize, v: usize) -> bool {
+        u < v && self.get_reachable_from(u)[v]
+    }
+
+    /// Adds the relation `u < v` (requires vertex `u` < vertex `v`, panics
+    /// otherwise).  Any covers that become implied through it are removed.
+    /// Returns whether the poset changed, i.e. `u < v` didn't hold already.
+    pub fn add_relation(&mut self, u: usize, v: usize) -> bool {
+        assert!(u < v);
+        let from_v = self.get_reachable_from(v);
+        if self.get_reachable_from(u)[v] {
+            return false;
+        }
+        // A newly implied edge `(a, b)` has an alternative path `a ⇝ u -> v ⇝
+        // b`.
+        for a in self.get_reaching(u).ones() {
+            let implied: Vec<usize> = self.dag.iter_children(a).filter(|b| from_v[*b]).collect();
+            for b in implied {
+                self.set_cover(a, b, false);
+            }
+        }
+        self.set_cover(u, v, true);
+        true
+    }
+
+    /// Removes the cover `(u, v)` along with the relations implied only by
+    /// it.  Removing edges never makes others implied, so the diagram stays
+    /// reduced.  Returns whether `(u, v)` was a cover.
+    ///
+    /// Requires `u < v`.  Panics otherwise.
+    pub fn remove_cover(&mut self, u: usize, v: usize) -> bool {
+        assert!(u < v);
+        let was_cover = self.covers(u, v);
+        self.set_cover(u, v, false);
+        was_cover
+    }
+
+    pub fn get_dag(&self) -> &DirectedAcyclicGraph {
+        &self.dag
+    }
+
+    pub fn into_dag(self) -> DirectedAcyclicGraph {
+        self.dag
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn divisibility_poset_of_12_relations() {
+        let divisibility_poset_pairs = vec![
+            (1, 2),
+            (1, 3),
+            (1, 4),
+            (1, 5),
+            (1, 6),
+            (1, 7),
+            (1, 8),
+            (1, 9),
+            (1, 10),
+            (1, 11),
+            (1, 12),
+            (2, 4),
+            (2, 6),
+            (2, 8),
+            (2, 10),
+            (2, 12),
+            (3, 6),
+            (3, 9),
+            (3, 12),
+            (4, 8),
+            (4, 12),
+            (5, 10),
+            (6, 12),
+        ];
+        let dag =
+            DirectedAcyclicGraph::from_edges_iter(12 + 1, divisibility_poset_pairs.into_iter());
+        let poset = Poset::new(&dag);
+
+        assert!(poset.le(3, 3));
+        assert!(!poset.lt(3, 3));
+        assert!(poset.le(2, 12));
+        assert!(!poset.le(12, 2));
+        assert!(poset.covers(2, 4));
+        assert!(!poset.covers(2, 8));
+        assert!(!poset.covers(4, 2));
+        assert!(poset.comparable(12, 3));
+        assert!(!poset.comparable(4, 6));
+        assert!(!poset.comparable(5, 7));
+    }
+
+    #[test]
+    fn divisors_of_12_form_a_lattice() {
+        // Vertices 0..6 stand for the divisors 1, 2, 3, 4, 6 and 12.
+        let dag = crate::dag!(6;
+            0 => 1, 0 => 2, 0 => 3, 0 => 4, 0 => 5,
+            1 => 3, 1 => 4, 1 => 5,
+            2 => 4, 2 => 5,
+            3 => 5,
+            4 => 5
+        );
+        let poset = Poset::new(&dag);
+        assert!(poset.is_lattice());
+        // gcd(4, 6) == 2 and lcm(4, 6) == 12
+        assert_eq!(poset.meet(3, 4), Some(1));
+        assert_eq!(poset.join(3, 4), Some(5));
+        assert_eq!(poset.meet(1, 2), Some(0));
+        assert_eq!(poset.join(1, 3), Some(3));
+    }
+
+    #[test]
+    fn crown_is_not_a_lattice() {
+        let poset = Poset::new(&crate::dag!(4; 0 => 2, 0 => 3, 1 => 2, 1 => 3));
+        assert_eq!(poset.meet(2, 3), None);
+        assert_eq!(poset.join(0, 1), None);
+        assert_eq!(poset.meet(0, 2), Some(0));
+        assert!(!poset.is_lattice());
+    }
+
+    #[test]
+    fn minimal_and_maximal_elements() {
+        //  0 -> 1 -> 2   3   4 -> 5
+        let poset = Poset::new(&crate::dag!(6; 0 => 1, 1 => 2, 4 => 5));
+        assert_eq!(poset.iter_minimal().collect::<Vec<_>>(), vec![0, 3, 4]);
+        assert_eq!(poset.iter_maximal().collect::<Vec<_>>(), vec![2, 3, 5]);
+        assert_eq!(poset.iter_isolated().collect::<Vec<_>>(), vec![3]);
+    }
+
+    #[test]
+    fn intervals_of_divisors_of_12() {
+        // Vertices 0..6 stand for the divisors 1, 2, 3, 4, 6 and 12.
+        let dag = crate::dag!(6;
+            0 => 1, 0 => 2, 0 => 3, 0 => 4, 0 => 5,
+            1 => 3, 1 => 4, 1 => 5,
+            2 => 4, 2 => 5,
+            3 => 5,
+            4 => 5
+        );
+        let poset = Poset::new(&dag);
+        // [2, 12] = {2, 4, 6, 12}
+        let (interval, mapping) = poset.get_interval(1, 5);
+        assert_eq!(mapping, vec![1, 3, 4, 5]);
+        assert_eq!(interval, crate::dag!(4; 0 => 1, 0 => 2, 1 => 3, 2 => 3));
+
+        let (interval, mapping) = poset.get_interval(3, 3);
+        assert_eq!(mapping, vec![3]);
+        assert_eq!(interval, DirectedAcyclicGraph::empty(1));
+        assert!(poset.get_interval(3, 2).1.is_empty());
+    }
+
+    #[test]
+    fn down_and_up_sets() {
+        //  0   1
+        //  |\ /|
+        //  | 2  3
+        //  |/
+        //  4
+        let poset = Poset::new(&crate::dag!(5; 0 => 2, 0 => 4, 1 => 2, 1 => 3, 2 => 4));
+        assert_eq!(
+            poset.get_down_set([4]).ones().collect::<Vec<_>>(),
+            vec![0, 1, 2, 4]
+        );
+        assert_eq!(
+            poset.get_down_set([3, 0]).ones().collect::<Vec<_>>(),
+            vec![0, 1, 3]
+        );
+        assert_eq!(
+            poset.get_up_set([1]).ones().collect::<Vec<_>>(),
+            vec![1, 2, 3, 4]
+        );
+        assert_eq!(poset.get_up_set([]).count_ones(..), 0);
+    }
+
+    #[test]
+    fn maximal_antichains_of_small_poset() {
+        //  0   1
+        //  |\ /|
+        //  | 2  3
+        //  |/
+        //  4
+        let poset = Poset::new(&crate::dag!(5; 0 => 2, 0 => 4, 1 => 2, 1 => 3, 2 => 4));
+        let mut antichains: Vec<Vec<usize>> = poset.iter_maximal_antichains().collect();
+        antichains.sort();
+        assert_eq!(
+            antichains,
+            vec![vec![0, 1], vec![0, 3], vec![2, 3], vec![3, 4]]
+        );
+    }
+
+    fn prop_maximal_antichains_are_maximal(dag: DirectedAcyclicGraph) -> bool {
+        let poset = Poset::new(&dag);
+        poset.iter_maximal_antichains().all(|antichain| {
+            antichain.iter().all(|u| {
+                antichain
+                    .iter()
+                    .all(|v| u == v || !poset.comparable(*u, *v))
+            }) && (0..poset.get_element_count()).all(|w| {
+                antichain.contains(&w) || antichain.iter().any(|u| poset.comparable(*u, w))
+            })
+        })
+    }
+
+    #[test]
+    fn maximal_antichains_are_maximal() {
+        quickcheck::QuickCheck::new()
+            .rng(quickcheck::Gen::new(20))
+            .quickcheck(prop_maximal_antichains_are_maximal as fn(DirectedAcyclicGraph) -> bool);
+    }
+
+    #[cfg(feature = "num-bigint")]
+    #[test]
+    fn antichain_counts() {
+        // A chain of n elements has n + 1 antichains and n incomparable
+        // elements have 2^n.
+        let mut chain = DirectedAcyclicGraph::empty(100);
+        chain.complete_to_total_order();
+        assert_eq!(Poset::new(&chain).count_antichains(), BigUint::from(101u32));
+        assert_eq!(
+            Poset::new(&DirectedAcyclicGraph::empty(70)).count_antichains(),
+            BigUint::one() << 70
+        );
+        let poset = Poset::new(&crate::dag!(5; 0 => 2, 0 => 4, 1 => 2, 1 => 3, 2 => 4));
+        // {}, 5 singletons, {0, 1}, {0, 3}, {2, 3}, {3, 4}
+        assert_eq!(poset.count_antichains(), BigUint::from(10u32));
+    }
+
+    fn prop_lattice_of_ideals_is_distributive_lattice(dag: DirectedAcyclicGraph) -> bool {
+        let poset = Poset::new(&dag);
+        let (lattice, ideals) = poset.get_lattice_of_ideals();
+        let lattice_poset = Poset::new(&lattice);
+        // Birkhoff: the join-irreducible ideals, i.e. the ones covering exactly
+        // one other ideal, are the principal ones and so correspond to the
+        // elements of the poset.
+        let mut join_irreducible: Vec<FixedBitSet> = (0..lattice.get_vertex_count())
+            .filter(|v| lattice.get_parents_bitset(*v).count_ones(..) == 1)
+            .map(|v| ideals[v].clone())
+            .collect();
+        join_irreducible.sort_by_key(|ideal| ideal.ones().last());
+        let principal: Vec<FixedBitSet> = (0..poset.get_element_count())
+            .map(|u| poset.get_down_set([u]))
+            .collect();
+        ideals
+            .iter()
+            .all(|ideal| poset.get_down_set(ideal.ones()) == *ideal)
+            && ideals.iter().collect::<HashSet<_>>().len() == ideals.len()
+            && lattice_poset.is_lattice()
+            && join_irreducible == principal
+    }
+
+    #[test]
+    fn lattice_of_ideals_is_distributive_lattice() {
+        quickcheck::QuickCheck::new()
+            .rng(quickcheck::Gen::new(10))
+            .quickcheck(
+                prop_lattice_of_ideals_is_distributive_lattice as fn(DirectedAcyclicGraph) -> bool,
+            );
+    }
+
+    #[cfg(feature = "num-bigint")]
+    fn prop_order_ideal_count_agrees_with_antichains(dag: DirectedAcyclicGraph) -> bool {
+        let poset = Poset::new(&dag);
+        poset.count_order_ideals() == poset.count_antichains()
+    }
+
+    #[cfg(feature = "num-bigint")]
+    #[test]
+    fn order_ideal_count_agrees_with_antichains() {
+        let mut chain = DirectedAcyclicGraph::empty(100);
+        chain.complete_to_total_order();
+        assert_eq!(
+            Poset::new(&chain).count_order_ideals(),
+            BigUint::from(101u32)
+        );
+        assert_eq!(
+            Poset::new(&DirectedAcyclicGraph::empty(70)).count_order_ideals(),
+            BigUint::one() << 70
+        );
+        quickcheck::QuickCheck::new().quickcheck(
+            prop_order_ideal_count_agrees_with_antichains as fn(DirectedAcyclicGraph) -> bool,
+        );
+    }
+
+    fn prop_hasse_diagram_stays_reduced(dag: DirectedAcyclicGraph) -> bool {
+        let mut diagram = HasseDiagram::new(&DirectedAcyclicGraph::empty(dag.get_vertex_count()));
+        // Adding edges in decreasing order makes the longer ones get implied
+        // after they were added.
+        let mut edges: Vec<(usize, usize)> = dag.iter_edges().collect();
+        edges.reverse();
+        for (u, v) in edges {
+            diagram.add_relation(u, v);
+        }
+        diagram.get_dag() == &transitive_reduction(&dag)
+            && dag.iter_edges().all(|(u, v)| diagram.lt(u, v))
+    }
+
+    #[test]
+    fn hasse_diagram_stays_reduced() {
+        quickcheck::QuickCheck::new()
+            .rng(quickcheck::Gen::new(30))
+            .quickcheck(prop_hasse_diagram_stays_reduced as fn(DirectedAcyclicGraph) -> bool);
+
+        // 0 -> 1 -> 2
+        let mut diagram = HasseDiagram::new(&crate::dag!(3; 0 => 1, 1 => 2, 0 => 2));
+        assert!(!diagram.covers(0, 2));
+        assert!(!diagram.add_relation(0, 2));
+        assert_eq!(diagram.iter_lower_covers(2).collect::<Vec<_>>(), vec![1]);
+        assert!(diagram.remove_cover(1, 2));
+        assert!(!diagram.lt(0, 2));
+        assert!(diagram.add_relation(0, 2));
+        assert_eq!(diagram.iter_upper_covers(0).collect::<Vec<_>>(), vec![1, 2]);
+    }
+
+    #[test]
+    #[should_panic = "assertion failed: u < v"]
+    fn hasse_diagram_remove_cover_rejects_reversed_pairs() {
+        HasseDiagram::new(&crate::dag!(2; 0 => 1)).remove_cover(1, 0);
+    }
+
+    fn prop_incomparable_pairs_complement_closure(dag: DirectedAcyclicGraph) -> bool {
+        let poset = Poset::new(&dag);
+        let closure = crate::algorithm::transitive_closure(&dag);
+        poset.iter_incomparable_pairs().eq(closure.iter_non_edges())
+    }
+
+    #[test]
+    fn incomparable_pairs_complement_closure() {
+        quickcheck::QuickCheck::new().quickcheck(
+            prop_incomparable_pairs_complement_closure as fn(DirectedAcyclicGraph) -> bool,
+        );
+    }
+
+    #[test]
+    fn mobius_function_of_divisors_of_12() {
+        // Vertices 0..6 stand for the divisors 1, 2, 3, 4, 6 and 12.  On
+        // divisibility μ(a, b) is the number theoretic μ(b / a).
+        let dag = crate::dag!(6;
+            0 => 1, 0 => 2, 0 => 3, 0 => 4, 0 => 5,
+            1 => 3, 1 => 4, 1 => 5,
+            2 => 4, 2 => 5,
+            3 => 5,
+            4 => 5
+        );
+        let poset = Poset::new(&dag);
+        assert_eq!(
+            (0..6).map(|v| poset.get_mobius(0, v)).collect::<Vec<_>>(),
+            vec![1, -1, -1, 0, 1, 0]
+        );
+        assert_eq!(poset.get_mobius(1, 4), -1);
+        assert_eq!(poset.get_mobius(4, 1), 0);
+        assert_eq!(poset.get_mobius(2, 3), 0);
+        let matrix = poset.get_mobius_matrix();
+        assert!((0..6).all(|u| (0..6).all(|v| matrix[u][v] == poset.get_mobius(u, v))));
+    }
+
+    #[test]
+    fn zeta_transforms_sum_over_ancestors_and_descendants() {
+        //  0   1
+        //  |\ /|
+        //  | 2  3
+        //  |/
+        //  4
+        let poset = Poset::new(&crate::dag!(5; 0 => 2, 0 => 4, 1 => 2, 1 => 3, 2 => 4));
+        let values = [1, 10, 100, 1000, 10000];
+        assert_eq!(
+            poset.zeta_transform_down(&values),
+            vec![1, 10, 111, 1010, 10111]
+        );
+        assert_eq!(
+            poset.zeta_transform_up(&values),
+            vec![10101, 11110, 10100, 1000, 10000]
+        );
+    }
+
+    fn prop_mobius_transforms_invert_zeta_transforms(dag: DirectedAcyclicGraph) -> bool {
+        let poset = Poset::new(&dag);
+        let values: Vec<i64> = (0..dag.get_vertex_count() as i64)
+            .map(|v| v * v - 7)
+            .collect();
+        poset.mobius_transform_down(&poset.zeta_transform_down(&values)) == values
+            && poset.mobius_transform_up(&poset.zeta_transform_up(&values)) == values
+    }
+
+    #[test]
+    fn mobius_transforms_invert_zeta_transforms() {
+        quickcheck::QuickCheck::new().quickcheck(
+            prop_mobius_transforms_invert_zeta_transforms as fn(DirectedAcyclicGraph) -> bool,
+        );
+    }
+
+    fn is_realizer(poset: &Poset, first: &[usize], second: &[usize]) -> bool {
+        let element_count = poset.get_element_count();
+        let get_positions = |order: &[usize]| {
+            let mut result = vec![0; element_count];
+            for (i, v) in order.iter().enumerate() {
+                result[*v] = i;
+            }
+            result
+        };
+        let (first, second) = (get_positions(first), get_positions(second));
+        (0..element_count).all(|u| {
+            (0..element_count)
+                .all(|v| poset.lt(u, v) == (u != v && first[u] < first[v] && second[u] < second[v]))
+        })
+    }
+
+    fn prop_realizers_are_valid(dag: DirectedAcyclicGraph) -> bool {
+        let poset = Poset::new(&dag);
+        match poset.get_two_dimensional_realizer() {
+            Some((first, second)) => is_realizer(&poset, &first, &second),
+            None => true,
+        }
+    }
+
+    #[test]
+    fn realizers_are_valid() {
+        quickcheck::QuickCheck::new()
+            .quickcheck(prop_realizers_are_valid as fn(DirectedAcyclicGraph) -> bool);
+    }
+
+    #[test]
+    fn standard_example_is_three_dimensional() {
+        // a_i < b_j for i != j
+        let poset = Poset::new(&crate::dag!(6; 0 => 4, 0 => 5, 1 => 3, 1 => 5, 2 => 3, 2 => 4));
+        assert!(!poset.is_two_dimensional());
+        let crown = Poset::new(&crate::dag!(4; 0 => 2, 0 => 3, 1 => 2, 1 => 3));
+        assert!(crown.is_two_dimensional());
+    }
+
+    #[test]
+    fn intersections_of_two_linear_orders_are_recognized() {
+        use rand::seq::SliceRandom;
+        use rand::SeedableRng;
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(5);
+        for vertex_count in 0..12 {
+            // The second linear order is a random permutation of the first.
+            let mut positions: Vec<usize> = (0..vertex_count).collect();
+            positions.shuffle(&mut rng);
+            let dag = DirectedAcyclicGraph::from_edges_iter(
+                vertex_count,
+                (0..vertex_count).flat_map(|u| {
+                    let positions = &positions;
+                    ((u + 1)..vertex_count)
+                        .filter(move |v| positions[u] < positions[*v])
+                        .map(move |v| (u, v))
+                }),
+            );
+            let poset = Poset::new(&dag);
+            let (first, second) = poset.get_two_dimensional_realizer().unwrap();
+            assert!(is_realizer(&poset, &first, &second));
+        }
+    }
+
+    fn is_interval_representation(poset: &Poset, intervals: &[(usize, usize)]) -> bool {
+        let element_count = poset.get_element_count();
+        intervals.iter().all(|(left, right)| left <= right)
+            && (0..element_count).all(|u| {
+                (0..element_count).all(|v| poset.lt(u, v) == (intervals[u].1 < intervals[v].0))
+            })
+    }
+
+    #[test]
+    fn interval_orders_and_semiorders() {
+        // 2 + 2
+        let poset = Poset::new(&crate::dag!(4; 0 => 1, 2 => 3));
+        assert!(!poset.is_interval_order());
+        assert!(!poset.is_semiorder());
+
+        // 3 + 1 is an interval order but not a semiorder.
+        let poset = Poset::new(&crate::dag!(4; 0 => 1, 1 => 2));
+        let intervals = poset.get_interval_representation().unwrap();
+        assert!(is_interval_representation(&poset, &intervals));
+        assert!(!poset.is_semiorder());
+
+        let poset = Poset::new(&crate::dag!(4; 0 => 2, 0 => 3, 1 => 3));
+        let intervals = poset.get_unit_interval_representation().unwrap();
+        assert!(is_interval_representation(&poset, &intervals));
+        assert!(intervals
+            .iter()
+            .all(|(left, right)| right - left == intervals[0].1 - intervals[0].0));
+    }
+
+    fn prop_interval_representations_are_valid(dag: DirectedAcyclicGraph) -> bool {
+        let poset = Poset::new(&dag);
+        let interval = poset.get_interval_representation();
+        let unit = poset.get_unit_interval_representation();
+        interval.is_none_or(|intervals| is_interval_representation(&poset, &intervals))
+            && unit.as_ref().is_none_or(|intervals| {
+                is_interval_representation(&poset, intervals)
+            })
+            // Every semiorder is an interval order.
+            && (unit.is_none() || poset.is_interval_order())
+    }
+
+    #[test]
+    fn interval_representations_are_valid() {
+        quickcheck::QuickCheck::new().quickcheck(
+            prop_interval_representations_are_valid as fn(DirectedAcyclicGraph) -> bool,
+        );
+    }
+
+    #[test]
+    fn completion_of_crown() {
+        let poset = Poset::new(&crate::dag!(4; 0 => 2, 0 => 3, 1 => 2, 1 => 3));
+        let (lattice, embedding) = poset.get_dedekind_macneille_completion();
+        // Bottom, 0, 1, their join, 2, 3 and top.
+        assert_eq!(lattice.get_vertex_count(), 7);
+        assert_eq!(embedding, vec![1, 2, 4, 5]);
+        let lattice = Poset::new(&lattice);
+        assert!(lattice.is_lattice());
+        assert_eq!(lattice.join(1, 2), Some(3));
+        assert_eq!(lattice.meet(4, 5), Some(3));
+    }
+
+    fn prop_completion_is_lattice_embedding(dag: DirectedAcyclicGraph) -> bool {
+        let poset = Poset::new(&dag);
+        let (lattice, embedding) = poset.get_dedekind_macneille_completion();
+        let lattice = Poset::new(&lattice);
+        let element_count = poset.get_element_count();
+        lattice.is_lattice()
+            && (0..element_count).all(|u| {
+                (0..element_count).all(|v| poset.le(u, v) == lattice.le(embedding[u], embedding[v]))
+            })
+    }
+
+    #[test]
+    fn completion_is_lattice_embedding() {
+        quickcheck::QuickCheck::new()
+            .rng(quickcheck::Gen::new(20))
+            .quickcheck(prop_completion_is_lattice_embedding as fn(DirectedAcyclicGraph) -> bool);
+    }
+}
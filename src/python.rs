@@ -0,0 +1,178 @@
+//! Python bindings.  Build the extension module with e.g. `maturin develop
+//! --features pyo3` to get an importable `dograph` Python module.  `maturin`
+//! builds the `cdylib` itself, so the crate type needn't be configured.
+//!
+//! ```python
+//! import dograph
+//! dag = dograph.DirectedAcyclicGraph.random(100, 0.1, seed=42)
+//! closure = dograph.transitive_closure(dag)
+//! ```
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rand_distr::Bernoulli;
+
+use crate::{algorithm, traversal, DirectedAcyclicGraph};
+
+/// A Python wrapper around [`DirectedAcyclicGraph`].  Invalid vertices and
+/// edges raise `ValueError` instead of panicking.
+#[pyclass(name = "DirectedAcyclicGraph", eq)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PyDirectedAcyclicGraph(pub DirectedAcyclicGraph);
+
+fn check_vertex(dag: &DirectedAcyclicGraph, u: usize) -> PyResult<()> {
+    if u < dag.get_vertex_count() {
+        Ok(())
+    } else {
+        Err(PyValueError::new_err(format!(
+            "vertex {} out of bounds for {} vertices",
+            u,
+            dag.get_vertex_count()
+        )))
+    }
+}
+
+#[pymethods]
+impl PyDirectedAcyclicGraph {
+    /// An empty DAG.
+    #[new]
+    fn new(vertex_count: usize) -> Self {
+        Self(DirectedAcyclicGraph::empty(vertex_count))
+    }
+
+    #[staticmethod]
+    fn from_edges(vertex_count: usize, edges: Vec<(usize, usize)>) -> PyResult<Self> {
+        let mut dag = DirectedAcyclicGraph::empty(vertex_count);
+        for (u, v) in edges {
+            dag.try_set_edge(u, v, true)
+                .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        }
+        Ok(Self(dag))
+    }
+
+    /// A random DAG where every admissible edge exists with probability
+    /// `edge_probability`.
+    #[staticmethod]
+    #[pyo3(signature = (vertex_count, edge_probability, seed=None))]
+    fn random(vertex_count: usize, edge_probability: f64, seed: Option<u64>) -> PyResult<Self> {
+        let distribution =
+            Bernoulli::new(edge_probability).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        let mut rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        Ok(Self(DirectedAcyclicGraph::random(
+            vertex_count,
+            &mut rng,
+            distribution,
+        )))
+    }
+
+    #[getter]
+    fn vertex_count(&self) -> usize {
+        self.0.get_vertex_count()
+    }
+
+    fn get_edge(&self, u: usize, v: usize) -> PyResult<bool> {
+        self.0
+            .try_get_edge(u, v)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    fn set_edge(&mut self, u: usize, v: usize, exists: bool) -> PyResult<()> {
+        self.0
+            .try_set_edge(u, v, exists)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    fn edges(&self) -> Vec<(usize, usize)> {
+        self.0.iter_edges().collect()
+    }
+
+    fn children(&self, u: usize) -> PyResult<Vec<usize>> {
+        check_vertex(&self.0, u)?;
+        Ok(self.0.iter_children(u).collect())
+    }
+
+    fn to_dot(&self) -> String {
+        let mut output = Vec::new();
+        crate::to_dot(&self.0, &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "DirectedAcyclicGraph.from_edges({}, {:?})",
+            self.0.get_vertex_count(),
+            self.edges()
+        )
+    }
+}
+
+#[pyfunction]
+fn transitive_closure(dag: &PyDirectedAcyclicGraph) -> PyDirectedAcyclicGraph {
+    PyDirectedAcyclicGraph(algorithm::transitive_closure(&dag.0))
+}
+
+#[pyfunction]
+fn transitive_reduction(dag: &PyDirectedAcyclicGraph) -> PyDirectedAcyclicGraph {
+    PyDirectedAcyclicGraph(algorithm::transitive_reduction(&dag.0))
+}
+
+/// A mapping: vertex -> sorted list of its descendants.
+#[pyfunction]
+fn get_descendants(dag: &PyDirectedAcyclicGraph) -> Vec<Vec<usize>> {
+    algorithm::get_descendants(&dag.0)
+        .iter()
+        .map(|descendants| descendants.ones().collect())
+        .collect()
+}
+
+#[pyfunction]
+fn count_reachable_pairs(dag: &PyDirectedAcyclicGraph) -> usize {
+    algorithm::count_reachable_pairs(&dag.0)
+}
+
+#[pyfunction]
+fn is_weakly_connected(dag: &PyDirectedAcyclicGraph) -> bool {
+    algorithm::is_weakly_connected(&dag.0)
+}
+
+#[pyfunction]
+fn get_topologically_ordered_vertices(dag: &PyDirectedAcyclicGraph) -> Vec<usize> {
+    traversal::get_topologically_ordered_vertices(&dag.0)
+}
+
+#[pymodule]
+fn dograph(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyDirectedAcyclicGraph>()?;
+    m.add_function(wrap_pyfunction!(transitive_closure, m)?)?;
+    m.add_function(wrap_pyfunction!(transitive_reduction, m)?)?;
+    m.add_function(wrap_pyfunction!(get_descendants, m)?)?;
+    m.add_function(wrap_pyfunction!(count_reachable_pairs, m)?)?;
+    m.add_function(wrap_pyfunction!(is_weakly_connected, m)?)?;
+    m.add_function(wrap_pyfunction!(get_topologically_ordered_vertices, m)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wraps_dag() {
+        let dag = PyDirectedAcyclicGraph::from_edges(3, vec![(0, 1), (1, 2)]).unwrap();
+        assert_eq!(dag.vertex_count(), 3);
+        assert!(dag.get_edge(0, 1).unwrap());
+        assert_eq!(
+            transitive_closure(&dag).edges(),
+            vec![(0, 1), (0, 2), (1, 2)]
+        );
+        assert_eq!(
+            PyDirectedAcyclicGraph::random(10, 0.5, Some(1)).unwrap(),
+            PyDirectedAcyclicGraph::random(10, 0.5, Some(1)).unwrap()
+        );
+    }
+}
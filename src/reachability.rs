@@ -0,0 +1,345 @@
+//! Reachability indices: precomputed structures answering "is there a path
+//! from `u` to `v`?" using less memory than the full transitive closure.
+
+use std::collections::VecDeque;
+
+use fixedbitset::FixedBitSet;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::algorithm::get_minimum_chain_decomposition;
+use crate::DirectedAcyclicGraph;
+
+/// A reachability index based on a [minimum chain
+/// decomposition](get_minimum_chain_decomposition).  For every vertex `u` and
+/// every chain `c` it stores the earliest position in `c` reachable from `u`.
+/// Since every vertex in a chain reaches all the later ones, that's enough to
+/// answer reachability queries in `O(1)`.
+///
+/// Takes `O(|V| * k)` memory where `k` is the width of the DAG, which makes
+/// it much smaller than the closure for narrow graphs, e.g. dependency graphs
+/// consisting of a few long pipelines.  Building the index computes the
+/// transitive closure temporarily, at one bit per vertex pair.
+#[derive(Clone, Debug)]
+pub struct ChainCoverIndex {
+    // A mapping: vertex -> (chain, position within the chain)
+    chain_positions: Vec<(usize, usize)>,
+    chain_count: usize,
+    // `|V| x chain_count` row-major matrix: the earliest position in the chain
+    // reachable from the vertex (including the vertex itself) or `usize::MAX`
+    earliest_reachable: Vec<usize>,
+}
+
+impl ChainCoverIndex {
+    pub fn new(dag: &DirectedAcyclicGraph) -> Self {
+        let vertex_count = dag.get_vertex_count();
+        let chains = get_minimum_chain_decomposition(dag);
+        let chain_count = chains.len();
+        let mut chain_positions: Vec<(usize, usize)> = vec![(0, 0); vertex_count];
+        for (c, chain) in chains.iter().enumerate() {
+            for (position, u) in chain.iter().enumerate() {
+                chain_positions[*u] = (c, position);
+            }
+        }
+
+        let mut earliest_reachable: Vec<usize> = vec![usize::MAX; vertex_count * chain_count];
+        for u in (0..vertex_count).rev() {
+            let (before, after) = earliest_reachable.split_at_mut((u + 1) * chain_count);
+            let row = &mut before[u * chain_count..];
+            for v in dag.iter_children(u) {
+                let v_offset = (v - u - 1) * chain_count;
+                let v_row = &after[v_offset..v_offset + chain_count];
+                for (position, v_position) in row.iter_mut().zip(v_row) {
+                    *position = (*position).min(*v_position);
+                }
+            }
+            let (c, position) = chain_positions[u];
+            row[c] = position;
+        }
+
+        Self {
+            chain_positions,
+            chain_count,
+            earliest_reachable,
+        }
+    }
+
+    /// The number of chains, i.e. the width of the DAG.
+    pub fn get_chain_count(&self) -> usize {
+        self.chain_count
+    }
+
+    /// Answers whether there's a non-empty path from `u` to `v` in `O(1)`.
+    pub fn has_path(&self, u: usize, v: usize) -> bool {
+        let (c, position) = self.chain_positions[v];
+        u != v && self.earliest_reachable[u * self.chain_count + c] <= position
+    }
+}
+
+/// A [2-hop labeling](https://doi.org/10.1137/S0097539702403098)
+/// reachability index: every vertex gets a set of hubs it reaches (out-label)
+/// and a set of hubs reaching it (in-label), such that `v` is reachable from
+/// `u` iff the out-label of `u` and the in-label of `v` share a hub.
+///
+/// Labels are built with pruned landmark labeling (Akiba, Iwata and Yoshida,
+/// 2013): hubs are processed in the order of decreasing `(in-degree + 1) *
+/// (out-degree + 1)` and each one runs a forward and a backward BFS that stops
+/// at vertices whose reachability from the hub is already covered by the
+/// labels so far.  Neither building nor querying needs the transitive
+/// closure.  Queries are `O(label size)`.
+#[derive(Clone, Debug)]
+pub struct TwoHopIndex {
+    // Hub ranks in increasing order.
+    out_labels: Vec<Vec<usize>>,
+    in_labels: Vec<Vec<usize>>,
+}
+
+fn intersects(left: &[usize], right: &[usize]) -> bool {
+    let (mut i, mut j) = (0, 0);
+    while i < left.len() && j < right.len() {
+        match left[i].cmp(&right[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => return true,
+        }
+    }
+    false
+}
+
+impl TwoHopIndex {
+    pub fn new(dag: &DirectedAcyclicGraph) -> Self {
+        let vertex_count = dag.get_vertex_count();
+        let mut children: Vec<Vec<usize>> = vec![Vec::new(); vertex_count];
+        let mut parents: Vec<Vec<usize>> = vec![Vec::new(); vertex_count];
+        for (u, v) in dag.iter_edges() {
+            children[u].push(v);
+            parents[v].push(u);
+        }
+        let mut hubs: Vec<usize> = (0..vertex_count).collect();
+        hubs.sort_by_key(|u| std::cmp::Reverse((parents[*u].len() + 1) * (children[*u].len() + 1)));
+
+        let mut index = Self {
+            out_labels: vec![Vec::new(); vertex_count],
+            in_labels: vec![Vec::new(); vertex_count],
+        };
+        let mut visited = FixedBitSet::with_capacity(vertex_count);
+        let mut queue: VecDeque<usize> = VecDeque::new();
+        for (rank, hub) in hubs.iter().enumerate() {
+            // Forward: vertices reachable from `hub` get it in their in-labels.
+            visited.clear();
+            visited.insert(*hub);
+            queue.push_back(*hub);
+            while let Some(v) = queue.pop_front() {
+                if v != *hub && intersects(&index.out_labels[*hub], &index.in_labels[v]) {
+                    continue;
+                }
+                index.in_labels[v].push(rank);
+                for w in &children[v] {
+                    if !visited.put(*w) {
+                        queue.push_back(*w);
+                    }
+                }
+            }
+
+            // Backward: vertices reaching `hub` get it in their out-labels.
+            visited.clear();
+            visited.insert(*hub);
+            queue.push_back(*hub);
+            while let Some(v) = queue.pop_front() {
+                if v != *hub && intersects(&index.out_labels[v], &index.in_labels[*hub]) {
+                    continue;
+                }
+                index.out_labels[v].push(rank);
+                for w in &parents[v] {
+                    if !visited.put(*w) {
+                        queue.push_back(*w);
+                    }
+                }
+            }
+        }
+        index
+    }
+
+    /// The total number of hubs in all the labels.
+    pub fn get_label_size(&self) -> usize {
+        self.out_labels.iter().map(Vec::len).sum::<usize>()
+            + self.in_labels.iter().map(Vec::len).sum::<usize>()
+    }
+
+    /// Answers whether there's a non-empty path from `u` to `v`.
+    pub fn has_path(&self, u: usize, v: usize) -> bool {
+        u != v && intersects(&self.out_labels[u], &self.in_labels[v])
+    }
+}
+
+/// Randomized interval labels after
+/// [GRAIL](https://doi.org/10.14778/1920841.1920879) (Yildirim, Chaoji and
+/// Zaki, 2010).  Every vertex gets `dimensions` intervals, one for each random
+/// depth-first traversal: `[lowest post-order rank among its descendants, its
+/// own post-order rank]`.  If `v` is reachable from `u`, the intervals of `v`
+/// are contained in the ones of `u`, so a non-contained interval answers "no"
+/// in `O(dimensions)`.  Otherwise the query falls back to a depth-first search
+/// that skips vertices whose intervals don't contain the ones of `v`.
+///
+/// Takes `O(|V| * dimensions)` memory, trading query speed for memory between
+/// no index at all and the full transitive closure.
+#[derive(Clone, Debug)]
+pub struct GrailIndex<'a> {
+    dag: &'a DirectedAcyclicGraph,
+    dimensions: usize,
+    // `|V| x dimensions` row-major matrix of (low, rank) intervals
+    intervals: Vec<(usize, usize)>,
+}
+
+impl<'a> GrailIndex<'a> {
+    pub fn new<R: Rng>(dag: &'a DirectedAcyclicGraph, dimensions: usize, rng: &mut R) -> Self {
+        let vertex_count = dag.get_vertex_count();
+        let mut intervals: Vec<(usize, usize)> = vec![(0, 0); vertex_count * dimensions];
+        let mut has_parents = FixedBitSet::with_capacity(vertex_count);
+        has_parents.extend(dag.iter_edges().map(|(_, v)| v));
+        let mut roots: Vec<usize> = (0..vertex_count).filter(|u| !has_parents[*u]).collect();
+        let mut visited = FixedBitSet::with_capacity(vertex_count);
+        for dimension in 0..dimensions {
+            roots.shuffle(rng);
+            visited.clear();
+            let mut rank = 0;
+            // (vertex, its children yet to be visited)
+            let mut stack: Vec<(usize, Vec<usize>)> = Vec::new();
+            for root in &roots {
+                visited.insert(*root);
+                let mut children: Vec<usize> = dag.iter_children(*root).collect();
+                children.shuffle(rng);
+                stack.push((*root, children));
+                while let Some((u, children)) = stack.last_mut() {
+                    let u = *u;
+                    match children.pop() {
+                        Some(v) if !visited.put(v) => {
+                            let mut v_children: Vec<usize> = dag.iter_children(v).collect();
+                            v_children.shuffle(rng);
+                            stack.push((v, v_children));
+                        }
+                        Some(_) => {}
+                        None => {
+                            stack.pop();
+                            rank += 1;
+                            let low = dag
+                                .iter_children(u)
+                                .map(|v| intervals[v * dimensions + dimension].0)
+                                .fold(rank, usize::min);
+                            intervals[u * dimensions + dimension] = (low, rank);
+                        }
+                    }
+                }
+            }
+        }
+        Self {
+            dag,
+            dimensions,
+            intervals,
+        }
+    }
+
+    fn get_intervals(&self, u: usize) -> &[(usize, usize)] {
+        &self.intervals[u * self.dimensions..(u + 1) * self.dimensions]
+    }
+
+    /// Whether the intervals of `v` are contained in the ones of `u`, which is
+    /// a necessary condition for `v` to be reachable from `u`.
+    pub fn may_have_path(&self, u: usize, v: usize) -> bool {
+        self.get_intervals(u)
+            .iter()
+            .zip(self.get_intervals(v))
+            .all(|((u_low, u_rank), (v_low, v_rank))| u_low <= v_low && v_rank <= u_rank)
+    }
+
+    /// Answers whether there's a non-empty path from `u` to `v`.
+    pub fn has_path(&self, u: usize, v: usize) -> bool {
+        if u >= v || !self.may_have_path(u, v) {
+            return false;
+        }
+        let mut visited = FixedBitSet::with_capacity(self.dag.get_vertex_count());
+        let mut to_visit: Vec<usize> = vec![u];
+        while let Some(w) = to_visit.pop() {
+            for x in self.dag.iter_children(w).take_while(|x| *x <= v) {
+                if x == v {
+                    return true;
+                }
+                if !visited.put(x) && self.may_have_path(x, v) {
+                    to_visit.push(x);
+                }
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::get_descendants;
+    use rand::SeedableRng;
+
+    fn prop_chain_cover_index_agrees_with_closure(dag: DirectedAcyclicGraph) -> bool {
+        let index = ChainCoverIndex::new(&dag);
+        let descendants = get_descendants(&dag);
+        (0..dag.get_vertex_count()).all(|u| {
+            (0..dag.get_vertex_count()).all(|v| index.has_path(u, v) == descendants[u].contains(v))
+        })
+    }
+
+    #[test]
+    fn chain_cover_index_agrees_with_closure() {
+        quickcheck::QuickCheck::new().quickcheck(
+            prop_chain_cover_index_agrees_with_closure as fn(DirectedAcyclicGraph) -> bool,
+        );
+        let pipelines = crate::dag!(6; 0 => 2, 2 => 4, 1 => 3, 3 => 5, 2 => 5);
+        let index = ChainCoverIndex::new(&pipelines);
+        assert_eq!(index.get_chain_count(), 2);
+        assert!(index.has_path(0, 5));
+        assert!(!index.has_path(1, 4));
+    }
+
+    fn prop_two_hop_index_agrees_with_closure(dag: DirectedAcyclicGraph) -> bool {
+        let index = TwoHopIndex::new(&dag);
+        let descendants = get_descendants(&dag);
+        (0..dag.get_vertex_count()).all(|u| {
+            (0..dag.get_vertex_count()).all(|v| index.has_path(u, v) == descendants[u].contains(v))
+        })
+    }
+
+    #[test]
+    fn two_hop_index_agrees_with_closure() {
+        quickcheck::QuickCheck::new()
+            .quickcheck(prop_two_hop_index_agrees_with_closure as fn(DirectedAcyclicGraph) -> bool);
+        // Everything goes through the hub 2.
+        let hourglass = crate::dag!(5; 0 => 2, 1 => 2, 2 => 3, 2 => 4);
+        let index = TwoHopIndex::new(&hourglass);
+        assert!(index.has_path(0, 4));
+        assert!(!index.has_path(3, 4));
+        assert_eq!(index.get_label_size(), 2 * 5 + 4);
+    }
+
+    fn prop_grail_index_agrees_with_closure(dag: DirectedAcyclicGraph) -> bool {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(dag.get_vertex_count() as u64);
+        let index = GrailIndex::new(&dag, 3, &mut rng);
+        let descendants = get_descendants(&dag);
+        (0..dag.get_vertex_count()).all(|u| {
+            (0..dag.get_vertex_count()).all(|v| {
+                index.has_path(u, v) == descendants[u].contains(v)
+                    && (!descendants[u].contains(v) || index.may_have_path(u, v))
+            })
+        })
+    }
+
+    #[test]
+    fn grail_index_agrees_with_closure() {
+        quickcheck::QuickCheck::new()
+            .quickcheck(prop_grail_index_agrees_with_closure as fn(DirectedAcyclicGraph) -> bool);
+        let fork = crate::dag!(3; 0 => 1, 0 => 2);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+        let index = GrailIndex::new(&fork, 2, &mut rng);
+        // Siblings get disjoint intervals in every traversal.
+        assert!(!index.may_have_path(1, 2));
+        assert!(index.has_path(0, 2));
+    }
+}
@@ -0,0 +1,339 @@
+use std::ops::{Add, Sub};
+
+use crate::paths::get_in_eccentricities;
+use crate::traversal::get_incoming_edges_count;
+use crate::weighted::WeightedDag;
+use crate::DirectedAcyclicGraph;
+
+/// The result of the [critical path
+/// method](https://en.wikipedia.org/wiki/Critical_path_method).  See
+/// [`get_critical_path_schedule`].
+///
+/// All the vectors are mappings: vertex -> time.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CriticalPathSchedule<T> {
+    pub earliest_start: Vec<T>,
+    pub earliest_finish: Vec<T>,
+    pub latest_start: Vec<T>,
+    pub latest_finish: Vec<T>,
+    /// The time it takes to finish all the tasks.
+    pub makespan: T,
+}
+
+impl<T> CriticalPathSchedule<T>
+where
+    T: Copy + PartialOrd + Sub<Output = T> + Default,
+{
+    /// How much a task can be delayed without delaying the whole project.
+    pub fn get_slack(&self, vertex: usize) -> T {
+        self.latest_start[vertex] - self.earliest_start[vertex]
+    }
+
+    /// A task is critical iff it has no slack.
+    pub fn is_critical(&self, vertex: usize) -> bool {
+        self.get_slack(vertex) <= T::default()
+    }
+
+    /// Iterates over critical tasks in increasing order.
+    pub fn iter_critical_vertices(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.earliest_start.len()).filter(move |v| self.is_critical(*v))
+    }
+
+    /// Returns every critical path, i.e. every path of critical tasks that
+    /// starts at time zero, finishes at [`Self::makespan`] and where each task
+    /// starts as soon as the previous one finishes.
+    ///
+    /// Note that there may be exponentially many such paths.
+    pub fn get_critical_paths(&self, dag: &DirectedAcyclicGraph) -> Vec<Vec<usize>> {
+        let mut result: Vec<Vec<usize>> = Vec::new();
+        let mut stack: Vec<Vec<usize>> = self
+            .iter_critical_vertices()
+            .filter(|v| self.earliest_start[*v] <= T::default())
+            .map(|v| vec![v])
+            .collect();
+        stack.reverse();
+        while let Some(path) = stack.pop() {
+            let u = *path.last().unwrap();
+            if self.earliest_finish[u] >= self.makespan {
+                result.push(path);
+                continue;
+            }
+            let mut next: Vec<Vec<usize>> = dag
+                .iter_children(u)
+                .filter(|v| self.is_critical(*v))
+                .filter(|v| self.earliest_start[*v] <= self.earliest_finish[u])
+                .map(|v| {
+                    let mut extended = path.clone();
+                    extended.push(v);
+                    extended
+                })
+                .collect();
+            next.reverse();
+            stack.extend(next);
+        }
+        result
+    }
+}
+
+fn max<T: PartialOrd>(left: T, right: T) -> T {
+    if right > left {
+        right
+    } else {
+        left
+    }
+}
+
+fn min<T: PartialOrd>(left: T, right: T) -> T {
+    if right < left {
+        right
+    } else {
+        left
+    }
+}
+
+/// Computes the earliest and latest start and finish times of every task
+/// (vertex) in a project given the time it takes to complete each one
+/// (`durations`: vertex -> duration).  Edges are dependencies: a task can
+/// start only once all of its parents have finished.  `T::default()` is
+/// assumed to be zero.
+pub fn get_critical_path_schedule<T>(
+    dag: &DirectedAcyclicGraph,
+    durations: &[T],
+) -> CriticalPathSchedule<T>
+where
+    T: Copy + PartialOrd + Add<Output = T> + Sub<Output = T> + Default,
+{
+    let vertex_count = dag.get_vertex_count();
+    assert_eq!(durations.len(), vertex_count);
+
+    let mut earliest_start: Vec<T> = vec![T::default(); vertex_count];
+    let mut earliest_finish: Vec<T> = vec![T::default(); vertex_count];
+    let mut makespan = T::default();
+    for u in 0..vertex_count {
+        earliest_finish[u] = earliest_start[u] + durations[u];
+        makespan = max(makespan, earliest_finish[u]);
+        for v in dag.iter_children(u) {
+            earliest_start[v] = max(earliest_start[v], earliest_finish[u]);
+        }
+    }
+
+    let mut latest_start: Vec<T> = vec![T::default(); vertex_count];
+    let mut latest_finish: Vec<T> = vec![T::default(); vertex_count];
+    for u in (0..vertex_count).rev() {
+        latest_finish[u] = dag
+            .iter_children(u)
+            .map(|v| latest_start[v])
+            .fold(makespan, min);
+        latest_start[u] = latest_finish[u] - durations[u];
+    }
+
+    CriticalPathSchedule {
+        earliest_start,
+        earliest_finish,
+        latest_start,
+        latest_finish,
+        makespan,
+    }
+}
+
+/// Groups vertices into waves where every vertex has all its parents in
+/// strictly earlier waves, i.e. the sets of tasks that can be run
+/// concurrently if the waves are run one after another.  Every vertex is in
+/// the earliest possible wave: the one given by the length of the longest
+/// path ending at it (see [`crate::paths::get_in_eccentricities`]).  Vertices
+/// within a wave are sorted.
+pub fn get_schedule_levels(dag: &DirectedAcyclicGraph) -> Vec<Vec<usize>> {
+    let mut levels: Vec<Vec<usize>> = Vec::new();
+    for (u, level) in get_in_eccentricities(dag).into_iter().enumerate() {
+        if level >= levels.len() {
+            levels.resize_with(level + 1, Vec::new);
+        }
+        levels[level].push(u);
+    }
+    levels
+}
+
+/// A schedule of tasks on identical machines.  See [`get_list_schedule`].
+///
+/// The vectors are mappings: vertex -> start time and vertex -> machine.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ListSchedule<T> {
+    pub start: Vec<T>,
+    pub machine: Vec<usize>,
+    /// The time it takes to finish all the tasks.
+    pub makespan: T,
+}
+
+/// Assigns every task (vertex) to one of `machine_count` identical machines,
+/// so that each task starts only once all of its parents have finished and
+/// every machine runs one task at a time.  The vertex weights of `dag` are the
+/// task durations; edge weights are ignored.  `T::default()` is assumed to be
+/// zero.
+///
+/// This is [list scheduling](https://en.wikipedia.org/wiki/List_scheduling)
+/// prioritized by the critical path method: whenever a machine becomes free,
+/// it takes the ready task with the smallest latest start time (see
+/// [`get_critical_path_schedule`]), ties broken by picking the smaller
+/// vertex.  Machines never idle while there's a ready task, so the makespan is
+/// within a factor of `2 - 1 / machine_count` of the optimum.
+pub fn get_list_schedule<T>(dag: &WeightedDag<T>, machine_count: usize) -> ListSchedule<T>
+where
+    T: Copy + PartialOrd + Add<Output = T> + Sub<Output = T> + Default,
+{
+    assert!(machine_count > 0);
+    let vertex_count = dag.get_vertex_count();
+    let durations = dag.get_vertex_weights();
+    let priorities = get_critical_path_schedule(dag, durations).latest_start;
+
+    let mut incoming_edges_count = get_incoming_edges_count(dag);
+    // The time at which all the already scheduled parents are finished.
+    let mut release: Vec<T> = vec![T::default(); vertex_count];
+    let mut ready: Vec<usize> = (0..vertex_count)
+        .filter(|v| incoming_edges_count[*v] == 0)
+        .collect();
+    let mut machine_free: Vec<T> = vec![T::default(); machine_count];
+    let mut start: Vec<T> = vec![T::default(); vertex_count];
+    let mut machine: Vec<usize> = vec![0; vertex_count];
+    let mut makespan = T::default();
+    while !ready.is_empty() {
+        let m = (1..machine_count).fold(0, |best, m| {
+            if machine_free[m] < machine_free[best] {
+                m
+            } else {
+                best
+            }
+        });
+        // If nothing is ready when the machine gets free, it waits for the
+        // earliest release.
+        let earliest_release = ready.iter().map(|v| release[*v]).reduce(min).unwrap();
+        let time = max(machine_free[m], earliest_release);
+        let (i, &u) = ready
+            .iter()
+            .enumerate()
+            .filter(|(_, v)| release[**v] <= time)
+            .reduce(|best, candidate| {
+                if priorities[*candidate.1] < priorities[*best.1]
+                    || (priorities[*candidate.1] <= priorities[*best.1] && candidate.1 < best.1)
+                {
+                    candidate
+                } else {
+                    best
+                }
+            })
+            .unwrap();
+        ready.swap_remove(i);
+        let finish = time + durations[u];
+        start[u] = time;
+        machine[u] = m;
+        machine_free[m] = finish;
+        makespan = max(makespan, finish);
+        for v in dag.iter_children(u) {
+            release[v] = max(release[v], finish);
+            incoming_edges_count[v] -= 1;
+            if incoming_edges_count[v] == 0 {
+                ready.push(v);
+            }
+        }
+    }
+
+    ListSchedule {
+        start,
+        machine,
+        makespan,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn critical_path_of_small_project() {
+        //   0 (3) -> 1 (2) -> 3 (4)
+        //   0 (3) -> 2 (1) -> 3 (4)
+        //            4 (5)
+        let dag = crate::dag!(5; 0 => 1, 0 => 2, 1 => 3, 2 => 3);
+        let schedule = get_critical_path_schedule(&dag, &[3, 2, 1, 4, 5]);
+        assert_eq!(schedule.makespan, 9);
+        assert_eq!(schedule.earliest_start, vec![0, 3, 3, 5, 0]);
+        assert_eq!(schedule.latest_start, vec![0, 3, 4, 5, 4]);
+        assert_eq!(
+            (0..5).map(|v| schedule.get_slack(v)).collect::<Vec<_>>(),
+            vec![0, 0, 1, 0, 4]
+        );
+        assert_eq!(
+            schedule.iter_critical_vertices().collect::<Vec<_>>(),
+            vec![0, 1, 3]
+        );
+        assert_eq!(schedule.get_critical_paths(&dag), vec![vec![0, 1, 3]]);
+    }
+
+    #[test]
+    fn multiple_critical_paths() {
+        let dag = crate::dag!(4; 0 => 1, 0 => 2, 1 => 3, 2 => 3);
+        let schedule = get_critical_path_schedule(&dag, &[1.0, 2.0, 2.0, 1.0]);
+        assert_eq!(schedule.makespan, 4.0);
+        assert_eq!(
+            schedule.get_critical_paths(&dag),
+            vec![vec![0, 1, 3], vec![0, 2, 3]]
+        );
+    }
+
+    #[test]
+    fn schedule_levels() {
+        //  0 -> 1 -> 3
+        //  2 ------> 3
+        //  4
+        let dag = crate::dag!(5; 0 => 1, 1 => 3, 2 => 3);
+        assert_eq!(
+            get_schedule_levels(&dag),
+            vec![vec![0, 2, 4], vec![1], vec![3]]
+        );
+        assert!(get_schedule_levels(&DirectedAcyclicGraph::empty(0)).is_empty());
+    }
+
+    #[test]
+    fn list_schedule_on_two_machines() {
+        //   0 (3) -> 1 (2) -> 3 (4)
+        //   0 (3) -> 2 (1) -> 3 (4)
+        //            4 (5)
+        let durations = vec![3, 2, 1, 4, 5];
+        let dag = WeightedDag::from_dag(crate::dag!(5; 0 => 1, 0 => 2, 1 => 3, 2 => 3), |_, _| 0)
+            .with_vertex_weights(durations.clone());
+        let schedule = get_list_schedule(&dag, 2);
+        assert_eq!(schedule.start, vec![0, 3, 5, 6, 0]);
+        assert_eq!(schedule.makespan, 10);
+        assert_eq!(get_list_schedule(&dag, 1).makespan, 15);
+        assert_eq!(
+            get_list_schedule(&dag, 5).start,
+            get_critical_path_schedule(&dag, &durations).earliest_start
+        );
+    }
+
+    fn prop_list_schedule_is_feasible(dag: DirectedAcyclicGraph) -> bool {
+        let durations: Vec<u32> = (0..dag.get_vertex_count() as u32)
+            .map(|u| u * 7 % 4)
+            .collect();
+        let schedule = get_list_schedule(
+            &WeightedDag::from_dag(dag.clone(), |_, _| 0).with_vertex_weights(durations.clone()),
+            3,
+        );
+        let finish = |u: usize| schedule.start[u] + durations[u];
+        let vertex_count = dag.get_vertex_count();
+        dag.iter_edges()
+            .all(|(u, v)| finish(u) <= schedule.start[v])
+            && (0..vertex_count).all(|u| {
+                ((u + 1)..vertex_count)
+                    .filter(|v| schedule.machine[u] == schedule.machine[*v])
+                    .all(|v| finish(u) <= schedule.start[v] || finish(v) <= schedule.start[u])
+            })
+            && schedule.makespan >= get_critical_path_schedule(&dag, &durations).makespan
+            && (0..vertex_count).all(|u| finish(u) <= schedule.makespan)
+    }
+
+    #[test]
+    fn list_schedule_is_feasible() {
+        quickcheck::QuickCheck::new()
+            .quickcheck(prop_list_schedule_is_feasible as fn(DirectedAcyclicGraph) -> bool);
+    }
+}
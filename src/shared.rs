@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use crate::DirectedAcyclicGraph;
+
+/// A cheaply clonable handle to a [`DirectedAcyclicGraph`] that can be sent to
+/// and read from many threads at once.
+///
+/// Clones share the same adjacency matrix until one of them is mutated, at
+/// which point that one gets its own copy (copy-on-write).  Read access is via
+/// [`std::ops::Deref`], so all of the [`DirectedAcyclicGraph`] methods and the
+/// functions in [`crate::algorithm`] and [`crate::traversal`] work as usual.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SharedDirectedAcyclicGraph {
+    dag: Arc<DirectedAcyclicGraph>,
+}
+
+impl SharedDirectedAcyclicGraph {
+    pub fn new(dag: DirectedAcyclicGraph) -> Self {
+        Self { dag: Arc::new(dag) }
+    }
+
+    /// Returns a mutable reference to the DAG, copying it first if it's shared
+    /// with any other handle.
+    pub fn make_mut(&mut self) -> &mut DirectedAcyclicGraph {
+        Arc::make_mut(&mut self.dag)
+    }
+
+    /// Requires `u < v`.  Panics otherwise.  See [`Self::make_mut`].
+    pub fn set_edge(&mut self, u: usize, v: usize, exists: bool) {
+        self.make_mut().set_edge(u, v, exists);
+    }
+
+    /// Returns `true` iff both handles point to the same copy of the DAG.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.dag, &other.dag)
+    }
+
+    /// Returns the DAG, copying it only if it's shared with any other handle.
+    pub fn into_dag(self) -> DirectedAcyclicGraph {
+        Arc::try_unwrap(self.dag).unwrap_or_else(|dag| (*dag).clone())
+    }
+}
+
+impl std::ops::Deref for SharedDirectedAcyclicGraph {
+    type Target = DirectedAcyclicGraph;
+
+    fn deref(&self) -> &Self::Target {
+        &self.dag
+    }
+}
+
+impl From<DirectedAcyclicGraph> for SharedDirectedAcyclicGraph {
+    fn from(dag: DirectedAcyclicGraph) -> Self {
+        Self::new(dag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn copies_on_first_mutation() {
+        let original = SharedDirectedAcyclicGraph::new(crate::dag!(3; 0 => 1));
+        let mut copy = original.clone();
+        assert!(copy.ptr_eq(&original));
+
+        let reader = {
+            let shared = original.clone();
+            std::thread::spawn(move || shared.get_edge(0, 1))
+        };
+        assert!(reader.join().unwrap());
+
+        copy.set_edge(1, 2, true);
+        assert!(!copy.ptr_eq(&original));
+        assert!(!original.get_edge(1, 2));
+        assert!(copy.get_edge(1, 2));
+        assert_eq!(copy.into_dag(), crate::dag!(3; 0 => 1, 1 => 2));
+    }
+}
@@ -0,0 +1,138 @@
+use roaring::RoaringBitmap;
+
+use crate::DirectedAcyclicGraph;
+
+/// A directed acyclic graph storing the children of every vertex in a
+/// [`RoaringBitmap`] instead of one dense bit matrix.
+///
+/// Memory use is proportional to the number of edges rather than the square of
+/// the number of vertices, which makes it suitable for huge and very sparse
+/// graphs, e.g. 500k vertices with about 1M edges.  The vertex count is limited
+/// to [`u32::MAX`].
+#[derive(Clone, PartialEq)]
+pub struct SparseDirectedAcyclicGraph {
+    children: Vec<RoaringBitmap>,
+}
+
+impl std::fmt::Debug for SparseDirectedAcyclicGraph {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ones: Vec<(usize, usize)> = self.iter_edges().collect();
+        write!(
+            f,
+            "SparseDirectedAcyclicGraph::from_edges_iter({}, vec!{:?}.into_iter())",
+            self.get_vertex_count(),
+            ones
+        )?;
+        Ok(())
+    }
+}
+
+impl SparseDirectedAcyclicGraph {
+    pub fn empty(vertex_count: usize) -> Self {
+        assert!(vertex_count <= u32::MAX as usize);
+        Self {
+            children: vec![RoaringBitmap::new(); vertex_count],
+        }
+    }
+
+    /// Requires `u < vertex_count && v < vertex_count && u < v` for every edge
+    /// `(u, v)` in `edges`.  Panics otherwise.
+    pub fn from_edges_iter<I: Iterator<Item = (usize, usize)>>(
+        vertex_count: usize,
+        edges: I,
+    ) -> Self {
+        let mut dag = Self::empty(vertex_count);
+        for (u, v) in edges {
+            dag.set_edge(u, v, true);
+        }
+        dag
+    }
+
+    #[inline]
+    pub fn get_vertex_count(&self) -> usize {
+        self.children.len()
+    }
+
+    pub fn get_edge_count(&self) -> usize {
+        self.children.iter().map(|c| c.len() as usize).sum()
+    }
+
+    /// Requires `u < v`.  Panics otherwise.
+    pub fn get_edge(&self, u: usize, v: usize) -> bool {
+        assert!(u < self.get_vertex_count());
+        assert!(v < self.get_vertex_count());
+        assert!(u < v);
+        self.children[u].contains(v as u32)
+    }
+
+    /// Requires `u < v`.  Panics otherwise.
+    pub fn set_edge(&mut self, u: usize, v: usize, exists: bool) {
+        assert!(u < self.get_vertex_count());
+        assert!(v < self.get_vertex_count());
+        assert!(u < v);
+        if exists {
+            self.children[u].insert(v as u32);
+        } else {
+            self.children[u].remove(v as u32);
+        }
+    }
+
+    /// Iterates over the edges in increasing order.
+    pub fn iter_edges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..self.get_vertex_count()).flat_map(move |u| self.iter_children(u).map(move |v| (u, v)))
+    }
+
+    /// Iterates over vertices `v` such that there's an edge `(u, v)` in the
+    /// DAG, in increasing order.
+    pub fn iter_children(&self, u: usize) -> impl Iterator<Item = usize> + '_ {
+        self.children[u].iter().map(|v| v as usize)
+    }
+
+    /// The children of `u` as a bitmap, e.g. for further set algebra.
+    pub fn get_children(&self, u: usize) -> &RoaringBitmap {
+        &self.children[u]
+    }
+
+    /// Converts to a dense DAG.  Note that this takes `O(|V|^2)` memory.
+    pub fn to_dag(&self) -> DirectedAcyclicGraph {
+        DirectedAcyclicGraph::from_edges_iter(self.get_vertex_count(), self.iter_edges())
+    }
+}
+
+impl From<&DirectedAcyclicGraph> for SparseDirectedAcyclicGraph {
+    fn from(dag: &DirectedAcyclicGraph) -> Self {
+        Self::from_edges_iter(dag.get_vertex_count(), dag.iter_edges())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn huge_sparse_dag() {
+        let vertex_count = 500_000;
+        let dag = SparseDirectedAcyclicGraph::from_edges_iter(
+            vertex_count,
+            (0..vertex_count - 2).flat_map(|u| [(u, u + 1), (u, u + 2)]),
+        );
+        assert_eq!(dag.get_edge_count(), 2 * (vertex_count - 2));
+        assert!(dag.get_edge(123_456, 123_458));
+        assert!(!dag.get_edge(123_456, 123_459));
+        assert_eq!(
+            dag.iter_children(vertex_count - 3).collect::<Vec<_>>(),
+            vec![vertex_count - 2, vertex_count - 1]
+        );
+    }
+
+    fn prop_roundtrips_through_dense(dag: DirectedAcyclicGraph) -> bool {
+        let sparse = SparseDirectedAcyclicGraph::from(&dag);
+        sparse.to_dag() == dag && sparse.iter_edges().eq(dag.iter_edges())
+    }
+
+    #[test]
+    fn roundtrips_through_dense() {
+        quickcheck::QuickCheck::new()
+            .quickcheck(prop_roundtrips_through_dense as fn(DirectedAcyclicGraph) -> bool);
+    }
+}
@@ -0,0 +1,152 @@
+//! [proptest](https://crates.io/crates/proptest) strategies for generating DAGs,
+//! alone or together with values that depend on them.  Unlike the
+//! [`quickcheck::Arbitrary`] impl, these shrink edge by edge.
+//!
+//! ```
+//! use dograph::algorithm::get_descendants;
+//! use dograph::strategies::dag_with_comparable_pair;
+//! use proptest::prelude::*;
+//!
+//! proptest!(|((dag, u, v) in dag_with_comparable_pair(2..10))| {
+//!     prop_assert!(get_descendants(&dag)[u].contains(v));
+//! });
+//! ```
+
+use std::ops::Range;
+
+use proptest::prelude::*;
+
+use crate::algorithm::get_descendants;
+use crate::strictly_upper_triangular_logical_matrix::strictly_upper_triangular_matrix_capacity;
+use crate::traversal::get_topological_order_by;
+use crate::DirectedAcyclicGraph;
+
+/// DAGs with a vertex count from `vertex_counts` where every edge exists with
+/// probability 1/2.  Shrinks towards fewer vertices and fewer edges.
+pub fn dag(vertex_counts: Range<usize>) -> impl Strategy<Value = DirectedAcyclicGraph> {
+    vertex_counts
+        .prop_flat_map(|vertex_count| {
+            (
+                Just(vertex_count),
+                proptest::collection::vec(
+                    any::<bool>(),
+                    strictly_upper_triangular_matrix_capacity(vertex_count),
+                ),
+            )
+        })
+        .prop_map(|(vertex_count, bits)| {
+            let edges =
+                (0..vertex_count).flat_map(|u| ((u + 1)..vertex_count).map(move |v| (u, v)));
+            DirectedAcyclicGraph::from_edges_iter(
+                vertex_count,
+                edges
+                    .zip(bits)
+                    .filter(|(_, exists)| *exists)
+                    .map(|(edge, _)| edge),
+            )
+        })
+}
+
+/// DAGs along with a pair of vertices `u < v`.  Requires `vertex_counts` to
+/// start at 2 or more.
+fn dag_with_pair(
+    vertex_counts: Range<usize>,
+) -> impl Strategy<Value = (DirectedAcyclicGraph, usize, usize)> {
+    assert!(vertex_counts.start >= 2);
+    dag(vertex_counts)
+        .prop_flat_map(|dag| {
+            let vertex_count = dag.get_vertex_count();
+            (Just(dag), 0..(vertex_count - 1))
+        })
+        .prop_flat_map(|(dag, u)| {
+            let vertex_count = dag.get_vertex_count();
+            (Just(dag), Just(u), (u + 1)..vertex_count)
+        })
+}
+
+/// DAGs along with a pair of vertices `(u, v)` such that there's a path from
+/// `u` to `v`.  If the generated DAG has no such path, the edge `(u, v)` is
+/// added, so no samples get rejected.  Requires `vertex_counts` to start at 2
+/// or more.
+pub fn dag_with_comparable_pair(
+    vertex_counts: Range<usize>,
+) -> impl Strategy<Value = (DirectedAcyclicGraph, usize, usize)> {
+    dag_with_pair(vertex_counts).prop_map(|(mut dag, u, v)| {
+        if !get_descendants(&dag)[u].contains(v) {
+            dag.set_edge(u, v, true);
+        }
+        (dag, u, v)
+    })
+}
+
+/// DAGs along with a pair of vertices `u < v` such that there's no path from
+/// `u` to `v`.  Every edge that lies on a path from `u` to `v` in the generated
+/// DAG is removed, so no samples get rejected.  Requires `vertex_counts` to
+/// start at 2 or more.
+pub fn dag_with_incomparable_pair(
+    vertex_counts: Range<usize>,
+) -> impl Strategy<Value = (DirectedAcyclicGraph, usize, usize)> {
+    dag_with_pair(vertex_counts).prop_map(|(mut dag, u, v)| {
+        let descendants = get_descendants(&dag);
+        let reaches_v = |w: usize| w == v || descendants[w].contains(v);
+        let reached_from_u = |w: usize| w == u || descendants[u].contains(w);
+        let on_path: Vec<(usize, usize)> = dag
+            .iter_edges()
+            .filter(|&(a, b)| reached_from_u(a) && reaches_v(b))
+            .collect();
+        for (a, b) in on_path {
+            dag.set_edge(a, b, false);
+        }
+        (dag, u, v)
+    })
+}
+
+/// DAGs along with one of their topological orders, chosen at random.  A
+/// random priority is drawn for every vertex and [`get_topological_order_by`]
+/// always picks the available vertex with the lowest one, so the order
+/// shrinks together with the DAG, towards increasing vertex order.
+pub fn dag_with_topological_order(
+    vertex_counts: Range<usize>,
+) -> impl Strategy<Value = (DirectedAcyclicGraph, Vec<usize>)> {
+    dag(vertex_counts)
+        .prop_flat_map(|dag| {
+            let vertex_count = dag.get_vertex_count();
+            (
+                Just(dag),
+                proptest::collection::vec(any::<u32>(), vertex_count),
+            )
+        })
+        .prop_map(|(dag, priorities)| {
+            let order = get_topological_order_by(&dag, |u, v| priorities[*u].cmp(&priorities[*v]));
+            (dag, order)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn comparable_pair_is_comparable((dag, u, v) in dag_with_comparable_pair(2..12)) {
+            prop_assert!(get_descendants(&dag)[u].contains(v));
+        }
+
+        #[test]
+        fn incomparable_pair_is_incomparable((dag, u, v) in dag_with_incomparable_pair(2..12)) {
+            prop_assert!(u < v);
+            prop_assert!(!get_descendants(&dag)[u].contains(v));
+        }
+
+        #[test]
+        fn topological_order_is_topological((dag, order) in dag_with_topological_order(0..12)) {
+            let mut position: Vec<usize> = vec![usize::MAX; dag.get_vertex_count()];
+            for (i, &u) in order.iter().enumerate() {
+                position[u] = i;
+            }
+            prop_assert_eq!(order.len(), dag.get_vertex_count());
+            prop_assert!(position.iter().all(|&i| i != usize::MAX));
+            prop_assert!(dag.iter_edges().all(|(u, v)| position[u] < position[v]));
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use fixedbitset::FixedBitSet;
+
+use crate::strictly_upper_triangular_logical_matrix::{
+    iter_ones_in_range, strictly_upper_triangular_matrix_capacity,
+};
+use crate::StrictlyUpperTriangularLogicalMatrix;
+
+/// The transpose counterpart of [`StrictlyUpperTriangularLogicalMatrix`]: a
+/// zero-indexed row-major packed matrix of booleans with ones only at `(i, j)`
+/// positions where `i > j`.
+///
+/// Row `i` is stored in consecutive bits, so for the transposed adjacency
+/// matrix of a DAG, the parents of a vertex can be scanned as quickly as the
+/// children in the upper triangular one.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct StrictlyLowerTriangularLogicalMatrix {
+    size: usize,
+    matrix: FixedBitSet,
+}
+
+// Row `i` has `i` elements and is preceded by `0 + 1 + ... + (i - 1)` of them.
+#[inline]
+fn unchecked_get_index_from_row_column(i: usize, j: usize) -> usize {
+    i * (i - 1) / 2 + j
+}
+
+impl StrictlyLowerTriangularLogicalMatrix {
+    /// Panics if `size` is so big that the number of elements would overflow
+    /// `usize`.
+    pub fn zeroed(size: usize) -> Self {
+        let capacity = strictly_upper_triangular_matrix_capacity(size);
+        Self {
+            size,
+            matrix: FixedBitSet::with_capacity(capacity),
+        }
+    }
+
+    pub fn from_iter<I: Iterator<Item = (usize, usize)>>(size: usize, iter: I) -> Self {
+        let mut matrix = Self::zeroed(size);
+        for (i, j) in iter {
+            matrix.set(i, j, true);
+        }
+        matrix
+    }
+
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    #[inline]
+    fn index_from_row_column(&self, i: usize, j: usize) -> usize {
+        assert!(i < self.size);
+        assert!(j < self.size);
+        assert!(i > j);
+        unchecked_get_index_from_row_column(i, j)
+    }
+
+    pub fn get(&self, i: usize, j: usize) -> bool {
+        let index = self.index_from_row_column(i, j);
+        self.matrix[index]
+    }
+
+    /// Returns the previous value.
+    pub fn set(&mut self, i: usize, j: usize, value: bool) -> bool {
+        let index = self.index_from_row_column(i, j);
+        let current = self.matrix[index];
+        self.matrix.set(index, value);
+        current
+    }
+
+    /// Iterates over the `(i, j)` positions that are set, in the row-major
+    /// order.
+    pub fn iter_ones(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let mut i = 1;
+        let mut row_start = 0;
+        self.matrix.ones().map(move |index| {
+            while index >= row_start + i {
+                row_start += i;
+                i += 1;
+            }
+            (i, index - row_start)
+        })
+    }
+
+    /// Iterates over the `j`s such that `(i, j)` is set, in increasing order,
+    /// a word at a time.
+    pub fn iter_ones_at_row(&self, i: usize) -> impl Iterator<Item = usize> + '_ {
+        assert!(i < self.size);
+        let row_start = if i == 0 {
+            0
+        } else {
+            unchecked_get_index_from_row_column(i, 0)
+        };
+        iter_ones_in_range(&self.matrix, row_start..row_start + i)
+            .map(move |index| index - row_start)
+    }
+
+    /// The upper triangular matrix with `(j, i)` set for every `(i, j)` set
+    /// here.
+    pub fn transpose(&self) -> StrictlyUpperTriangularLogicalMatrix {
+        StrictlyUpperTriangularLogicalMatrix::from_iter(
+            self.size,
+            self.iter_ones().map(|(i, j)| (j, i)),
+        )
+    }
+}
+
+impl StrictlyUpperTriangularLogicalMatrix {
+    /// The lower triangular matrix with `(j, i)` set for every `(i, j)` set
+    /// here, e.g. the parents of every vertex rather than the children.
+    pub fn transpose(&self) -> StrictlyLowerTriangularLogicalMatrix {
+        StrictlyLowerTriangularLogicalMatrix::from_iter(
+            self.size(),
+            self.iter_ones().map(|(i, j)| (j, i)),
+        )
+    }
+}
+
+impl std::fmt::Debug for StrictlyLowerTriangularLogicalMatrix {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ones: Vec<(usize, usize)> = self.iter_ones().collect();
+        write!(
+            f,
+            "StrictlyLowerTriangularLogicalMatrix::from_iter({}, vec!{:?}.into_iter())",
+            self.size(),
+            ones
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transpose_roundtrips() {
+        for size in 0..20 {
+            let upper = StrictlyUpperTriangularLogicalMatrix::from_iter(
+                size,
+                (0..size)
+                    .flat_map(|i| ((i + 1)..size).map(move |j| (i, j)))
+                    .filter(|(i, j)| (i * 3 + j) % 4 != 1),
+            );
+            let lower = upper.transpose();
+            assert!(upper.iter_ones().all(|(i, j)| lower.get(j, i)));
+            assert_eq!(lower.iter_ones().count(), upper.iter_ones().count());
+            for j in 0..size {
+                assert!(lower
+                    .iter_ones_at_row(j)
+                    .eq((0..j).filter(|&i| upper.get(i, j))));
+            }
+            assert_eq!(lower.transpose(), upper);
+        }
+    }
+}
@@ -1,7 +1,31 @@
 use fixedbitset::FixedBitSet;
 
-const fn strictly_upper_triangular_matrix_capacity(n: usize) -> usize {
-    (n * n - n) / 2
+/// `(n * n - n) / 2` or `None` if it overflows.  Also `None` if the capacity
+/// exceeds `usize::MAX / 2` since index computations multiply by up to twice
+/// the capacity.
+// `usize::is_multiple_of` needs Rust 1.87.
+#[allow(clippy::manual_is_multiple_of)]
+pub(crate) const fn checked_strictly_upper_triangular_matrix_capacity(n: usize) -> Option<usize> {
+    // Divide the even factor first so that the product doesn't overflow
+    // prematurely.
+    let capacity = if n % 2 == 0 {
+        (n / 2).checked_mul(n.saturating_sub(1))
+    } else {
+        n.checked_mul((n - 1) / 2)
+    };
+    match capacity {
+        Some(capacity) if capacity <= usize::MAX / 2 => Some(capacity),
+        _ => None,
+    }
+}
+
+/// Panics if the capacity overflows.  See
+/// [`checked_strictly_upper_triangular_matrix_capacity`].
+pub(crate) const fn strictly_upper_triangular_matrix_capacity(n: usize) -> usize {
+    match checked_strictly_upper_triangular_matrix_capacity(n) {
+        Some(capacity) => capacity,
+        None => panic!("strictly upper triangular matrix size overflows usize"),
+    }
 }
 
 pub struct CacheFriendlyMatrixIterator {
@@ -11,17 +35,17 @@ pub struct CacheFriendlyMatrixIterator {
     index: usize,
 }
 
-impl<'a> Iterator for CacheFriendlyMatrixIterator {
+impl Iterator for CacheFriendlyMatrixIterator {
     type Item = (usize, usize, usize); // (i, j, bitset index)
 
     fn next(&mut self) -> Option<Self::Item> {
         let result = (self.i, self.j, self.index);
-        if self.j < self.size - 1 {
+        if self.j + 1 < self.size {
             self.j += 1;
             self.index += 1;
             return Some(result);
         }
-        if self.i < self.size - 1 {
+        if self.i + 1 < self.size {
             self.i += 1;
             self.j = self.i + 1;
             self.index += 1;
@@ -33,7 +57,11 @@ impl<'a> Iterator for CacheFriendlyMatrixIterator {
 
 pub fn iter_matrix_starting_at(i: usize, size: usize) -> CacheFriendlyMatrixIterator {
     let j = i + 1;
-    let index = unchecked_get_index_from_row_column(i, j, size);
+    let index = if size == 0 {
+        0
+    } else {
+        unchecked_get_index_from_row_column(i, j, size)
+    };
     CacheFriendlyMatrixIterator { size, i, j, index }
 }
 
@@ -44,6 +72,200 @@ pub fn iter_matrix(size: usize) -> CacheFriendlyMatrixIterator {
     iter_matrix_starting_at(0, size)
 }
 
+/// An error returned by the fallible (`try_*`) accessors instead of panicking.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IndexError {
+    /// `index` is not smaller than `size`.
+    OutOfBounds { index: usize, size: usize },
+    /// `(i, j)` is not above the diagonal, i.e. `i < j` doesn't hold.
+    NotStrictlyUpper { i: usize, j: usize },
+}
+
+impl std::fmt::Display for IndexError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IndexError::OutOfBounds { index, size } => {
+                write!(f, "index {} out of bounds for size {}", index, size)
+            }
+            IndexError::NotStrictlyUpper { i, j } => {
+                write!(f, "({}, {}) is not strictly upper triangular", i, j)
+            }
+        }
+    }
+}
+
+impl std::error::Error for IndexError {}
+
+/// An error returned by [`StrictlyUpperTriangularLogicalMatrix::try_from_ones`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FromOnesError {
+    /// `pair` is not a valid position in the matrix.
+    InvalidPair {
+        pair: (usize, usize),
+        error: IndexError,
+    },
+    /// `pair` occurs more than once.
+    Duplicate { pair: (usize, usize) },
+    /// A matrix of `size` rows would be too big to be addressable (see
+    /// [`StrictlyUpperTriangularLogicalMatrix::try_zeroed`]).
+    CapacityOverflow { size: usize },
+}
+
+impl std::fmt::Display for FromOnesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FromOnesError::InvalidPair { pair, error } => {
+                write!(f, "invalid pair {:?}: {}", pair, error)
+            }
+            FromOnesError::Duplicate { pair } => write!(f, "duplicate pair {:?}", pair),
+            FromOnesError::CapacityOverflow { size } => {
+                write!(f, "a matrix of size {} is too big", size)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FromOnesError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FromOnesError::InvalidPair { error, .. } => Some(error),
+            FromOnesError::Duplicate { .. } | FromOnesError::CapacityOverflow { .. } => None,
+        }
+    }
+}
+
+/// An error returned by [`StrictlyUpperTriangularLogicalMatrix::from_dense`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FromDenseError {
+    /// Row `row` has `len` elements instead of as many as there are rows.
+    NotSquare { row: usize, len: usize },
+    /// `(i, j)` is set but it's not above the diagonal.
+    NotStrictlyUpper { i: usize, j: usize },
+}
+
+impl std::fmt::Display for FromDenseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FromDenseError::NotSquare { row, len } => {
+                write!(f, "row {} has {} elements, matrix is not square", row, len)
+            }
+            FromDenseError::NotStrictlyUpper { i, j } => {
+                write!(f, "({}, {}) is set but not strictly upper triangular", i, j)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FromDenseError {}
+
+/// An error returned by [`StrictlyUpperTriangularLogicalMatrix::validate`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InvariantViolation {
+    /// The backing bit set holds `len` bits instead of the `expected`
+    /// `(size * size - size) / 2`.
+    WrongLength { expected: usize, len: usize },
+    /// Block `block` (see [`StrictlyUpperTriangularLogicalMatrix::as_blocks`])
+    /// has bits set past the last row.
+    BitsPastEnd { block: usize },
+    /// The number of elements of a matrix of `size` rows overflows `usize`,
+    /// so no bit set can back it.
+    SizeOverflow { size: usize },
+}
+
+impl std::fmt::Display for InvariantViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InvariantViolation::WrongLength { expected, len } => {
+                write!(f, "bit set has {} bits instead of {}", len, expected)
+            }
+            InvariantViolation::BitsPastEnd { block } => {
+                write!(f, "block {} has bits set past the last row", block)
+            }
+            InvariantViolation::SizeOverflow { size } => {
+                write!(f, "a matrix of size {} is too big", size)
+            }
+        }
+    }
+}
+
+impl std::error::Error for InvariantViolation {}
+
+/// A range of consecutive rows of a [`StrictlyUpperTriangularLogicalMatrix`].
+/// See [`StrictlyUpperTriangularLogicalMatrix::iter_row_chunks`].
+#[derive(Clone, Copy, Debug)]
+pub struct RowChunk<'a> {
+    matrix: &'a StrictlyUpperTriangularLogicalMatrix,
+    start: usize,
+    end: usize,
+}
+
+impl<'a> RowChunk<'a> {
+    pub fn get_rows(&self) -> std::ops::Range<usize> {
+        self.start..self.end
+    }
+
+    /// The range of bit indices holding the rows of the chunk.  See
+    /// [`StrictlyUpperTriangularLogicalMatrix::get_row_bits`].
+    pub fn get_bits(&self) -> std::ops::Range<usize> {
+        self.matrix.get_row_bits(self.start).start..self.matrix.get_row_bits(self.end - 1).end
+    }
+
+    /// The blocks overlapping the chunk along with the index of the first bit
+    /// of the chunk within the first block.  The first and the last block may
+    /// be shared with the neighbouring chunks.
+    pub fn get_blocks(&self) -> (&'a [u32], usize) {
+        const BLOCK_BITS: usize = u32::BITS as usize;
+        let bits = self.get_bits();
+        let first_block = bits.start / BLOCK_BITS;
+        let blocks = &self.matrix.as_blocks()[first_block..bits.end.div_ceil(BLOCK_BITS)];
+        (blocks, bits.start % BLOCK_BITS)
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.matrix.matrix.count_ones(self.get_bits())
+    }
+
+    /// Iterates over the `(i, j)` positions within the chunk that are set, in
+    /// the row-major order.
+    pub fn iter_ones(&self) -> impl Iterator<Item = (usize, usize)> + 'a {
+        let matrix = self.matrix;
+        (self.start..self.end).flat_map(move |i| matrix.iter_ones_at_row(i).map(move |j| (i, j)))
+    }
+}
+
+/// See [`StrictlyUpperTriangularLogicalMatrix::iter_row_chunks`].
+#[derive(Clone, Debug)]
+pub struct RowChunksIterator<'a> {
+    matrix: &'a StrictlyUpperTriangularLogicalMatrix,
+    next_row: usize,
+    bits_per_chunk: usize,
+}
+
+impl<'a> Iterator for RowChunksIterator<'a> {
+    type Item = RowChunk<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let size = self.matrix.size;
+        if self.next_row >= size {
+            return None;
+        }
+        let start = self.next_row;
+        let mut bits = 0;
+        // The last row is always empty, so it's never a chunk on its own.
+        while self.next_row < size
+            && (bits < self.bits_per_chunk || self.next_row == start || self.next_row == size - 1)
+        {
+            bits += size - self.next_row - 1;
+            self.next_row += 1;
+        }
+        Some(RowChunk {
+            matrix: self.matrix,
+            start,
+            end: self.next_row,
+        })
+    }
+}
+
 /// A zero-indexed [row-major
 /// packed](https://www.intel.com/content/www/us/en/develop/documentation/onemkl-developer-reference-c/top/lapack-routines/matrix-storage-schemes-for-lapack-routines.html)
 /// matrix of booleans.
@@ -56,11 +278,50 @@ pub struct StrictlyUpperTriangularLogicalMatrix {
 // Reference: https://www.intel.com/content/www/us/en/develop/documentation/onemkl-developer-reference-c/top/lapack-routines/matrix-storage-schemes-for-lapack-routines.html
 // Formulas adjusted for indexing from zero.
 #[inline]
-fn unchecked_get_index_from_row_column(i: usize, j: usize, size: usize) -> usize {
+pub(crate) fn unchecked_get_index_from_row_column(i: usize, j: usize, size: usize) -> usize {
     ((2 * size - i - 1) * i) / 2 + j - i - 1
 }
 
+/// Iterates over the set bits of `bits` within `range`, in increasing order.
+/// Works 32 bits at a time, so runs of zeros are skipped cheaply.  Requires
+/// `range.end <= bits.len()`.
+pub(crate) fn iter_ones_in_range(
+    bits: &FixedBitSet,
+    range: std::ops::Range<usize>,
+) -> impl Iterator<Item = usize> + '_ {
+    const BLOCK_BITS: usize = u32::BITS as usize;
+    assert!(range.end <= bits.len());
+    let std::ops::Range { start, end } = range;
+    let blocks = bits.as_slice();
+    let first_block = start / BLOCK_BITS;
+    let last_block = if start < end {
+        (end - 1) / BLOCK_BITS + 1
+    } else {
+        first_block
+    };
+    (first_block..last_block).flat_map(move |block| {
+        let block_start = block * BLOCK_BITS;
+        let mut word = blocks[block];
+        if start > block_start {
+            word &= u32::MAX << (start - block_start);
+        }
+        if end - block_start < BLOCK_BITS {
+            word &= (1 << (end - block_start)) - 1;
+        }
+        std::iter::from_fn(move || {
+            if word == 0 {
+                return None;
+            }
+            let bit = word.trailing_zeros() as usize;
+            word &= word - 1;
+            Some(block_start + bit)
+        })
+    })
+}
+
 impl StrictlyUpperTriangularLogicalMatrix {
+    /// Panics if `size` is so big that the number of elements would overflow
+    /// `usize`.  See [`Self::try_zeroed`] for a non-panicking variant.
     pub fn zeroed(size: usize) -> Self {
         let capacity = strictly_upper_triangular_matrix_capacity(size);
         Self {
@@ -69,6 +330,17 @@ impl StrictlyUpperTriangularLogicalMatrix {
         }
     }
 
+    /// Same as [`Self::zeroed`] but returns `None` instead of panicking if the
+    /// number of elements would overflow `usize`, e.g. for sizes above 2^16 on
+    /// 32-bit targets.
+    pub fn try_zeroed(size: usize) -> Option<Self> {
+        let capacity = checked_strictly_upper_triangular_matrix_capacity(size)?;
+        Some(Self {
+            size,
+            matrix: FixedBitSet::with_capacity(capacity),
+        })
+    }
+
     pub fn from_iter<I: Iterator<Item = (usize, usize)>>(size: usize, iter: I) -> Self {
         let mut matrix = Self::zeroed(size);
         for (i, j) in iter {
@@ -77,6 +349,58 @@ impl StrictlyUpperTriangularLogicalMatrix {
         matrix
     }
 
+    /// Same as [`Self::from_iter`] but returns an error identifying the first
+    /// offending pair instead of panicking, e.g. when the pairs come from
+    /// untrusted input.  Unlike [`Self::from_iter`], duplicate pairs are
+    /// rejected too.
+    pub fn try_from_ones<I: IntoIterator<Item = (usize, usize)>>(
+        size: usize,
+        ones: I,
+    ) -> Result<Self, FromOnesError> {
+        let mut matrix = Self::try_zeroed(size).ok_or(FromOnesError::CapacityOverflow { size })?;
+        for pair in ones {
+            let index = matrix
+                .try_index_from_row_column(pair.0, pair.1)
+                .map_err(|error| FromOnesError::InvalidPair { pair, error })?;
+            if matrix.matrix.put(index) {
+                return Err(FromOnesError::Duplicate { pair });
+            }
+        }
+        Ok(matrix)
+    }
+
+    /// Converts from a dense square matrix given as rows, e.g. a test fixture.
+    /// Only the part above the diagonal may have `true`s.
+    pub fn from_dense(rows: &[Vec<bool>]) -> Result<Self, FromDenseError> {
+        let size = rows.len();
+        let mut matrix = Self::zeroed(size);
+        for (i, row) in rows.iter().enumerate() {
+            if row.len() != size {
+                return Err(FromDenseError::NotSquare {
+                    row: i,
+                    len: row.len(),
+                });
+            }
+            for (j, &value) in row.iter().enumerate().filter(|(_, value)| **value) {
+                if i >= j {
+                    return Err(FromDenseError::NotStrictlyUpper { i, j });
+                }
+                matrix.set(i, j, value);
+            }
+        }
+        Ok(matrix)
+    }
+
+    /// Converts to a dense square matrix given as rows.  See
+    /// [`Self::from_dense`].
+    pub fn to_dense(&self) -> Vec<Vec<bool>> {
+        let mut rows: Vec<Vec<bool>> = vec![vec![false; self.size]; self.size];
+        for (i, j) in self.iter_ones() {
+            rows[i][j] = true;
+        }
+        rows
+    }
+
     #[inline]
     pub fn size(&self) -> usize {
         self.size
@@ -90,6 +414,26 @@ impl StrictlyUpperTriangularLogicalMatrix {
         unchecked_get_index_from_row_column(i, j, self.size)
     }
 
+    fn try_index_from_row_column(&self, i: usize, j: usize) -> Result<usize, IndexError> {
+        self.check_index(i)?;
+        self.check_index(j)?;
+        if i >= j {
+            return Err(IndexError::NotStrictlyUpper { i, j });
+        }
+        Ok(unchecked_get_index_from_row_column(i, j, self.size))
+    }
+
+    #[inline]
+    pub(crate) fn check_index(&self, index: usize) -> Result<(), IndexError> {
+        if index >= self.size {
+            return Err(IndexError::OutOfBounds {
+                index,
+                size: self.size,
+            });
+        }
+        Ok(())
+    }
+
     pub fn get(&self, i: usize, j: usize) -> bool {
         let index = self.index_from_row_column(i, j);
         self.matrix[index]
@@ -103,22 +447,499 @@ impl StrictlyUpperTriangularLogicalMatrix {
         current
     }
 
+    /// Same as [`Self::get`] but without the bounds and triangularity checks in
+    /// release builds.
+    ///
+    /// # Safety
+    ///
+    /// Requires `i < j < self.size()`.
+    #[inline]
+    pub unsafe fn get_unchecked(&self, i: usize, j: usize) -> bool {
+        debug_assert!(i < j && j < self.size);
+        let index = unchecked_get_index_from_row_column(i, j, self.size);
+        let block = *self
+            .matrix
+            .as_slice()
+            .get_unchecked(index / u32::BITS as usize);
+        (block >> (index % u32::BITS as usize)) & 1 == 1
+    }
+
+    /// Same as [`Self::set`] but without the bounds and triangularity checks in
+    /// release builds and without returning the previous value.
+    ///
+    /// # Safety
+    ///
+    /// Requires `i < j < self.size()`.
+    #[inline]
+    pub unsafe fn set_unchecked(&mut self, i: usize, j: usize, value: bool) {
+        debug_assert!(i < j && j < self.size);
+        let index = unchecked_get_index_from_row_column(i, j, self.size);
+        let block = self
+            .matrix
+            .as_mut_slice()
+            .get_unchecked_mut(index / u32::BITS as usize);
+        let mask = 1 << (index % u32::BITS as usize);
+        if value {
+            *block |= mask;
+        } else {
+            *block &= !mask;
+        }
+    }
+
+    /// Flips the value in place.  Returns the new value.
+    pub fn toggle(&mut self, i: usize, j: usize) -> bool {
+        let index = self.index_from_row_column(i, j);
+        self.matrix.toggle(index);
+        self.matrix[index]
+    }
+
+    /// Sets the value to `true`.  Returns whether it was `false` before, i.e.
+    /// whether anything changed.
+    pub fn set_if_unset(&mut self, i: usize, j: usize) -> bool {
+        let index = self.index_from_row_column(i, j);
+        !self.matrix.put(index)
+    }
+
+    /// Sets the value to `false`.  Returns whether it was `true` before, i.e.
+    /// whether anything changed.
+    pub fn unset_if_set(&mut self, i: usize, j: usize) -> bool {
+        let index = self.index_from_row_column(i, j);
+        let current = self.matrix[index];
+        self.matrix.set(index, false);
+        current
+    }
+
+    /// Sets every value to `false`.
+    pub fn clear(&mut self) {
+        self.matrix.clear();
+    }
+
+    /// Sets every value above the diagonal to `true`.
+    pub fn fill(&mut self) {
+        self.matrix.insert_range(..);
+    }
+
+    /// The matrix with every value above the diagonal negated.
+    pub fn complement(&self) -> Self {
+        let mut result = self.clone();
+        result.matrix.toggle_range(..);
+        result
+    }
+
+    /// Same as [`Self::get`] but returns an error instead of panicking.
+    pub fn try_get(&self, i: usize, j: usize) -> Result<bool, IndexError> {
+        let index = self.try_index_from_row_column(i, j)?;
+        Ok(self.matrix[index])
+    }
+
+    /// Same as [`Self::set`] but returns an error instead of panicking.
+    pub fn try_set(&mut self, i: usize, j: usize, value: bool) -> Result<bool, IndexError> {
+        let index = self.try_index_from_row_column(i, j)?;
+        let current = self.matrix[index];
+        self.matrix.set(index, value);
+        Ok(current)
+    }
+
+    /// Iterates over the `(i, j)` positions that are set, in the row-major
+    /// order.  Empty words of the underlying bit set are skipped, so this is
+    /// cheap for sparse matrices.
     pub fn iter_ones(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let size = self.size;
+        let mut i = 0;
+        let mut row_start = 0;
+        self.matrix.ones().map(move |index| {
+            // Rows are stored consecutively, so indices grow with rows.
+            while index >= row_start + (size - i - 1) {
+                row_start += size - i - 1;
+                i += 1;
+            }
+            (i, i + 1 + index - row_start)
+        })
+    }
+
+    /// The number of positions that are set, counted a block at a time.
+    pub fn count_ones(&self) -> usize {
+        self.matrix.count_ones(..)
+    }
+
+    /// Same as [`Self::iter_ones`] but in the column-major order, i.e. sorted
+    /// by `j` first and `i` second.  The columns are the rows of the
+    /// [transpose](Self::transpose), so they're scanned a word at a time
+    /// there, at the cost of allocating it.
+    pub fn iter_ones_by_target(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let ones: Vec<(usize, usize)> = self.transpose().iter_ones().map(|(j, i)| (i, j)).collect();
+        ones.into_iter()
+    }
+
+    /// Iterates over the `(i, j)` positions with `i < j` that are *not* set.
+    pub fn iter_zeros(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
         iter_matrix(self.size()).filter_map(move |(i, j, index)| {
             if self.matrix[index] {
-                Some((i, j))
-            } else {
                 None
+            } else {
+                Some((i, j))
             }
         })
     }
 
+    /// Iterates over the `i`s such that `(i, j)` is set, in increasing order.
+    /// Column `j` is spread over `j` rows, so this takes a bit per row.  To
+    /// scan many columns, go over the rows of the [transpose](Self::transpose)
+    /// instead.
+    pub fn iter_ones_at_column(&self, j: usize) -> impl Iterator<Item = usize> + '_ {
+        assert!(j < self.size());
+        let size = self.size;
+        // Row `i + 1` starts one bit closer to the diagonal than row `i`, so
+        // `(i + 1, j)` is `size - i - 2` bits past `(i, j)`.
+        let mut index = j.wrapping_sub(1);
+        (0..j).filter(move |i| {
+            let is_set = self.matrix[index];
+            index += size - i - 2;
+            is_set
+        })
+    }
+
     pub fn iter_ones_at_row(&self, i: usize) -> impl Iterator<Item = usize> + '_ {
         assert!(i < self.size());
-        iter_matrix_starting_at(i, self.size())
-            .take_while(move |(ii, _, _)| *ii == i)
-            .filter(move |(_, _, index)| self.matrix[*index])
-            .map(move |(_, jj, _)| jj)
+        let row_start = unchecked_get_index_from_row_column(i, i + 1, self.size);
+        let row_len = self.size - i - 1;
+        iter_ones_in_range(&self.matrix, row_start..row_start + row_len)
+            .map(move |index| i + 1 + index - row_start)
+    }
+}
+
+impl StrictlyUpperTriangularLogicalMatrix {
+    /// ORs row `i` into `target`, so that `target[j]` gets set for every `j`
+    /// such that `(i, j)` is set.  Works 32 bits at a time.  Requires
+    /// `target.len() >= self.size()`.
+    pub(crate) fn union_row_into(&self, i: usize, target: &mut FixedBitSet) {
+        const BLOCK_BITS: usize = u32::BITS as usize;
+        assert!(i < self.size);
+        assert!(target.len() >= self.size);
+        let row_len = self.size - i - 1;
+        if row_len == 0 {
+            return;
+        }
+        let row_start = unchecked_get_index_from_row_column(i, i + 1, self.size);
+        let blocks = self.matrix.as_slice();
+        let target_blocks = target.as_mut_slice();
+        for k in (0..row_len).step_by(BLOCK_BITS) {
+            let source = row_start + k;
+            let (block, shift) = (source / BLOCK_BITS, source % BLOCK_BITS);
+            let mut word = blocks[block] >> shift;
+            if shift > 0 && block + 1 < blocks.len() {
+                word |= blocks[block + 1] << (BLOCK_BITS - shift);
+            }
+            let bit_count = BLOCK_BITS.min(row_len - k);
+            if bit_count < BLOCK_BITS {
+                word &= (1 << bit_count) - 1;
+            }
+            let destination = i + 1 + k;
+            let (block, shift) = (destination / BLOCK_BITS, destination % BLOCK_BITS);
+            target_blocks[block] |= word << shift;
+            if shift > 0 && word >> (BLOCK_BITS - shift) != 0 {
+                target_blocks[block + 1] |= word >> (BLOCK_BITS - shift);
+            }
+        }
+    }
+
+    /// The boolean matrix product `self * other`: `(i, j)` is set iff there's
+    /// a `k` such that both `self[(i, k)]` and `other[(k, j)]` are set, e.g.
+    /// the pairs connected by a path of exactly two edges for `a.boolean_mul(&a)`.
+    /// The product of strictly upper triangular matrices is strictly upper
+    /// triangular too.  Computed by ORing whole rows of `other`, 32 bits at a
+    /// time.
+    ///
+    /// Requires both matrices to be of the same size.  Panics otherwise.
+    pub fn boolean_mul(&self, other: &Self) -> Self {
+        assert_eq!(self.size, other.size);
+        let mut result = Self::zeroed(self.size);
+        let mut row = FixedBitSet::with_capacity(self.size);
+        for i in 0..self.size {
+            row.clear();
+            for k in self.iter_ones_at_row(i) {
+                other.union_row_into(k, &mut row);
+            }
+            for j in row.ones() {
+                result
+                    .matrix
+                    .insert(unchecked_get_index_from_row_column(i, j, self.size));
+            }
+        }
+        result
+    }
+
+    /// Sets every `(i, j)` set in `other`.  Word-wise.  Requires both
+    /// matrices to be of the same size.  Panics otherwise.
+    pub fn union_with(&mut self, other: &Self) {
+        assert_eq!(self.size, other.size);
+        self.matrix.union_with(&other.matrix);
+    }
+
+    /// Unsets every `(i, j)` not set in `other`.  Word-wise.  Requires both
+    /// matrices to be of the same size.  Panics otherwise.
+    pub fn intersect_with(&mut self, other: &Self) {
+        assert_eq!(self.size, other.size);
+        self.matrix.intersect_with(&other.matrix);
+    }
+
+    /// Unsets every `(i, j)` set in `other`.  Word-wise.  Requires both
+    /// matrices to be of the same size.  Panics otherwise.
+    pub fn difference_with(&mut self, other: &Self) {
+        assert_eq!(self.size, other.size);
+        self.matrix.difference_with(&other.matrix);
+    }
+
+    /// Toggles every `(i, j)` set in `other`.  Word-wise.  Requires both
+    /// matrices to be of the same size.  Panics otherwise.
+    pub fn symmetric_difference_with(&mut self, other: &Self) {
+        assert_eq!(self.size, other.size);
+        self.matrix.symmetric_difference_with(&other.matrix);
+    }
+
+    /// The principal submatrix on `indices`: `(a, b)` of the result is
+    /// `(indices[a], indices[b])` of `self`.  Requires `indices` to be strictly
+    /// increasing, so that the result stays strictly upper triangular.  Panics
+    /// otherwise.
+    pub fn submatrix(&self, indices: &[usize]) -> Self {
+        assert!(indices.windows(2).all(|pair| pair[0] < pair[1]));
+        if let Some(&last) = indices.last() {
+            self.check_index(last).unwrap();
+        }
+        let mut result = Self::zeroed(indices.len());
+        for (a, &i) in indices.iter().enumerate() {
+            for (b, &j) in indices.iter().enumerate().skip(a + 1) {
+                if self.matrix[unchecked_get_index_from_row_column(i, j, self.size)] {
+                    result
+                        .matrix
+                        .insert(unchecked_get_index_from_row_column(a, b, result.size));
+                }
+            }
+        }
+        result
+    }
+
+    /// Changes the size of the matrix in place.  Growing adds all-zero rows
+    /// and columns and shrinking drops the ones at indices `new_size` and up.
+    /// Rows are moved within the existing bit set, which is only reallocated
+    /// when growing past its allocation or when truncated after shrinking.
+    pub fn resize(&mut self, new_size: usize) {
+        let old_size = self.size;
+        let new_capacity = strictly_upper_triangular_matrix_capacity(new_size);
+        let move_bit = |matrix: &mut FixedBitSet, from: usize, to: usize| {
+            if from != to && matrix[from] {
+                matrix.set(from, false);
+                matrix.insert(to);
+            }
+        };
+        if new_size > old_size {
+            self.matrix.grow(new_capacity);
+            // Rows only move towards the end, so start from the last one.
+            for i in (0..old_size).rev() {
+                let old_start = unchecked_get_index_from_row_column(i, i + 1, old_size);
+                let new_start = unchecked_get_index_from_row_column(i, i + 1, new_size);
+                for k in (0..(old_size - i - 1)).rev() {
+                    move_bit(&mut self.matrix, old_start + k, new_start + k);
+                }
+            }
+        } else if new_size < old_size {
+            // Rows only move towards the beginning, so start from the first one.
+            for i in 0..new_size {
+                let old_start = unchecked_get_index_from_row_column(i, i + 1, old_size);
+                let new_start = unchecked_get_index_from_row_column(i, i + 1, new_size);
+                // Drop the columns past the new size before moving the rest.
+                self.matrix.set_range(
+                    (old_start + new_size - i - 1)..(old_start + old_size - i - 1),
+                    false,
+                );
+                for k in 0..(new_size - i - 1) {
+                    move_bit(&mut self.matrix, old_start + k, new_start + k);
+                }
+            }
+            let block_count = new_capacity.div_ceil(u32::BITS as usize);
+            self.matrix = FixedBitSet::with_capacity_and_blocks(
+                new_capacity,
+                self.matrix.as_slice()[..block_count].iter().copied(),
+            );
+        }
+        self.size = new_size;
+    }
+
+    /// The raw 32-bit blocks of the backing bit set, e.g. for SIMD processing
+    /// or hashing.  Bit `b` of the whole matrix is bit `b % 32` of block `b /
+    /// 32`, rows are stored one after another (see [`Self::get_row_bits`]) and
+    /// the bits past the last row are always zero.
+    pub fn as_blocks(&self) -> &[u32] {
+        self.matrix.as_slice()
+    }
+
+    /// Gives `f` mutable access to the raw blocks (see [`Self::as_blocks`]).
+    /// Any bits set past the last row are cleared afterwards, so the matrix
+    /// stays valid no matter what `f` does.
+    pub fn with_blocks_mut<F: FnOnce(&mut [u32])>(&mut self, f: F) {
+        const BLOCK_BITS: usize = u32::BITS as usize;
+        let remaining_bits = self.matrix.len() % BLOCK_BITS;
+        let blocks = self.matrix.as_mut_slice();
+        f(blocks);
+        if remaining_bits > 0 {
+            *blocks.last_mut().unwrap() &= (1 << remaining_bits) - 1;
+        }
+    }
+
+    /// The range of bit indices (see [`Self::as_blocks`]) holding row `i`,
+    /// i.e. `(i, i + 1)..(i, size)`.  Rows aren't aligned to blocks.
+    pub fn get_row_bits(&self, i: usize) -> std::ops::Range<usize> {
+        assert!(i < self.size);
+        let row_start = if i + 1 < self.size {
+            unchecked_get_index_from_row_column(i, i + 1, self.size)
+        } else {
+            self.matrix.len()
+        };
+        row_start..(row_start + self.size - i - 1)
+    }
+
+    // The `(i, j)` position stored at bit `index`.
+    pub(crate) fn get_row_column_from_index(&self, index: usize) -> (usize, usize) {
+        assert!(index < self.matrix.len());
+        // Binary search for the last row starting at or before `index`.  Row
+        // `i` starts at the bit of `(i, i + 1)`.
+        let (mut low, mut high) = (0, self.size - 1);
+        while high - low > 1 {
+            let middle = low + (high - low) / 2;
+            if unchecked_get_index_from_row_column(middle, middle + 1, self.size) <= index {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        let i = low;
+        let row_start = unchecked_get_index_from_row_column(i, i + 1, self.size);
+        (i, i + 1 + index - row_start)
+    }
+
+    // The `(i, j)` position of the `n`-th one in the row-major order, counting
+    // the ones a block at a time.
+    pub(crate) fn get_nth_one(&self, mut n: usize) -> Option<(usize, usize)> {
+        const BLOCK_BITS: usize = u32::BITS as usize;
+        for (b, block) in self.iter_meaningful_blocks().enumerate() {
+            let ones = block.count_ones() as usize;
+            if n >= ones {
+                n -= ones;
+                continue;
+            }
+            let mut block = block;
+            for _ in 0..n {
+                block &= block - 1;
+            }
+            let index = b * BLOCK_BITS + block.trailing_zeros() as usize;
+            return Some(self.get_row_column_from_index(index));
+        }
+        None
+    }
+
+    /// Splits the rows into at most `chunk_count` ranges of consecutive rows
+    /// with roughly the same number of elements each, e.g. to be processed
+    /// independently on separate threads.  Rows get shorter towards the
+    /// end, so the later chunks span more of them.
+    pub fn iter_row_chunks(&self, chunk_count: usize) -> RowChunksIterator<'_> {
+        assert!(chunk_count > 0);
+        let capacity = strictly_upper_triangular_matrix_capacity(self.size);
+        RowChunksIterator {
+            matrix: self,
+            next_row: 0,
+            bits_per_chunk: capacity.div_ceil(chunk_count),
+        }
+    }
+
+    /// Checks the internal consistency of the matrix: the backing bit set has
+    /// exactly one bit per element above the diagonal and no bits set past
+    /// them.  There are no cached counters to check.
+    ///
+    /// Both always hold for matrices built with safe code, so this is meant
+    /// for asserting soundness after going through `unsafe` or raw access
+    /// paths.
+    pub fn validate(&self) -> Result<(), InvariantViolation> {
+        const BLOCK_BITS: usize = u32::BITS as usize;
+        let expected = checked_strictly_upper_triangular_matrix_capacity(self.size)
+            .ok_or(InvariantViolation::SizeOverflow { size: self.size })?;
+        if self.matrix.len() != expected {
+            return Err(InvariantViolation::WrongLength {
+                expected,
+                len: self.matrix.len(),
+            });
+        }
+        let blocks = self.matrix.as_slice();
+        let remaining_bits = expected % BLOCK_BITS;
+        if remaining_bits > 0 && blocks[blocks.len() - 1] >> remaining_bits != 0 {
+            return Err(InvariantViolation::BitsPastEnd {
+                block: blocks.len() - 1,
+            });
+        }
+        Ok(())
+    }
+
+    // The blocks of the backing bit set with any bits past the capacity of the
+    // matrix masked out.
+    fn iter_meaningful_blocks(&self) -> impl Iterator<Item = u32> + '_ {
+        const BLOCK_BITS: usize = u32::BITS as usize;
+        let capacity = strictly_upper_triangular_matrix_capacity(self.size);
+        let blocks = self.matrix.as_slice();
+        let full_blocks = capacity / BLOCK_BITS;
+        let remaining_bits = capacity % BLOCK_BITS;
+        let last_block = if remaining_bits > 0 {
+            Some(blocks[full_blocks] & ((1 << remaining_bits) - 1))
+        } else {
+            None
+        };
+        blocks[..full_blocks].iter().copied().chain(last_block)
+    }
+}
+
+impl std::fmt::Debug for StrictlyUpperTriangularLogicalMatrix {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ones: Vec<(usize, usize)> = self.iter_ones().collect();
+        write!(
+            f,
+            "StrictlyUpperTriangularLogicalMatrix::from_iter({}, vec!{:?}.into_iter())",
+            self.size(),
+            ones
+        )?;
+        Ok(())
+    }
+}
+
+/// Two matrices are equal iff they are of the same size and have ones at the
+/// same positions.
+impl PartialEq for StrictlyUpperTriangularLogicalMatrix {
+    fn eq(&self, other: &Self) -> bool {
+        self.size == other.size
+            && self
+                .iter_meaningful_blocks()
+                .eq(other.iter_meaningful_blocks())
+    }
+}
+
+impl Eq for StrictlyUpperTriangularLogicalMatrix {}
+
+impl std::hash::Hash for StrictlyUpperTriangularLogicalMatrix {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.size.hash(state);
+        for block in self.iter_meaningful_blocks() {
+            block.hash(state);
+        }
+    }
+}
+
+impl std::ops::Index<(usize, usize)> for StrictlyUpperTriangularLogicalMatrix {
+    type Output = bool;
+
+    /// Same as [`StrictlyUpperTriangularLogicalMatrix::get`].
+    fn index(&self, (i, j): (usize, usize)) -> &Self::Output {
+        if self.get(i, j) {
+            &true
+        } else {
+            &false
+        }
     }
 }
 
@@ -129,13 +950,17 @@ mod tests {
     #[test]
     fn positive_test_3x3_matrix() {
         let mut matrix = StrictlyUpperTriangularLogicalMatrix::zeroed(3);
-        assert_eq!(matrix.get(0, 1), false);
+        assert!(!matrix.get(0, 1));
         let ones: Vec<(usize, usize)> = matrix.iter_ones().collect();
         assert_eq!(ones, vec![]);
 
         matrix.set(0, 1, true);
         let ones: Vec<(usize, usize)> = matrix.iter_ones().collect();
         assert_eq!(ones, vec![(0, 1)]);
+        assert!(matrix[(0, 1)]);
+        assert!(!matrix[(0, 2)]);
+        let zeros: Vec<(usize, usize)> = matrix.iter_zeros().collect();
+        assert_eq!(zeros, vec![(0, 2), (1, 2)]);
     }
 
     #[test]
@@ -174,5 +999,414 @@ mod tests {
                 (2, 3, 5),
             ]
         );
+
+        assert_eq!(iter_matrix(0).next(), None);
+        assert_eq!(iter_matrix(1).next(), None);
+    }
+
+    #[test]
+    fn fallible_accessors() {
+        let mut matrix = StrictlyUpperTriangularLogicalMatrix::zeroed(3);
+        assert_eq!(matrix.try_set(0, 2, true), Ok(false));
+        assert_eq!(matrix.try_get(0, 2), Ok(true));
+        assert_eq!(
+            matrix.try_get(0, 3),
+            Err(IndexError::OutOfBounds { index: 3, size: 3 })
+        );
+        assert_eq!(
+            matrix.try_set(2, 1, true),
+            Err(IndexError::NotStrictlyUpper { i: 2, j: 1 })
+        );
+        assert_eq!(
+            matrix.try_get(1, 1),
+            Err(IndexError::NotStrictlyUpper { i: 1, j: 1 })
+        );
+    }
+
+    #[test]
+    fn equality_and_hashing() {
+        use std::collections::HashSet;
+
+        let left =
+            StrictlyUpperTriangularLogicalMatrix::from_iter(40, vec![(0, 1), (5, 39)].into_iter());
+        let mut right =
+            StrictlyUpperTriangularLogicalMatrix::from_iter(40, vec![(5, 39)].into_iter());
+        assert_ne!(left, right);
+        right.set(0, 1, true);
+        assert_eq!(left, right);
+        assert_ne!(left, StrictlyUpperTriangularLogicalMatrix::zeroed(40));
+        assert_ne!(
+            StrictlyUpperTriangularLogicalMatrix::zeroed(2),
+            StrictlyUpperTriangularLogicalMatrix::zeroed(3)
+        );
+
+        let set: HashSet<StrictlyUpperTriangularLogicalMatrix> =
+            vec![left, right].into_iter().collect();
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    fn debug_lists_ones() {
+        let matrix =
+            StrictlyUpperTriangularLogicalMatrix::from_iter(3, vec![(0, 2), (1, 2)].into_iter());
+        assert_eq!(
+            format!("{:?}", matrix),
+            "StrictlyUpperTriangularLogicalMatrix::from_iter(3, vec![(0, 2), (1, 2)].into_iter())"
+        );
+    }
+
+    #[test]
+    fn clear_and_fill() {
+        let mut matrix =
+            StrictlyUpperTriangularLogicalMatrix::from_iter(4, vec![(1, 3)].into_iter());
+        matrix.fill();
+        assert_eq!(matrix.iter_ones().count(), 6);
+        assert_eq!(matrix.iter_zeros().count(), 0);
+        matrix.clear();
+        assert_eq!(matrix, StrictlyUpperTriangularLogicalMatrix::zeroed(4));
+    }
+
+    #[test]
+    fn try_from_ones_reports_offending_pair() {
+        assert_eq!(
+            StrictlyUpperTriangularLogicalMatrix::try_from_ones(3, vec![(0, 1), (1, 2)]),
+            Ok(StrictlyUpperTriangularLogicalMatrix::from_iter(
+                3,
+                vec![(0, 1), (1, 2)].into_iter()
+            ))
+        );
+        assert_eq!(
+            StrictlyUpperTriangularLogicalMatrix::try_from_ones(3, vec![(0, 1), (0, 3)]),
+            Err(FromOnesError::InvalidPair {
+                pair: (0, 3),
+                error: IndexError::OutOfBounds { index: 3, size: 3 }
+            })
+        );
+        assert_eq!(
+            StrictlyUpperTriangularLogicalMatrix::try_from_ones(3, vec![(2, 1)]),
+            Err(FromOnesError::InvalidPair {
+                pair: (2, 1),
+                error: IndexError::NotStrictlyUpper { i: 2, j: 1 }
+            })
+        );
+        assert_eq!(
+            StrictlyUpperTriangularLogicalMatrix::try_from_ones(3, vec![(0, 2), (1, 2), (0, 2)]),
+            Err(FromOnesError::Duplicate { pair: (0, 2) })
+        );
+        assert_eq!(
+            StrictlyUpperTriangularLogicalMatrix::try_from_ones(usize::MAX, vec![]),
+            Err(FromOnesError::CapacityOverflow { size: usize::MAX })
+        );
+    }
+
+    #[test]
+    fn unchecked_accessors() {
+        let mut matrix = StrictlyUpperTriangularLogicalMatrix::zeroed(40);
+        unsafe {
+            matrix.set_unchecked(3, 39, true);
+            matrix.set_unchecked(0, 1, true);
+            matrix.set_unchecked(0, 1, false);
+            assert!(matrix.get_unchecked(3, 39));
+            assert!(!matrix.get_unchecked(0, 1));
+        }
+        assert_eq!(matrix.iter_ones().collect::<Vec<_>>(), vec![(3, 39)]);
+    }
+
+    #[test]
+    fn iter_ones_by_target_is_column_major() {
+        let matrix = StrictlyUpperTriangularLogicalMatrix::from_iter(
+            4,
+            vec![(0, 3), (1, 2), (0, 1), (2, 3)].into_iter(),
+        );
+        assert_eq!(
+            matrix.iter_ones_by_target().collect::<Vec<_>>(),
+            vec![(0, 1), (1, 2), (0, 3), (2, 3)]
+        );
+        assert_eq!(matrix.iter_ones_at_column(0).next(), None);
+        assert_eq!(
+            matrix.iter_ones_at_column(3).collect::<Vec<_>>(),
+            vec![0, 2]
+        );
+    }
+
+    #[test]
+    fn iter_ones_agrees_with_matrix_iterator() {
+        for size in 0..10 {
+            let matrix = StrictlyUpperTriangularLogicalMatrix::from_iter(
+                size,
+                iter_matrix(size)
+                    .filter(|(i, j, _)| (i * 7 + j * 3) % 4 == 0)
+                    .map(|(i, j, _)| (i, j)),
+            );
+            let expected: Vec<(usize, usize)> = iter_matrix(size)
+                .filter(|(_, _, index)| matrix.matrix[*index])
+                .map(|(i, j, _)| (i, j))
+                .collect();
+            assert_eq!(matrix.iter_ones().collect::<Vec<_>>(), expected);
+        }
+        assert_eq!(iter_matrix(0).count(), 0);
+        assert_eq!(iter_matrix(1).count(), 0);
+    }
+
+    #[test]
+    fn capacity_overflow_is_detected() {
+        assert_eq!(
+            checked_strictly_upper_triangular_matrix_capacity(0),
+            Some(0)
+        );
+        assert_eq!(
+            checked_strictly_upper_triangular_matrix_capacity(1),
+            Some(0)
+        );
+        assert_eq!(
+            checked_strictly_upper_triangular_matrix_capacity(4),
+            Some(6)
+        );
+        assert_eq!(
+            checked_strictly_upper_triangular_matrix_capacity(5),
+            Some(10)
+        );
+        assert_eq!(
+            checked_strictly_upper_triangular_matrix_capacity(usize::MAX),
+            None
+        );
+        assert_eq!(
+            checked_strictly_upper_triangular_matrix_capacity(1 << (usize::BITS / 2 + 1)),
+            None
+        );
+        assert!(StrictlyUpperTriangularLogicalMatrix::try_zeroed(usize::MAX).is_none());
+        assert_eq!(
+            StrictlyUpperTriangularLogicalMatrix::try_zeroed(3),
+            Some(StrictlyUpperTriangularLogicalMatrix::zeroed(3))
+        );
+    }
+
+    #[test]
+    fn iter_ones_in_range_matches_naive_scan() {
+        let mut bits = FixedBitSet::with_capacity(100);
+        bits.extend((0..100).filter(|i| (i * 7) % 5 < 2));
+        for start in 0..=100 {
+            for end in start..=100 {
+                assert!(iter_ones_in_range(&bits, start..end).eq((start..end).filter(|i| bits[*i])));
+            }
+        }
+    }
+
+    #[test]
+    fn union_row_into_matches_iter_ones_at_row() {
+        for size in 1..80 {
+            let matrix = StrictlyUpperTriangularLogicalMatrix::from_iter(
+                size,
+                iter_matrix(size)
+                    .filter(|(i, j, _)| (i * 5 + j * 11) % 3 != 0)
+                    .map(|(i, j, _)| (i, j)),
+            );
+            for i in 0..size {
+                let mut row = FixedBitSet::with_capacity(size);
+                matrix.union_row_into(i, &mut row);
+                assert!(row.ones().eq(matrix.iter_ones_at_row(i)));
+            }
+        }
+    }
+
+    #[test]
+    fn boolean_mul_matches_naive_product() {
+        for size in 0..40 {
+            let a = StrictlyUpperTriangularLogicalMatrix::from_iter(
+                size,
+                iter_matrix(size)
+                    .filter(|(i, j, _)| (i * 7 + j * 3) % 4 == 0)
+                    .map(|(i, j, _)| (i, j)),
+            );
+            let b = StrictlyUpperTriangularLogicalMatrix::from_iter(
+                size,
+                iter_matrix(size)
+                    .filter(|(i, j, _)| (i + j * 5) % 3 == 0)
+                    .map(|(i, j, _)| (i, j)),
+            );
+            let naive = StrictlyUpperTriangularLogicalMatrix::from_iter(
+                size,
+                iter_matrix(size)
+                    .filter(|(i, j, _)| ((i + 1)..*j).any(|k| a.get(*i, k) && b.get(k, *j)))
+                    .map(|(i, j, _)| (i, j)),
+            );
+            assert_eq!(a.boolean_mul(&b), naive);
+        }
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = StrictlyUpperTriangularLogicalMatrix::from_iter(3, [(0, 1), (0, 2)].into_iter());
+        let b = StrictlyUpperTriangularLogicalMatrix::from_iter(3, [(0, 2), (1, 2)].into_iter());
+        let apply = |operation: fn(
+            &mut StrictlyUpperTriangularLogicalMatrix,
+            &StrictlyUpperTriangularLogicalMatrix,
+        )| {
+            let mut result = a.clone();
+            operation(&mut result, &b);
+            result.iter_ones().collect::<Vec<_>>()
+        };
+        assert_eq!(
+            apply(StrictlyUpperTriangularLogicalMatrix::union_with),
+            vec![(0, 1), (0, 2), (1, 2)]
+        );
+        assert_eq!(
+            apply(StrictlyUpperTriangularLogicalMatrix::intersect_with),
+            vec![(0, 2)]
+        );
+        assert_eq!(
+            apply(StrictlyUpperTriangularLogicalMatrix::difference_with),
+            vec![(0, 1)]
+        );
+        assert_eq!(
+            apply(StrictlyUpperTriangularLogicalMatrix::symmetric_difference_with),
+            vec![(0, 1), (1, 2)]
+        );
+    }
+
+    #[test]
+    fn principal_submatrix() {
+        let matrix = StrictlyUpperTriangularLogicalMatrix::from_iter(
+            5,
+            [(0, 2), (0, 4), (1, 3), (2, 4), (3, 4)].into_iter(),
+        );
+        assert_eq!(
+            matrix.submatrix(&[0, 2, 4]),
+            StrictlyUpperTriangularLogicalMatrix::from_iter(
+                3,
+                [(0, 1), (0, 2), (1, 2)].into_iter()
+            )
+        );
+        assert_eq!(
+            matrix.submatrix(&[1, 3]),
+            StrictlyUpperTriangularLogicalMatrix::from_iter(2, [(0, 1)].into_iter())
+        );
+        assert_eq!(matrix.submatrix(&[]).size(), 0);
+    }
+
+    #[test]
+    fn resize_keeps_ones_in_range() {
+        for old_size in 0..40 {
+            let ones: Vec<(usize, usize)> = iter_matrix(old_size)
+                .filter(|(i, j, _)| (i * 13 + j * 7) % 5 < 2)
+                .map(|(i, j, _)| (i, j))
+                .collect();
+            for new_size in [0, old_size / 2, old_size, old_size + 1, old_size * 2 + 3] {
+                let mut matrix =
+                    StrictlyUpperTriangularLogicalMatrix::from_iter(old_size, ones.iter().copied());
+                matrix.resize(new_size);
+                let expected = StrictlyUpperTriangularLogicalMatrix::from_iter(
+                    new_size,
+                    ones.iter().copied().filter(|(_, j)| *j < new_size),
+                );
+                assert_eq!(matrix, expected);
+                assert!(matrix.iter_ones().eq(expected.iter_ones()));
+            }
+        }
+    }
+
+    #[test]
+    fn raw_block_access() {
+        let mut matrix = StrictlyUpperTriangularLogicalMatrix::from_iter(4, [(1, 3)].into_iter());
+        // Row 0 takes bits 0..3, row 1 bits 3..5 and row 2 bit 5.
+        assert_eq!(matrix.get_row_bits(0), 0..3);
+        assert_eq!(matrix.get_row_bits(1), 3..5);
+        assert_eq!(matrix.get_row_bits(3), 6..6);
+        assert_eq!(matrix.as_blocks(), &[0b010000]);
+
+        matrix.with_blocks_mut(|blocks| blocks[0] = u32::MAX);
+        assert_eq!(matrix.as_blocks(), &[0b111111]);
+        let mut full = StrictlyUpperTriangularLogicalMatrix::zeroed(4);
+        full.fill();
+        assert_eq!(matrix, full);
+    }
+
+    #[test]
+    fn dense_conversions() {
+        let dense = vec![
+            vec![false, true, true],
+            vec![false, false, false],
+            vec![false, false, false],
+        ];
+        let matrix = StrictlyUpperTriangularLogicalMatrix::from_dense(&dense).unwrap();
+        assert_eq!(matrix.iter_ones().collect::<Vec<_>>(), vec![(0, 1), (0, 2)]);
+        assert_eq!(matrix.to_dense(), dense);
+        assert_eq!(
+            StrictlyUpperTriangularLogicalMatrix::from_dense(&[
+                vec![false, true],
+                vec![true, false]
+            ]),
+            Err(FromDenseError::NotStrictlyUpper { i: 1, j: 0 })
+        );
+        assert_eq!(
+            StrictlyUpperTriangularLogicalMatrix::from_dense(&[vec![false, true], vec![false]]),
+            Err(FromDenseError::NotSquare { row: 1, len: 1 })
+        );
+    }
+
+    #[test]
+    fn validate_detects_violations() {
+        let mut matrix = StrictlyUpperTriangularLogicalMatrix::from_iter(3, [(0, 2)].into_iter());
+        assert_eq!(matrix.validate(), Ok(()));
+        matrix.matrix.as_mut_slice()[0] |= 1 << 31;
+        assert_eq!(
+            matrix.validate(),
+            Err(InvariantViolation::BitsPastEnd { block: 0 })
+        );
+        matrix.matrix = FixedBitSet::with_capacity(4);
+        assert_eq!(
+            matrix.validate(),
+            Err(InvariantViolation::WrongLength {
+                expected: 3,
+                len: 4
+            })
+        );
+        matrix.size = usize::MAX;
+        assert_eq!(
+            matrix.validate(),
+            Err(InvariantViolation::SizeOverflow { size: usize::MAX })
+        );
+        for size in [0, 1, 8, 9, 100] {
+            assert_eq!(
+                StrictlyUpperTriangularLogicalMatrix::zeroed(size).validate(),
+                Ok(())
+            );
+        }
+    }
+
+    #[test]
+    fn row_chunks_partition_the_matrix() {
+        for size in [0, 1, 2, 7, 40] {
+            let matrix = StrictlyUpperTriangularLogicalMatrix::from_iter(
+                size,
+                (0..size)
+                    .flat_map(|i| ((i + 1)..size).map(move |j| (i, j)))
+                    .filter(|(i, j)| (i + 2 * j) % 3 == 0),
+            );
+            for chunk_count in [1, 3, 8, 100] {
+                let chunks: Vec<RowChunk> = matrix.iter_row_chunks(chunk_count).collect();
+                assert!(chunks.len() <= chunk_count);
+                let rows: Vec<usize> = chunks.iter().flat_map(|c| c.get_rows()).collect();
+                assert_eq!(rows, (0..size).collect::<Vec<_>>());
+                let ones: Vec<(usize, usize)> = chunks.iter().flat_map(|c| c.iter_ones()).collect();
+                assert_eq!(ones, matrix.iter_ones().collect::<Vec<_>>());
+                for chunk in &chunks {
+                    let (blocks, offset) = chunk.get_blocks();
+                    let bits = chunk.get_bits();
+                    let count: usize = (0..bits.len())
+                        .filter(|b| (blocks[(offset + b) / 32] >> ((offset + b) % 32)) & 1 == 1)
+                        .count();
+                    assert_eq!(count, chunk.count_ones());
+                }
+                let total: usize = std::thread::scope(|scope| {
+                    let handles: Vec<_> = chunks
+                        .iter()
+                        .map(|chunk| scope.spawn(move || chunk.count_ones()))
+                        .collect();
+                    handles.into_iter().map(|h| h.join().unwrap()).sum()
+                });
+                assert_eq!(total, matrix.iter_ones().count());
+            }
+        }
     }
 }
@@ -0,0 +1,163 @@
+use fixedbitset::FixedBitSet;
+
+use crate::DirectedAcyclicGraph;
+
+/// See [`iter_subgraph_matches`].
+pub struct SubgraphMatchesIterator<'a> {
+    pattern: &'a DirectedAcyclicGraph,
+    target: &'a DirectedAcyclicGraph,
+    pattern_parents: Vec<Vec<usize>>,
+    pattern_degrees: Vec<(usize, usize)>,
+    target_degrees: Vec<(usize, usize)>,
+    mapping: Vec<usize>,
+    used: FixedBitSet,
+    // Candidates not yet tried for each pattern vertex on the current search
+    // path.  Reversed so that they're tried in increasing order.
+    candidates: Vec<Vec<usize>>,
+    started: bool,
+}
+
+// A mapping: vertex -> (in-degree, out-degree)
+fn get_degrees(dag: &DirectedAcyclicGraph) -> Vec<(usize, usize)> {
+    let mut degrees: Vec<(usize, usize)> = vec![(0, 0); dag.get_vertex_count()];
+    for (u, v) in dag.iter_edges() {
+        degrees[u].1 += 1;
+        degrees[v].0 += 1;
+    }
+    degrees
+}
+
+impl<'a> SubgraphMatchesIterator<'a> {
+    // Feasible images of pattern vertex `p` given the images of pattern
+    // vertices `0..p`.
+    fn get_candidates(&self, p: usize) -> Vec<usize> {
+        let (in_degree, out_degree) = self.pattern_degrees[p];
+        let is_feasible = |c: &usize| {
+            !self.used[*c]
+                && self.target_degrees[*c].0 >= in_degree
+                && self.target_degrees[*c].1 >= out_degree
+                && self.pattern_parents[p].iter().all(|q| {
+                    let image = self.mapping[*q];
+                    image < *c && self.target.get_edge(image, *c)
+                })
+        };
+        let mut result: Vec<usize> = match self.pattern_parents[p].first() {
+            Some(q) => self
+                .target
+                .iter_children(self.mapping[*q])
+                .filter(is_feasible)
+                .collect(),
+            None => (0..self.target.get_vertex_count())
+                .filter(is_feasible)
+                .collect(),
+        };
+        result.reverse();
+        result
+    }
+}
+
+impl<'a> Iterator for SubgraphMatchesIterator<'a> {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.started {
+            self.started = true;
+            if self.pattern.get_vertex_count() == 0 {
+                return Some(Vec::new());
+            }
+            self.candidates.push(self.get_candidates(0));
+        }
+
+        loop {
+            let depth = self.candidates.len();
+            if depth == 0 {
+                return None;
+            }
+            if self.mapping.len() == depth {
+                let previous = self.mapping.pop().unwrap();
+                self.used.set(previous, false);
+            }
+            match self.candidates[depth - 1].pop() {
+                Some(c) => {
+                    self.mapping.push(c);
+                    self.used.insert(c);
+                    if self.mapping.len() == self.pattern.get_vertex_count() {
+                        return Some(self.mapping.clone());
+                    }
+                    let next = self.get_candidates(self.mapping.len());
+                    self.candidates.push(next);
+                }
+                None => {
+                    self.candidates.pop();
+                }
+            }
+        }
+    }
+}
+
+/// Finds all occurrences of `pattern` in `target`, i.e. all injective mappings:
+/// pattern vertex -> target vertex such that every edge of `pattern` is mapped
+/// onto an edge of `target`.  `target` may have additional edges between the
+/// mapped vertices (the occurrences aren't necessarily induced subgraphs).
+///
+/// The search is a VF2-style backtracking that extends a partial mapping one
+/// pattern vertex at a time, in increasing order, pruning candidates that
+/// don't preserve edges to the vertices mapped so far or that have too small
+/// degrees.  Mappings are yielded in lexicographical order.
+pub fn iter_subgraph_matches<'a>(
+    pattern: &'a DirectedAcyclicGraph,
+    target: &'a DirectedAcyclicGraph,
+) -> SubgraphMatchesIterator<'a> {
+    let mut pattern_parents: Vec<Vec<usize>> = vec![Vec::new(); pattern.get_vertex_count()];
+    for (u, v) in pattern.iter_edges() {
+        pattern_parents[v].push(u);
+    }
+    SubgraphMatchesIterator {
+        pattern,
+        target,
+        pattern_parents,
+        pattern_degrees: get_degrees(pattern),
+        target_degrees: get_degrees(target),
+        mapping: Vec::with_capacity(pattern.get_vertex_count()),
+        used: FixedBitSet::with_capacity(target.get_vertex_count()),
+        candidates: Vec::with_capacity(pattern.get_vertex_count()),
+        started: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_diamonds() {
+        let diamond = crate::dag!(4; 0 => 1, 0 => 2, 1 => 3, 2 => 3);
+        let target = crate::dag!(6; 0 => 1, 0 => 2, 1 => 3, 2 => 3, 3 => 4, 3 => 5);
+        assert_eq!(
+            iter_subgraph_matches(&diamond, &target).collect::<Vec<_>>(),
+            vec![vec![0, 1, 2, 3], vec![0, 2, 1, 3]]
+        );
+
+        let fork = crate::dag!(3; 0 => 1, 0 => 2);
+        assert_eq!(iter_subgraph_matches(&fork, &target).count(), 4);
+        assert_eq!(iter_subgraph_matches(&target, &diamond).count(), 0);
+        assert_eq!(
+            iter_subgraph_matches(&crate::dag!(0), &target).collect::<Vec<_>>(),
+            vec![Vec::<usize>::new()]
+        );
+    }
+
+    fn prop_matches_preserve_edges(target: DirectedAcyclicGraph) -> bool {
+        let path = crate::dag!(3; 0 => 1, 1 => 2);
+        iter_subgraph_matches(&path, &target).all(|mapping| {
+            path.iter_edges()
+                .all(|(u, v)| target.get_edge(mapping[u], mapping[v]))
+        })
+    }
+
+    #[test]
+    fn matches_preserve_edges() {
+        quickcheck::QuickCheck::new()
+            .quickcheck(prop_matches_preserve_edges as fn(DirectedAcyclicGraph) -> bool);
+    }
+}
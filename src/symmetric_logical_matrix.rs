@@ -0,0 +1,100 @@
+use crate::StrictlyUpperTriangularLogicalMatrix;
+
+/// A zero-indexed symmetric matrix of booleans with a zero diagonal, e.g. the
+/// adjacency matrix of an undirected graph or a comparability relation.  Only
+/// the part above the diagonal is stored, as a
+/// [`StrictlyUpperTriangularLogicalMatrix`], so `(i, j)` and `(j, i)` always
+/// share a single bit.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct SymmetricLogicalMatrix {
+    upper: StrictlyUpperTriangularLogicalMatrix,
+}
+
+impl SymmetricLogicalMatrix {
+    pub fn zeroed(size: usize) -> Self {
+        Self {
+            upper: StrictlyUpperTriangularLogicalMatrix::zeroed(size),
+        }
+    }
+
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.upper.size()
+    }
+
+    /// Always `false` on the diagonal.
+    pub fn get(&self, i: usize, j: usize) -> bool {
+        assert!(i < self.size());
+        assert!(j < self.size());
+        i != j && self.upper.get(std::cmp::min(i, j), std::cmp::max(i, j))
+    }
+
+    /// Sets both `(i, j)` and `(j, i)`.  Requires `i != j`.  Returns the
+    /// previous value.
+    pub fn set(&mut self, i: usize, j: usize, value: bool) -> bool {
+        assert!(i != j);
+        self.upper
+            .set(std::cmp::min(i, j), std::cmp::max(i, j), value)
+    }
+
+    /// Iterates over the `(i, j)` positions with `i < j` that are set, i.e.
+    /// every pair once.
+    pub fn iter_ones_above_diagonal(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.upper.iter_ones()
+    }
+
+    /// Iterates over the `j`s such that `(i, j)` is set, in increasing order.
+    pub fn iter_ones_at_row(&self, i: usize) -> impl Iterator<Item = usize> + '_ {
+        assert!(i < self.size());
+        (0..i)
+            .filter(move |&j| self.upper.get(j, i))
+            .chain(self.upper.iter_ones_at_row(i))
+    }
+
+    /// The part above the diagonal.
+    pub fn get_upper_triangle(&self) -> &StrictlyUpperTriangularLogicalMatrix {
+        &self.upper
+    }
+}
+
+impl StrictlyUpperTriangularLogicalMatrix {
+    /// The smallest symmetric matrix containing this one: `(i, j)` and `(j,
+    /// i)` are set for every `(i, j)` set here.  For the transitive closure
+    /// of a DAG, that's the comparability relation.
+    pub fn symmetric_closure(&self) -> SymmetricLogicalMatrix {
+        SymmetricLogicalMatrix {
+            upper: self.clone(),
+        }
+    }
+}
+
+impl std::fmt::Debug for SymmetricLogicalMatrix {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}.symmetric_closure()", self.upper)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn symmetric_closure() {
+        let upper =
+            StrictlyUpperTriangularLogicalMatrix::from_iter(4, [(0, 2), (1, 3)].into_iter());
+        let mut symmetric = upper.symmetric_closure();
+        assert!(symmetric.get(2, 0) && symmetric.get(0, 2));
+        assert!(!symmetric.get(1, 1));
+        assert_eq!(symmetric.iter_ones_at_row(2).collect::<Vec<_>>(), vec![0]);
+        assert!(!symmetric.set(3, 2, true));
+        assert!(symmetric.get(2, 3));
+        assert_eq!(
+            symmetric.iter_ones_above_diagonal().collect::<Vec<_>>(),
+            vec![(0, 2), (1, 3), (2, 3)]
+        );
+        assert_eq!(
+            symmetric.iter_ones_at_row(3).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+    }
+}
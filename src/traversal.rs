@@ -1,19 +1,33 @@
-use std::collections::VecDeque;
+use std::cell::RefCell;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, VecDeque};
 
 use fixedbitset::FixedBitSet;
+use rand::Rng;
 
 use crate::DirectedAcyclicGraph;
 
+/// A mapping: vertex -> number of `edges` ending at vertex.
+pub(crate) fn count_incoming_edges<I: Iterator<Item = (usize, usize)>>(
+    vertex_count: usize,
+    edges: I,
+) -> Vec<usize> {
+    let mut incoming_edges_count: Vec<usize> = vec![0; vertex_count];
+    for (_, v) in edges {
+        incoming_edges_count[v] += 1;
+    }
+    incoming_edges_count
+}
+
+/// A mapping: vertex -> in-degree.
+pub(crate) fn get_incoming_edges_count(dag: &DirectedAcyclicGraph) -> Vec<usize> {
+    count_incoming_edges(dag.get_vertex_count(), dag.iter_edges())
+}
+
 /// Returns a set "seed" vertices of a DAG from which a traversal may start so
 /// that the process covers all vertices in the graph.
 pub fn get_vertices_without_incoming_edges(dag: &DirectedAcyclicGraph) -> Vec<usize> {
-    let incoming_edges_count = {
-        let mut incoming_edges_count: Vec<usize> = vec![0; dag.get_vertex_count()];
-        for (_, v) in dag.iter_edges() {
-            incoming_edges_count[v] += 1;
-        }
-        incoming_edges_count
-    };
+    let incoming_edges_count = get_incoming_edges_count(dag);
 
     let vertices_without_incoming_edges: Vec<usize> = incoming_edges_count
         .into_iter()
@@ -51,7 +65,7 @@ impl<'a> Iterator for BfsVerticesIterator<'a> {
 
 /// Visit all vertices reachable from `vertex` in a breadth-first-search (BFS)
 /// order.
-pub fn iter_descendants_bfs(dag: &DirectedAcyclicGraph, vertex: usize) -> BfsVerticesIterator {
+pub fn iter_descendants_bfs(dag: &DirectedAcyclicGraph, vertex: usize) -> BfsVerticesIterator<'_> {
     BfsVerticesIterator {
         dag,
         visited: FixedBitSet::with_capacity(dag.get_vertex_count()),
@@ -60,7 +74,7 @@ pub fn iter_descendants_bfs(dag: &DirectedAcyclicGraph, vertex: usize) -> BfsVer
 }
 
 /// Visit all vertices of a DAG in a breadth-first-search (BFS) order.
-pub fn iter_vertices_bfs(dag: &DirectedAcyclicGraph) -> BfsVerticesIterator {
+pub fn iter_vertices_bfs(dag: &DirectedAcyclicGraph) -> BfsVerticesIterator<'_> {
     BfsVerticesIterator {
         dag,
         visited: FixedBitSet::with_capacity(dag.get_vertex_count()),
@@ -68,6 +82,61 @@ pub fn iter_vertices_bfs(dag: &DirectedAcyclicGraph) -> BfsVerticesIterator {
     }
 }
 
+/// See [`iter_descendants_bfs_within`].
+pub struct DepthBoundedBfsVerticesIterator<'a> {
+    dag: &'a DirectedAcyclicGraph,
+    max_depth: usize,
+    visited: FixedBitSet,
+    to_visit: VecDeque<(usize, usize)>,
+}
+
+impl<'a> Iterator for DepthBoundedBfsVerticesIterator<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (u, depth) = self.to_visit.pop_front()?;
+        if depth < self.max_depth {
+            for v in self.dag.iter_children(u) {
+                if !self.visited[v] {
+                    self.visited.insert(v);
+                    self.to_visit.push_back((v, depth + 1));
+                }
+            }
+        }
+        Some(u)
+    }
+}
+
+/// Visit all vertices reachable from `vertex` by a path of at most `max_depth`
+/// edges in a breadth-first-search (BFS) order.  `vertex` itself is at depth
+/// 0.
+pub fn iter_descendants_bfs_within(
+    dag: &DirectedAcyclicGraph,
+    vertex: usize,
+    max_depth: usize,
+) -> DepthBoundedBfsVerticesIterator<'_> {
+    let mut visited = FixedBitSet::with_capacity(dag.get_vertex_count());
+    visited.insert(vertex);
+    DepthBoundedBfsVerticesIterator {
+        dag,
+        max_depth,
+        visited,
+        to_visit: vec![(vertex, 0)].into(),
+    }
+}
+
+// All the vertices except the ones reachable from `vertex` by a path of at
+// most `max_depth` edges.  Marking them visited upfront confines a DFS to the
+// latter, even though it may reach a vertex by a longer path first.
+fn get_vertices_beyond(dag: &DirectedAcyclicGraph, vertex: usize, max_depth: usize) -> FixedBitSet {
+    let mut result = FixedBitSet::with_capacity(dag.get_vertex_count());
+    result.insert_range(..);
+    for u in iter_descendants_bfs_within(dag, vertex, max_depth) {
+        result.set(u, false);
+    }
+    result
+}
+
 /// See [`iter_vertices_dfs`].
 pub struct DfsVerticesIterator<'a> {
     dag: &'a DirectedAcyclicGraph,
@@ -93,7 +162,7 @@ impl<'a> Iterator for DfsVerticesIterator<'a> {
 
 /// Visit all vertices reachable from `vertex` in a depth-first-search (DFS)
 /// order.
-pub fn iter_descendants_dfs(dag: &DirectedAcyclicGraph, vertex: usize) -> DfsVerticesIterator {
+pub fn iter_descendants_dfs(dag: &DirectedAcyclicGraph, vertex: usize) -> DfsVerticesIterator<'_> {
     DfsVerticesIterator {
         dag,
         visited: FixedBitSet::with_capacity(dag.get_vertex_count()),
@@ -101,8 +170,26 @@ pub fn iter_descendants_dfs(dag: &DirectedAcyclicGraph, vertex: usize) -> DfsVer
     }
 }
 
+/// Same as [`iter_descendants_dfs`] but only visits the vertices reachable
+/// from `vertex` by a path of at most `max_depth` edges.  `vertex` itself is at
+/// depth 0.
+///
+/// Those vertices are found with [`iter_descendants_bfs_within`] upfront, since
+/// the DFS may reach a vertex by a longer path before a shorter one.
+pub fn iter_descendants_dfs_within(
+    dag: &DirectedAcyclicGraph,
+    vertex: usize,
+    max_depth: usize,
+) -> DfsVerticesIterator<'_> {
+    DfsVerticesIterator {
+        dag,
+        visited: get_vertices_beyond(dag, vertex, max_depth),
+        to_visit: vec![vertex],
+    }
+}
+
 /// Visit all vertices of a DAG in a depth-first-search (DFS) order.
-pub fn iter_vertices_dfs(dag: &DirectedAcyclicGraph) -> DfsVerticesIterator {
+pub fn iter_vertices_dfs(dag: &DirectedAcyclicGraph) -> DfsVerticesIterator<'_> {
     DfsVerticesIterator {
         dag,
         visited: FixedBitSet::with_capacity(dag.get_vertex_count()),
@@ -111,7 +198,6 @@ pub fn iter_vertices_dfs(dag: &DirectedAcyclicGraph) -> DfsVerticesIterator {
 }
 
 /// See [`iter_vertices_dfs_post_order`].
-
 pub struct DfsPostOrderVerticesIterator<'a> {
     dag: &'a DirectedAcyclicGraph,
     visited: FixedBitSet,
@@ -123,10 +209,7 @@ impl<'a> Iterator for DfsPostOrderVerticesIterator<'a> {
 
     fn next(&mut self) -> Option<Self::Item> {
         loop {
-            let u = match self.to_visit.last().copied() {
-                Some(u) => u,
-                None => return None,
-            };
+            let u = self.to_visit.last().copied()?;
             if self.visited[u] {
                 self.to_visit.pop();
                 continue;
@@ -154,7 +237,7 @@ impl<'a> Iterator for DfsPostOrderVerticesIterator<'a> {
 pub fn iter_descendants_dfs_post_order(
     dag: &DirectedAcyclicGraph,
     vertex: usize,
-) -> DfsPostOrderVerticesIterator {
+) -> DfsPostOrderVerticesIterator<'_> {
     DfsPostOrderVerticesIterator {
         dag,
         visited: FixedBitSet::with_capacity(dag.get_vertex_count()),
@@ -162,9 +245,27 @@ pub fn iter_descendants_dfs_post_order(
     }
 }
 
+/// Same as [`iter_descendants_dfs_post_order`] but only visits the vertices
+/// reachable from `vertex` by a path of at most `max_depth` edges, so a vertex
+/// is emitted after all its descendants among those.  See
+/// [`iter_descendants_dfs_within`].
+pub fn iter_descendants_dfs_post_order_within(
+    dag: &DirectedAcyclicGraph,
+    vertex: usize,
+    max_depth: usize,
+) -> DfsPostOrderVerticesIterator<'_> {
+    DfsPostOrderVerticesIterator {
+        dag,
+        visited: get_vertices_beyond(dag, vertex, max_depth),
+        to_visit: vec![vertex],
+    }
+}
+
 /// Visit all vertices of a DAG in a depth-first-search postorder, i.e. emitting
 /// vertices only after all their descendants have been emitted first.
-pub fn iter_vertices_dfs_post_order(dag: &DirectedAcyclicGraph) -> DfsPostOrderVerticesIterator {
+pub fn iter_vertices_dfs_post_order(
+    dag: &DirectedAcyclicGraph,
+) -> DfsPostOrderVerticesIterator<'_> {
     DfsPostOrderVerticesIterator {
         dag,
         visited: FixedBitSet::with_capacity(dag.get_vertex_count()),
@@ -210,7 +311,7 @@ impl<'a> Iterator for DfsPostOrderEdgesIterator<'a> {
 /// either a [`crate::algorithm::transitive_reduction`] of a DAG, to only get
 /// the minimal set of pairs spanning the entire poset, or a
 /// [`crate::algorithm::transitive_closure`] to get all the pairs of that poset.
-pub fn iter_edges_dfs_post_order(dag: &DirectedAcyclicGraph) -> DfsPostOrderEdgesIterator {
+pub fn iter_edges_dfs_post_order(dag: &DirectedAcyclicGraph) -> DfsPostOrderEdgesIterator<'_> {
     DfsPostOrderEdgesIterator {
         dag,
         inner: iter_vertices_dfs_post_order(dag),
@@ -229,6 +330,380 @@ pub fn get_topologically_ordered_vertices(dag: &DirectedAcyclicGraph) -> Vec<usi
     result
 }
 
+/// See [`iter_vertices_kahn`].
+pub struct KahnVerticesIterator<'a> {
+    dag: &'a DirectedAcyclicGraph,
+    incoming_edges_count: Vec<usize>,
+    ready: VecDeque<usize>,
+}
+
+impl<'a> Iterator for KahnVerticesIterator<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let u = self.ready.pop_front()?;
+        for v in self.dag.iter_children(u) {
+            self.incoming_edges_count[v] -= 1;
+            if self.incoming_edges_count[v] == 0 {
+                self.ready.push_back(v);
+            }
+        }
+        Some(u)
+    }
+}
+
+/// Lazily visit all vertices of a DAG in a topological order using [Kahn's
+/// algorithm](https://en.wikipedia.org/wiki/Topological_sorting#Kahn's_algorithm).
+///
+/// A vertex is emitted only once all of its parents have been emitted.  The
+/// next vertex is computed on demand so that it is cheap to only consume a
+/// prefix of the order.
+pub fn iter_vertices_kahn(dag: &DirectedAcyclicGraph) -> KahnVerticesIterator<'_> {
+    let incoming_edges_count = get_incoming_edges_count(dag);
+    let ready: VecDeque<usize> = incoming_edges_count
+        .iter()
+        .enumerate()
+        .filter(|(_, indegree)| **indegree == 0)
+        .map(|(vertex, _)| vertex)
+        .collect();
+    KahnVerticesIterator {
+        dag,
+        incoming_edges_count,
+        ready,
+    }
+}
+
+/// Visit all vertices of a DAG in a reverse topological order, i.e. every
+/// vertex is emitted only after all of its children have been emitted first.
+///
+/// Since edges only ever go from a smaller vertex to a bigger one, decreasing
+/// vertex order is always a valid reverse topological order, so this requires
+/// neither a traversal nor any extra memory.  Useful for bottom-up dynamic
+/// programming over a DAG.
+pub fn iter_vertices_reverse_topological(
+    dag: &DirectedAcyclicGraph,
+) -> std::iter::Rev<std::ops::Range<usize>> {
+    (0..dag.get_vertex_count()).rev()
+}
+
+/// Visit all vertices of a DAG in the lexicographically smallest topological
+/// order, i.e. the one that breaks ties by picking the smallest vertex.
+///
+/// Since edges only ever go from a smaller vertex to a bigger one, that's
+/// simply increasing vertex order and it is deterministic for a given vertex
+/// count regardless of the edges.
+pub fn iter_vertices_lexicographical_topological(
+    dag: &DirectedAcyclicGraph,
+) -> std::ops::Range<usize> {
+    0..dag.get_vertex_count()
+}
+
+/// Returns the topological order that stays closest to `preferred_order` (a
+/// permutation of all the vertices), e.g. the order from a previous run of a
+/// build plan.  If `preferred_order` is topological already, it's returned
+/// unchanged.
+///
+/// Among all the vertices whose parents have been emitted, the one that comes
+/// first in `preferred_order` is emitted next, so the result is the
+/// lexicographically smallest topological order with respect to
+/// `preferred_order`.
+pub fn get_topological_order_closest_to(
+    dag: &DirectedAcyclicGraph,
+    preferred_order: &[usize],
+) -> Vec<usize> {
+    let vertex_count = dag.get_vertex_count();
+    assert_eq!(preferred_order.len(), vertex_count);
+    let mut rank: Vec<usize> = vec![usize::MAX; vertex_count];
+    for (i, u) in preferred_order.iter().enumerate() {
+        assert_eq!(rank[*u], usize::MAX, "vertex {} repeated", u);
+        rank[*u] = i;
+    }
+    get_topological_order_by_key(dag, |u| rank[u])
+}
+
+// Kahn's algorithm with a heap of ready vertices that always emits the one
+// with the smallest `key` next, breaking ties by picking the smaller vertex.
+fn get_topological_order_by_key<K, F>(dag: &DirectedAcyclicGraph, mut key: F) -> Vec<usize>
+where
+    K: Ord,
+    F: FnMut(usize) -> K,
+{
+    let mut incoming_edges_count = get_incoming_edges_count(dag);
+    let mut ready: BinaryHeap<Reverse<(K, usize)>> = get_vertices_without_incoming_edges(dag)
+        .into_iter()
+        .map(|u| Reverse((key(u), u)))
+        .collect();
+    let mut result: Vec<usize> = Vec::with_capacity(dag.get_vertex_count());
+    while let Some(Reverse((_, u))) = ready.pop() {
+        result.push(u);
+        for v in dag.iter_children(u) {
+            incoming_edges_count[v] -= 1;
+            if incoming_edges_count[v] == 0 {
+                ready.push(Reverse((key(v), v)));
+            }
+        }
+    }
+    result
+}
+
+// A vertex ordered by a comparator shared by all the vertices in a heap.
+struct ComparedVertex<'a, F> {
+    vertex: usize,
+    compare: &'a RefCell<F>,
+}
+
+impl<F: FnMut(&usize, &usize) -> Ordering> Ord for ComparedVertex<'_, F> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.compare.borrow_mut())(&self.vertex, &other.vertex)
+    }
+}
+
+impl<F: FnMut(&usize, &usize) -> Ordering> PartialOrd for ComparedVertex<'_, F> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<F: FnMut(&usize, &usize) -> Ordering> PartialEq for ComparedVertex<'_, F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<F: FnMut(&usize, &usize) -> Ordering> Eq for ComparedVertex<'_, F> {}
+
+/// Returns the topological order that, among all the vertices whose parents
+/// have been emitted, always emits the minimum one under `compare` next, e.g.
+/// to schedule by priority subject to dependencies.  Ties are broken by
+/// picking the smaller vertex.  Also known as `topological_sort_by`.
+///
+/// This is Kahn's algorithm with a heap of ready vertices.  Every vertex
+/// enters and leaves the heap once, so `compare`, while it needs to be a total
+/// order, is called `O(|V| log |V|)` times.
+pub fn get_topological_order_by<F>(dag: &DirectedAcyclicGraph, compare: F) -> Vec<usize>
+where
+    F: FnMut(&usize, &usize) -> Ordering,
+{
+    let compare = RefCell::new(compare);
+    get_topological_order_by_key(dag, |vertex| ComparedVertex {
+        vertex,
+        compare: &compare,
+    })
+}
+
+// Removes `ready[i]` from `remaining` and returns the vertices of `remaining`
+// without incoming edges afterwards.
+fn remove_ready_vertex(
+    children: &[Vec<usize>],
+    remaining: &mut FixedBitSet,
+    ready: &[usize],
+    i: usize,
+    incoming_edges_count: &mut [usize],
+) -> Vec<usize> {
+    let u = ready[i];
+    remaining.set(u, false);
+    let mut next_ready: Vec<usize> = ready.iter().copied().filter(|v| *v != u).collect();
+    for v in &children[u] {
+        incoming_edges_count[*v] -= 1;
+        if incoming_edges_count[*v] == 0 {
+            next_ready.push(*v);
+        }
+    }
+    next_ready
+}
+
+// Undoes `remove_ready_vertex()`.
+fn restore_ready_vertex(
+    children: &[Vec<usize>],
+    remaining: &mut FixedBitSet,
+    u: usize,
+    incoming_edges_count: &mut [usize],
+) {
+    remaining.insert(u);
+    for v in &children[u] {
+        incoming_edges_count[*v] += 1;
+    }
+}
+
+// Natural logarithm of the number of topological orders of the sub-DAG induced
+// by `remaining`, whose vertices without incoming edges are `ready`.  The
+// counts overflow any integer type, and even `f64`, very quickly (two parallel
+// chains of 600 vertices already have more than 10^300 orders), so they are
+// kept in log space.  Only their ratios matter for sampling anyway.
+fn get_log_topological_order_count(
+    children: &[Vec<usize>],
+    remaining: &mut FixedBitSet,
+    ready: &[usize],
+    incoming_edges_count: &mut [usize],
+    memo: &mut HashMap<FixedBitSet, f64>,
+) -> f64 {
+    if ready.is_empty() {
+        return 0.0;
+    }
+    if let Some(log_count) = memo.get(remaining) {
+        return *log_count;
+    }
+    let mut log_counts: Vec<f64> = Vec::with_capacity(ready.len());
+    for i in 0..ready.len() {
+        let next_ready = remove_ready_vertex(children, remaining, ready, i, incoming_edges_count);
+        log_counts.push(get_log_topological_order_count(
+            children,
+            remaining,
+            &next_ready,
+            incoming_edges_count,
+            memo,
+        ));
+        restore_ready_vertex(children, remaining, ready[i], incoming_edges_count);
+    }
+    let max = log_counts.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let log_count = max + log_counts.iter().map(|l| (l - max).exp()).sum::<f64>().ln();
+    memo.insert(remaining.clone(), log_count);
+    log_count
+}
+
+/// Returns a topological order of a DAG chosen uniformly at random from among
+/// *all* of its topological orders (i.e. [linear
+/// extensions](https://en.wikipedia.org/wiki/Linear_extension)).
+///
+/// Note that breaking ties randomly in Kahn's algorithm does *not* produce a
+/// uniform distribution.  Instead, this function counts the orders of every
+/// sub-DAG it encounters, which takes time and memory proportional to the
+/// number of downward-closed vertex sets of the DAG.  That is exponential in
+/// the size of the largest antichain, so it's only practical for DAGs that are
+/// either small or "narrow".
+pub fn get_random_topological_order<R: Rng>(dag: &DirectedAcyclicGraph, rng: &mut R) -> Vec<usize> {
+    let children: Vec<Vec<usize>> = (0..dag.get_vertex_count())
+        .map(|u| dag.iter_children(u).collect())
+        .collect();
+    let mut incoming_edges_count = get_incoming_edges_count(dag);
+    let mut memo: HashMap<FixedBitSet, f64> = HashMap::new();
+    let mut remaining = FixedBitSet::with_capacity(dag.get_vertex_count());
+    remaining.insert_range(..);
+    let mut ready = get_vertices_without_incoming_edges(dag);
+
+    let mut result: Vec<usize> = Vec::with_capacity(dag.get_vertex_count());
+    while !ready.is_empty() {
+        let mut weights: Vec<f64> = Vec::with_capacity(ready.len());
+        for i in 0..ready.len() {
+            let next_ready = remove_ready_vertex(
+                &children,
+                &mut remaining,
+                &ready,
+                i,
+                &mut incoming_edges_count,
+            );
+            weights.push(get_log_topological_order_count(
+                &children,
+                &mut remaining,
+                &next_ready,
+                &mut incoming_edges_count,
+                &mut memo,
+            ));
+            restore_ready_vertex(
+                &children,
+                &mut remaining,
+                ready[i],
+                &mut incoming_edges_count,
+            );
+        }
+        // Scale the counts relative to the largest one so that they fit in
+        // `f64` again.
+        let max = weights.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        for weight in &mut weights {
+            *weight = (*weight - max).exp();
+        }
+        let total: f64 = weights.iter().sum();
+        let mut threshold = rng.gen::<f64>() * total;
+        let mut chosen = ready.len() - 1;
+        for (i, weight) in weights.into_iter().enumerate() {
+            if threshold < weight {
+                chosen = i;
+                break;
+            }
+            threshold -= weight;
+        }
+        result.push(ready[chosen]);
+        ready = remove_ready_vertex(
+            &children,
+            &mut remaining,
+            &ready,
+            chosen,
+            &mut incoming_edges_count,
+        );
+    }
+    result
+}
+
+/// An event emitted by [`depth_first_search`].
+///
+/// There are no back edges since a DAG cannot contain cycles.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DfsEvent {
+    /// A vertex is visited for the first time.
+    Discover(usize),
+    /// An edge `(u, v)` that leads to the discovery of `v`.
+    TreeEdge(usize, usize),
+    /// An edge `(u, v)` to an already finished descendant `v` of `u`.
+    ForwardEdge(usize, usize),
+    /// An edge `(u, v)` to an already finished vertex `v` that is not a
+    /// descendant of `u` in the DFS tree.
+    CrossEdge(usize, usize),
+    /// All the descendants of a vertex have been finished.
+    Finish(usize),
+}
+
+/// Performs a depth-first-search starting at each of `starts` in turn and
+/// calls `visitor` for each [`DfsEvent`] along the way.  Vertices already
+/// visited from an earlier start are not visited again.
+///
+/// This allows building several analyses on top of a single traversal pass.
+/// To traverse the whole DAG, pass [`get_vertices_without_incoming_edges`] as
+/// `starts`.
+pub fn depth_first_search<I, F>(dag: &DirectedAcyclicGraph, starts: I, mut visitor: F)
+where
+    I: IntoIterator<Item = usize>,
+    F: FnMut(DfsEvent),
+{
+    let mut discovery_time: Vec<usize> = vec![usize::MAX; dag.get_vertex_count()];
+    let mut finished = FixedBitSet::with_capacity(dag.get_vertex_count());
+    let mut time = 0;
+
+    for start in starts {
+        if discovery_time[start] != usize::MAX {
+            continue;
+        }
+        discovery_time[start] = time;
+        time += 1;
+        visitor(DfsEvent::Discover(start));
+        let mut stack = vec![(start, dag.iter_children(start))];
+        while let Some((u, children)) = stack.last_mut() {
+            let u = *u;
+            match children.next() {
+                Some(v) if discovery_time[v] == usize::MAX => {
+                    visitor(DfsEvent::TreeEdge(u, v));
+                    discovery_time[v] = time;
+                    time += 1;
+                    visitor(DfsEvent::Discover(v));
+                    stack.push((v, dag.iter_children(v)));
+                }
+                Some(v) => {
+                    debug_assert!(finished[v]);
+                    if discovery_time[u] < discovery_time[v] {
+                        visitor(DfsEvent::ForwardEdge(u, v));
+                    } else {
+                        visitor(DfsEvent::CrossEdge(u, v));
+                    }
+                }
+                None => {
+                    stack.pop();
+                    finished.insert(u);
+                    visitor(DfsEvent::Finish(u));
+                }
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashSet;
@@ -293,4 +768,277 @@ mod tests {
         quickcheck::QuickCheck::new()
             .quickcheck(prop_traversals_equal_modulo_order as fn(DirectedAcyclicGraph));
     }
+
+    #[test]
+    fn depth_first_search_classifies_edges() {
+        let dag = DirectedAcyclicGraph::from_edges_iter(
+            4,
+            vec![(0, 2), (0, 3), (1, 3), (2, 3)].into_iter(),
+        );
+        let mut events: Vec<DfsEvent> = Vec::new();
+        depth_first_search(&dag, vec![0, 1], |event| events.push(event));
+        assert_eq!(
+            events,
+            vec![
+                DfsEvent::Discover(0),
+                DfsEvent::TreeEdge(0, 2),
+                DfsEvent::Discover(2),
+                DfsEvent::TreeEdge(2, 3),
+                DfsEvent::Discover(3),
+                DfsEvent::Finish(3),
+                DfsEvent::Finish(2),
+                DfsEvent::ForwardEdge(0, 3),
+                DfsEvent::Finish(0),
+                DfsEvent::Discover(1),
+                DfsEvent::CrossEdge(1, 3),
+                DfsEvent::Finish(1),
+            ]
+        );
+    }
+
+    fn prop_depth_first_search_visits_every_edge_once(dag: DirectedAcyclicGraph) -> bool {
+        let mut edges: Vec<(usize, usize)> = Vec::new();
+        let mut discovered: Vec<usize> = Vec::new();
+        let mut finished: Vec<usize> = Vec::new();
+        depth_first_search(
+            &dag,
+            get_vertices_without_incoming_edges(&dag),
+            |event| match event {
+                DfsEvent::Discover(u) => discovered.push(u),
+                DfsEvent::Finish(u) => finished.push(u),
+                DfsEvent::TreeEdge(u, v)
+                | DfsEvent::ForwardEdge(u, v)
+                | DfsEvent::CrossEdge(u, v) => edges.push((u, v)),
+            },
+        );
+        edges.sort();
+        discovered.sort();
+        finished.sort();
+        let all_vertices: Vec<usize> = (0..dag.get_vertex_count()).collect();
+        edges == dag.iter_edges().collect::<Vec<(usize, usize)>>()
+            && discovered == all_vertices
+            && finished == all_vertices
+    }
+
+    #[test]
+    fn depth_first_search_visits_every_edge_once() {
+        quickcheck::QuickCheck::new().quickcheck(
+            prop_depth_first_search_visits_every_edge_once as fn(DirectedAcyclicGraph) -> bool,
+        );
+    }
+
+    #[test]
+    fn descendants_bfs_within_depth() {
+        let dag = DirectedAcyclicGraph::from_edges_iter(
+            5,
+            vec![(0, 1), (1, 2), (2, 3), (0, 3), (3, 4)].into_iter(),
+        );
+        assert_eq!(
+            iter_descendants_bfs_within(&dag, 0, 0).collect::<Vec<usize>>(),
+            vec![0]
+        );
+        assert_eq!(
+            iter_descendants_bfs_within(&dag, 0, 1).collect::<Vec<usize>>(),
+            vec![0, 1, 3]
+        );
+        assert_eq!(
+            iter_descendants_bfs_within(&dag, 0, 2).collect::<Vec<usize>>(),
+            vec![0, 1, 3, 2, 4]
+        );
+        assert_eq!(
+            iter_descendants_bfs_within(&dag, 2, 1).collect::<Vec<usize>>(),
+            vec![2, 3]
+        );
+    }
+
+    #[test]
+    fn descendants_dfs_within_depth() {
+        // 4 is at depth 2 through 1 but at depth 3 through 2, which comes
+        // first.
+        let dag = DirectedAcyclicGraph::from_edges_iter(
+            5,
+            vec![(0, 1), (1, 4), (0, 2), (2, 3), (3, 4)].into_iter(),
+        );
+        assert_eq!(
+            iter_descendants_dfs_within(&dag, 0, 0).collect::<Vec<usize>>(),
+            vec![0]
+        );
+        assert_eq!(
+            iter_descendants_dfs_within(&dag, 0, 1).collect::<Vec<usize>>(),
+            vec![0, 2, 1]
+        );
+        assert_eq!(
+            iter_descendants_dfs_within(&dag, 0, 2).collect::<Vec<usize>>(),
+            vec![0, 2, 3, 4, 1]
+        );
+        assert_eq!(
+            iter_descendants_dfs_post_order_within(&dag, 0, 1).collect::<Vec<usize>>(),
+            vec![2, 1, 0]
+        );
+        assert_eq!(
+            iter_descendants_dfs_post_order_within(&dag, 0, 2).collect::<Vec<usize>>(),
+            vec![4, 3, 2, 1, 0]
+        );
+    }
+
+    fn prop_kahn_order_is_topological(dag: DirectedAcyclicGraph) -> bool {
+        let order: Vec<usize> = iter_vertices_kahn(&dag).collect();
+        let mut position: Vec<usize> = vec![usize::MAX; dag.get_vertex_count()];
+        for (i, u) in order.iter().enumerate() {
+            position[*u] = i;
+        }
+        order.len() == dag.get_vertex_count()
+            && dag.iter_edges().all(|(u, v)| position[u] < position[v])
+    }
+
+    #[test]
+    fn kahn_order_is_topological() {
+        quickcheck::QuickCheck::new()
+            .quickcheck(prop_kahn_order_is_topological as fn(DirectedAcyclicGraph) -> bool);
+    }
+
+    fn prop_reverse_topological_order_is_reverse_topological(dag: DirectedAcyclicGraph) -> bool {
+        let order: Vec<usize> = iter_vertices_reverse_topological(&dag).collect();
+        let mut position: Vec<usize> = vec![usize::MAX; dag.get_vertex_count()];
+        for (i, u) in order.iter().enumerate() {
+            position[*u] = i;
+        }
+        order.len() == dag.get_vertex_count()
+            && dag.iter_edges().all(|(u, v)| position[v] < position[u])
+    }
+
+    #[test]
+    fn reverse_topological_order_is_reverse_topological() {
+        quickcheck::QuickCheck::new().quickcheck(
+            prop_reverse_topological_order_is_reverse_topological
+                as fn(DirectedAcyclicGraph) -> bool,
+        );
+    }
+
+    #[test]
+    fn random_topological_order_is_uniform() {
+        use rand::{prelude::StdRng, SeedableRng};
+
+        // Topological orders: [0, 1, 2], [0, 2, 1] and [2, 0, 1].
+        let dag = DirectedAcyclicGraph::from_edges_iter(3, vec![(0, 1)].into_iter());
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut counts: HashMap<Vec<usize>, usize> = HashMap::new();
+        for _ in 0..3000 {
+            *counts
+                .entry(get_random_topological_order(&dag, &mut rng))
+                .or_default() += 1;
+        }
+        assert_eq!(counts.len(), 3);
+        for (order, count) in counts {
+            assert!((900..1100).contains(&count), "{:?}: {}", order, count);
+        }
+    }
+
+    #[test]
+    fn random_topological_order_is_random_when_counts_overflow_f64() {
+        use rand::{prelude::StdRng, SeedableRng};
+
+        // Two parallel chains have C(1200, 600) > 10^300 topological orders.
+        let n = 600;
+        let mut dag = DirectedAcyclicGraph::empty(2 * n);
+        for u in 0..n - 1 {
+            dag.set_edge(u, u + 1, true);
+            dag.set_edge(n + u, n + u + 1, true);
+        }
+        let order = get_random_topological_order(&dag, &mut StdRng::seed_from_u64(42));
+        let mut position = vec![0; dag.get_vertex_count()];
+        for (i, u) in order.iter().enumerate() {
+            position[*u] = i;
+        }
+        assert!(dag.iter_edges().all(|(u, v)| position[u] < position[v]));
+        // In a uniformly random interleaving, about half of the first chain
+        // precedes the middle of the order.
+        let first_chain_in_first_half = order[..n].iter().filter(|u| **u < n).count();
+        assert!(
+            (200..400).contains(&first_chain_in_first_half),
+            "{}",
+            first_chain_in_first_half
+        );
+    }
+
+    fn prop_lexicographical_topological_order_equals_min_heap_kahn(
+        dag: DirectedAcyclicGraph,
+    ) -> bool {
+        use std::cmp::Reverse;
+        use std::collections::BinaryHeap;
+
+        let mut incoming_edges_count = get_incoming_edges_count(&dag);
+        let mut ready: BinaryHeap<Reverse<usize>> = get_vertices_without_incoming_edges(&dag)
+            .into_iter()
+            .map(Reverse)
+            .collect();
+        let mut expected: Vec<usize> = Vec::new();
+        while let Some(Reverse(u)) = ready.pop() {
+            expected.push(u);
+            for v in dag.iter_children(u) {
+                incoming_edges_count[v] -= 1;
+                if incoming_edges_count[v] == 0 {
+                    ready.push(Reverse(v));
+                }
+            }
+        }
+        iter_vertices_lexicographical_topological(&dag).collect::<Vec<usize>>() == expected
+    }
+
+    #[test]
+    fn lexicographical_topological_order_equals_min_heap_kahn() {
+        quickcheck::QuickCheck::new().quickcheck(
+            prop_lexicographical_topological_order_equals_min_heap_kahn
+                as fn(DirectedAcyclicGraph) -> bool,
+        );
+    }
+
+    #[test]
+    fn topological_order_closest_to_preferred_one() {
+        //  0 -> 2    1 -> 3
+        let dag = crate::dag!(4; 0 => 2, 1 => 3);
+        assert_eq!(
+            get_topological_order_closest_to(&dag, &[1, 3, 0, 2]),
+            vec![1, 3, 0, 2]
+        );
+        // 2 can't precede 0.
+        assert_eq!(
+            get_topological_order_closest_to(&dag, &[2, 1, 0, 3]),
+            vec![1, 0, 2, 3]
+        );
+    }
+
+    fn prop_closest_topological_order_is_topological(dag: DirectedAcyclicGraph) -> bool {
+        let preferred: Vec<usize> = (0..dag.get_vertex_count()).rev().collect();
+        let order = get_topological_order_closest_to(&dag, &preferred);
+        let mut position: Vec<usize> = vec![usize::MAX; dag.get_vertex_count()];
+        for (i, u) in order.iter().enumerate() {
+            position[*u] = i;
+        }
+        order.len() == dag.get_vertex_count()
+            && dag.iter_edges().all(|(u, v)| position[u] < position[v])
+    }
+
+    #[test]
+    fn closest_topological_order_is_topological() {
+        quickcheck::QuickCheck::new().quickcheck(
+            prop_closest_topological_order_is_topological as fn(DirectedAcyclicGraph) -> bool,
+        );
+    }
+
+    fn prop_topological_order_by_priority(dag: DirectedAcyclicGraph) -> bool {
+        // Higher priority first, with priorities colliding to exercise the
+        // tie-breaking.
+        let priority = |u: usize| (u * 7) % 5;
+        let order = get_topological_order_by(&dag, |a, b| priority(*b).cmp(&priority(*a)));
+        let mut preferred: Vec<usize> = (0..dag.get_vertex_count()).collect();
+        preferred.sort_by_key(|u| (std::cmp::Reverse(priority(*u)), *u));
+        order == get_topological_order_closest_to(&dag, &preferred)
+    }
+
+    #[test]
+    fn topological_order_by_priority() {
+        quickcheck::QuickCheck::new()
+            .quickcheck(prop_topological_order_by_priority as fn(DirectedAcyclicGraph) -> bool);
+    }
 }
@@ -0,0 +1,151 @@
+//! The underlying undirected graph of a DAG, for analyses that ignore edge
+//! directions, e.g. connectivity.  See [`DirectedAcyclicGraph::as_undirected`].
+
+use fixedbitset::FixedBitSet;
+
+use crate::{DirectedAcyclicGraph, StrictlyLowerTriangularLogicalMatrix};
+
+/// A view of a DAG with every edge `(u, v)` treated as an undirected edge
+/// `{u, v}`.  Neighbours are both the children and the parents.
+///
+/// The parents of every vertex are transposed upfront, so that they can be
+/// scanned a word at a time just like the children.
+#[derive(Clone, Debug)]
+pub struct UndirectedView<'a> {
+    dag: &'a DirectedAcyclicGraph,
+    parents: StrictlyLowerTriangularLogicalMatrix,
+}
+
+impl<'a> UndirectedView<'a> {
+    pub fn new(dag: &'a DirectedAcyclicGraph) -> Self {
+        let parents = dag.get_adjacency_matrix().transpose();
+        Self { dag, parents }
+    }
+
+    pub fn get_vertex_count(&self) -> usize {
+        self.dag.get_vertex_count()
+    }
+
+    /// Whether there's an edge between `u` and `v` in either direction.
+    pub fn get_edge(&self, u: usize, v: usize) -> bool {
+        u != v && self.dag.get_edge(std::cmp::min(u, v), std::cmp::max(u, v))
+    }
+
+    /// Iterates over the neighbours of `u` in increasing order.
+    pub fn iter_neighbours(&self, u: usize) -> impl Iterator<Item = usize> + '_ {
+        self.parents
+            .iter_ones_at_row(u)
+            .chain(self.dag.iter_children(u))
+    }
+
+    pub fn get_degree(&self, u: usize) -> usize {
+        self.iter_neighbours(u).count()
+    }
+
+    /// The connected components, i.e. the weakly connected components of the
+    /// DAG.  Both the components and the vertices within them are sorted.
+    pub fn get_connected_components(&self) -> Vec<Vec<usize>> {
+        let vertex_count = self.get_vertex_count();
+        let mut visited = FixedBitSet::with_capacity(vertex_count);
+        let mut components: Vec<Vec<usize>> = Vec::new();
+        for root in 0..vertex_count {
+            if visited.put(root) {
+                continue;
+            }
+            let mut component: Vec<usize> = Vec::new();
+            let mut to_visit: Vec<usize> = vec![root];
+            while let Some(u) = to_visit.pop() {
+                component.push(u);
+                for v in self.iter_neighbours(u) {
+                    if !visited.put(v) {
+                        to_visit.push(v);
+                    }
+                }
+            }
+            component.sort_unstable();
+            components.push(component);
+        }
+        components
+    }
+
+    /// The edges whose removal disconnects their component, sorted.
+    pub fn get_bridges(&self) -> Vec<(usize, usize)> {
+        let vertex_count = self.get_vertex_count();
+        let neighbours: Vec<Vec<usize>> = (0..vertex_count)
+            .map(|u| self.iter_neighbours(u).collect())
+            .collect();
+        // Discovery times and the lowest discovery time reachable by a back
+        // edge from the DFS subtree.
+        let mut discovered: Vec<usize> = vec![usize::MAX; vertex_count];
+        let mut low: Vec<usize> = vec![0; vertex_count];
+        let mut time = 0;
+        let mut bridges: Vec<(usize, usize)> = Vec::new();
+        // (vertex, DFS parent, index of the next neighbour to look at)
+        let mut stack: Vec<(usize, usize, usize)> = Vec::new();
+        for root in 0..vertex_count {
+            if discovered[root] != usize::MAX {
+                continue;
+            }
+            discovered[root] = time;
+            low[root] = time;
+            time += 1;
+            stack.push((root, usize::MAX, 0));
+            while let Some((u, parent, next)) = stack.last_mut() {
+                let (u, parent) = (*u, *parent);
+                if let Some(&v) = neighbours[u].get(*next) {
+                    *next += 1;
+                    if discovered[v] == usize::MAX {
+                        discovered[v] = time;
+                        low[v] = time;
+                        time += 1;
+                        stack.push((v, u, 0));
+                    } else if v != parent {
+                        low[u] = std::cmp::min(low[u], discovered[v]);
+                    }
+                    continue;
+                }
+                stack.pop();
+                if parent != usize::MAX {
+                    low[parent] = std::cmp::min(low[parent], low[u]);
+                    if low[u] > discovered[parent] {
+                        bridges.push((std::cmp::min(u, parent), std::cmp::max(u, parent)));
+                    }
+                }
+            }
+        }
+        bridges.sort_unstable();
+        bridges
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn undirected_view() {
+        //  0 -> 1 -> 2, 0 -> 2, 2 -> 3, 4
+        let dag = crate::dag!(5; 0 => 1, 1 => 2, 0 => 2, 2 => 3);
+        let view = dag.as_undirected();
+        assert!(view.get_edge(2, 1));
+        assert_eq!(view.iter_neighbours(2).collect::<Vec<_>>(), vec![0, 1, 3]);
+        assert_eq!(view.get_degree(4), 0);
+        assert_eq!(
+            view.get_connected_components(),
+            vec![vec![0, 1, 2, 3], vec![4]]
+        );
+        assert_eq!(view.get_bridges(), vec![(2, 3)]);
+    }
+
+    fn prop_components_agree_with_weak_connectivity(dag: DirectedAcyclicGraph) -> bool {
+        let components = dag.as_undirected().get_connected_components();
+        (components.len() <= 1) == crate::algorithm::is_weakly_connected(&dag)
+    }
+
+    #[test]
+    fn components_agree_with_weak_connectivity() {
+        quickcheck::QuickCheck::new().quickcheck(
+            prop_components_agree_with_weak_connectivity as fn(DirectedAcyclicGraph) -> bool,
+        );
+    }
+}
@@ -0,0 +1,114 @@
+//! JavaScript bindings for WebAssembly.  Build with e.g.:
+//!
+//! ```sh
+//! cargo rustc --release --target wasm32-unknown-unknown --features wasm --crate-type cdylib
+//! wasm-bindgen --target web --out-dir pkg target/wasm32-unknown-unknown/release/dograph.wasm
+//! ```
+//!
+//! `wasm-pack` works too, but needs `crate-type = ["cdylib", "rlib"]` added to
+//! the `[lib]` section of `Cargo.toml` first.
+//!
+//! ```js
+//! import init, { DirectedAcyclicGraph } from "./pkg/dograph.js";
+//! await init();
+//! const dag = DirectedAcyclicGraph.random(10, 0.3, 42n);
+//! console.log(dag.toDot());
+//! ```
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rand_distr::Bernoulli;
+use wasm_bindgen::prelude::*;
+
+use crate::DirectedAcyclicGraph;
+
+/// A JavaScript wrapper around [`DirectedAcyclicGraph`].  Invalid edges throw
+/// instead of panicking.
+#[wasm_bindgen(js_name = DirectedAcyclicGraph)]
+pub struct WasmDirectedAcyclicGraph(DirectedAcyclicGraph);
+
+#[wasm_bindgen(js_class = DirectedAcyclicGraph)]
+impl WasmDirectedAcyclicGraph {
+    /// An empty DAG.
+    #[wasm_bindgen(constructor)]
+    pub fn new(vertex_count: usize) -> Self {
+        Self(DirectedAcyclicGraph::empty(vertex_count))
+    }
+
+    /// `edges` is a flat array of vertex pairs: `[u0, v0, u1, v1, ...]`.
+    #[wasm_bindgen(js_name = fromEdges)]
+    // `usize::is_multiple_of` needs Rust 1.87.
+    #[allow(clippy::manual_is_multiple_of)]
+    pub fn from_edges(vertex_count: usize, edges: Vec<usize>) -> Result<Self, JsError> {
+        if edges.len() % 2 != 0 {
+            return Err(JsError::new("edges must consist of vertex pairs"));
+        }
+        let mut dag = DirectedAcyclicGraph::empty(vertex_count);
+        for edge in edges.chunks(2) {
+            dag.try_set_edge(edge[0], edge[1], true)?;
+        }
+        Ok(Self(dag))
+    }
+
+    /// A random DAG where every admissible edge exists with probability
+    /// `edge_probability`.
+    pub fn random(vertex_count: usize, edge_probability: f64, seed: u64) -> Result<Self, JsError> {
+        let distribution = Bernoulli::new(edge_probability)?;
+        let mut rng = StdRng::seed_from_u64(seed);
+        Ok(Self(DirectedAcyclicGraph::random(
+            vertex_count,
+            &mut rng,
+            distribution,
+        )))
+    }
+
+    #[wasm_bindgen(getter, js_name = vertexCount)]
+    pub fn vertex_count(&self) -> usize {
+        self.0.get_vertex_count()
+    }
+
+    #[wasm_bindgen(js_name = getEdge)]
+    pub fn get_edge(&self, u: usize, v: usize) -> Result<bool, JsError> {
+        Ok(self.0.try_get_edge(u, v)?)
+    }
+
+    #[wasm_bindgen(js_name = setEdge)]
+    pub fn set_edge(&mut self, u: usize, v: usize, exists: bool) -> Result<(), JsError> {
+        Ok(self.0.try_set_edge(u, v, exists)?)
+    }
+
+    /// The edges as a flat array of vertex pairs, see [`Self::from_edges`].
+    pub fn edges(&self) -> Vec<usize> {
+        self.0.iter_edges().flat_map(|(u, v)| [u, v]).collect()
+    }
+
+    /// See [`crate::to_dot`].
+    #[wasm_bindgen(js_name = toDot)]
+    pub fn to_dot(&self) -> String {
+        let mut output = Vec::new();
+        crate::to_dot(&self.0, &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wraps_dag() {
+        let dag = WasmDirectedAcyclicGraph::from_edges(3, vec![0, 1, 1, 2]).unwrap();
+        assert_eq!(dag.vertex_count(), 3);
+        assert!(dag.get_edge(1, 2).unwrap());
+        assert_eq!(dag.edges(), vec![0, 1, 1, 2]);
+        assert!(dag.to_dot().contains("_0 -> _1;"));
+        assert_eq!(
+            WasmDirectedAcyclicGraph::random(10, 0.5, 1)
+                .unwrap()
+                .edges(),
+            WasmDirectedAcyclicGraph::random(10, 0.5, 1)
+                .unwrap()
+                .edges()
+        );
+    }
+}
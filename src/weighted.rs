@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+
+use crate::DirectedAcyclicGraph;
+
+/// A [`DirectedAcyclicGraph`] with a weight of type `W` attached to every
+/// vertex and to every edge, e.g. task durations and transfer costs.
+///
+/// Read access to the structure is via [`std::ops::Deref`], so all of the
+/// [`DirectedAcyclicGraph`] methods and the functions in [`crate::algorithm`]
+/// and [`crate::traversal`] work as usual.  Edges are only added and removed
+/// through the methods below, which keep the weights in sync.  Indexing with
+/// `(u, v)` returns the weight of an edge, which makes it easy to pass the
+/// weights to functions taking them as closures:
+///
+/// ```
+/// use dograph::paths::get_heaviest_path_between;
+/// use dograph::weighted::WeightedDag;
+/// let mut dag: WeightedDag<u32> = WeightedDag::empty(3);
+/// dag.insert_edge(0, 1, 5);
+/// dag.insert_edge(1, 2, 2);
+/// dag.insert_edge(0, 2, 1);
+/// assert_eq!(
+///     get_heaviest_path_between(&dag, 0, 2, |u, v| dag[(u, v)]),
+///     Some((vec![0, 1, 2], 7))
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WeightedDag<W> {
+    dag: DirectedAcyclicGraph,
+    vertex_weights: Vec<W>,
+    edge_weights: HashMap<(usize, usize), W>,
+}
+
+impl<W: Default> WeightedDag<W> {
+    /// A DAG without edges where every vertex weighs `W::default()`.
+    pub fn empty(vertex_count: usize) -> Self {
+        Self {
+            dag: DirectedAcyclicGraph::empty(vertex_count),
+            vertex_weights: (0..vertex_count).map(|_| W::default()).collect(),
+            edge_weights: HashMap::new(),
+        }
+    }
+
+    /// Attaches `edge_weight(u, v)` to every edge `(u, v)` of `dag`.  Every
+    /// vertex weighs `W::default()`.
+    pub fn from_dag<F: FnMut(usize, usize) -> W>(
+        dag: DirectedAcyclicGraph,
+        mut edge_weight: F,
+    ) -> Self {
+        let vertex_weights = (0..dag.get_vertex_count()).map(|_| W::default()).collect();
+        let edge_weights = dag
+            .iter_edges()
+            .map(|(u, v)| ((u, v), edge_weight(u, v)))
+            .collect();
+        Self {
+            dag,
+            vertex_weights,
+            edge_weights,
+        }
+    }
+}
+
+impl<W> WeightedDag<W> {
+    pub fn get_dag(&self) -> &DirectedAcyclicGraph {
+        &self.dag
+    }
+
+    /// Drops the weights.
+    pub fn into_dag(self) -> DirectedAcyclicGraph {
+        self.dag
+    }
+
+    /// A mapping: vertex -> weight.
+    pub fn get_vertex_weights(&self) -> &[W] {
+        &self.vertex_weights
+    }
+
+    pub fn get_vertex_weight(&self, u: usize) -> &W {
+        &self.vertex_weights[u]
+    }
+
+    /// Replaces all the vertex weights at once.  `vertex_weights` is a mapping:
+    /// vertex -> weight.
+    ///
+    /// Requires a weight for every vertex.  Panics otherwise.
+    pub fn with_vertex_weights(mut self, vertex_weights: Vec<W>) -> Self {
+        assert_eq!(vertex_weights.len(), self.dag.get_vertex_count());
+        self.vertex_weights = vertex_weights;
+        self
+    }
+
+    /// Returns the previous weight of `u`.
+    pub fn set_vertex_weight(&mut self, u: usize, weight: W) -> W {
+        std::mem::replace(&mut self.vertex_weights[u], weight)
+    }
+
+    /// `None` if there's no edge `(u, v)`.
+    pub fn get_edge_weight(&self, u: usize, v: usize) -> Option<&W> {
+        self.edge_weights.get(&(u, v))
+    }
+
+    /// `None` if there's no edge `(u, v)`.
+    pub fn get_edge_weight_mut(&mut self, u: usize, v: usize) -> Option<&mut W> {
+        self.edge_weights.get_mut(&(u, v))
+    }
+
+    /// Adds the edge `(u, v)` with `weight`, or replaces its weight if it
+    /// exists already.  Returns the previous weight.
+    ///
+    /// Requires `u < v`.  Panics otherwise.
+    pub fn insert_edge(&mut self, u: usize, v: usize, weight: W) -> Option<W> {
+        self.dag.set_edge(u, v, true);
+        self.edge_weights.insert((u, v), weight)
+    }
+
+    /// Removes the edge `(u, v)`.  Returns its weight if it was present.
+    ///
+    /// Requires `u < v`.  Panics otherwise.
+    pub fn remove_edge(&mut self, u: usize, v: usize) -> Option<W> {
+        self.dag.set_edge(u, v, false);
+        self.edge_weights.remove(&(u, v))
+    }
+
+    /// Iterates over the edges, along with their weights, in an order that
+    /// favors CPU cache locality.
+    pub fn iter_weighted_edges(&self) -> impl Iterator<Item = (usize, usize, &W)> + '_ {
+        self.dag
+            .iter_edges()
+            .map(move |(u, v)| (u, v, &self.edge_weights[&(u, v)]))
+    }
+
+    /// Iterates over vertices `v` such that there's an edge `(u, v)` in the
+    /// DAG, along with the weight of that edge.
+    pub fn iter_weighted_children(&self, u: usize) -> impl Iterator<Item = (usize, &W)> + '_ {
+        self.dag
+            .iter_children(u)
+            .map(move |v| (v, &self.edge_weights[&(u, v)]))
+    }
+
+    /// Gives access to the edge `(u, v)` for in-place inspection and
+    /// modification, similarly to [`HashMap::entry`].
+    ///
+    /// Requires `u < v`.  Panics otherwise.
+    ///
+    /// ```
+    /// use dograph::weighted::WeightedDag;
+    /// let mut dag: WeightedDag<u32> = WeightedDag::empty(3);
+    /// *dag.edge_entry(0, 2).or_insert_with(|| 1) += 10;
+    /// assert_eq!(*dag.edge_entry(0, 2).or_insert_with(|| unreachable!()), 11);
+    /// assert!(!dag.edge_entry(1, 2).exists());
+    /// ```
+    pub fn edge_entry(&mut self, u: usize, v: usize) -> EdgeEntry<'_, W> {
+        assert!(u < self.dag.get_vertex_count());
+        assert!(v < self.dag.get_vertex_count());
+        assert!(u < v);
+        EdgeEntry {
+            weighted: self,
+            u,
+            v,
+        }
+    }
+}
+
+impl<W> std::ops::Deref for WeightedDag<W> {
+    type Target = DirectedAcyclicGraph;
+
+    fn deref(&self) -> &Self::Target {
+        &self.dag
+    }
+}
+
+/// The weight of the edge `(u, v)`.  Panics if there's no such edge.
+impl<W> std::ops::Index<(usize, usize)> for WeightedDag<W> {
+    type Output = W;
+
+    fn index(&self, (u, v): (usize, usize)) -> &Self::Output {
+        &self.edge_weights[&(u, v)]
+    }
+}
+
+/// See [`WeightedDag::edge_entry`].
+pub struct EdgeEntry<'a, W> {
+    weighted: &'a mut WeightedDag<W>,
+    u: usize,
+    v: usize,
+}
+
+impl<'a, W> EdgeEntry<'a, W> {
+    pub fn exists(&self) -> bool {
+        self.weighted.dag.get_edge(self.u, self.v)
+    }
+
+    /// Adds the edge with `weight` if it's absent.  Returns its weight.
+    pub fn or_insert(self, weight: W) -> &'a mut W {
+        self.or_insert_with(|| weight)
+    }
+
+    /// Adds the edge weighing `f()` if it's absent.  Returns its weight.
+    pub fn or_insert_with<F: FnOnce() -> W>(self, f: F) -> &'a mut W {
+        let WeightedDag {
+            dag, edge_weights, ..
+        } = self.weighted;
+        edge_weights.entry((self.u, self.v)).or_insert_with(|| {
+            dag.set_edge(self.u, self.v, true);
+            f()
+        })
+    }
+
+    /// Adds the edge or replaces its weight.  Returns the previous weight.
+    pub fn insert(self, weight: W) -> Option<W> {
+        self.weighted.insert_edge(self.u, self.v, weight)
+    }
+
+    /// Removes the edge.  Returns its weight if it was present.
+    pub fn remove(self) -> Option<W> {
+        self.weighted.remove_edge(self.u, self.v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_weights_in_sync_with_edges() {
+        let mut dag = WeightedDag::from_dag(crate::dag!(4; 0 => 1, 1 => 3), |u, v| u + v);
+        assert_eq!(dag[(1, 3)], 4);
+        assert_eq!(dag.insert_edge(0, 1, 7), Some(1));
+        assert_eq!(dag.insert_edge(2, 3, 5), None);
+        assert_eq!(dag.remove_edge(1, 3), Some(4));
+        assert_eq!(dag.remove_edge(1, 3), None);
+        assert_eq!(dag.get_edge_weight(1, 3), None);
+        *dag.get_edge_weight_mut(2, 3).unwrap() += 1;
+        assert_eq!(dag.edge_entry(0, 2).or_insert(3), &mut 3);
+        assert!(dag.edge_entry(0, 2).exists());
+        assert_eq!(dag.edge_entry(0, 2).insert(4), Some(3));
+        assert_eq!(dag.edge_entry(0, 2).remove(), Some(4));
+        assert_eq!(
+            dag.iter_weighted_edges().collect::<Vec<_>>(),
+            vec![(0, 1, &7), (2, 3, &6)]
+        );
+        assert_eq!(
+            dag.iter_weighted_children(2).collect::<Vec<_>>(),
+            vec![(3, &6)]
+        );
+        assert_eq!(dag.set_vertex_weight(3, 9), 0);
+        assert_eq!(dag.get_vertex_weights(), &[0, 0, 0, 9]);
+        assert_eq!(dag.into_dag(), crate::dag!(4; 0 => 1, 2 => 3));
+    }
+}